@@ -0,0 +1,26 @@
+//! Hosts a device, which can be used remotely via the `Network` device.
+//!
+//! Usage: `custos-server [address] [device]`
+//!
+//! The address defaults to `127.0.0.1:11001`, the device to `cpu`.
+//! With the `opencl` feature, `opencl` can be chosen as well.
+
+use std::net::TcpListener;
+
+use custos::network::serve;
+
+fn main() -> custos::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:11001".to_string());
+    let device = args.next().unwrap_or_else(|| "cpu".to_string());
+
+    let listener = TcpListener::bind(&addr)?;
+    println!("custos-server: serving {device} devices on {addr}");
+
+    match device.as_str() {
+        "cpu" => serve::<custos::CPU>(listener),
+        #[cfg(feature = "opencl")]
+        "opencl" => serve::<custos::OpenCL>(listener),
+        _ => Err(format!("Unknown device: {device}").into()),
+    }
+}
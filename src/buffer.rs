@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::{ffi::c_void, fmt::Debug};
 
 #[cfg(feature = "cpu")]
@@ -28,6 +29,7 @@ pub use impl_from_const::*;
 mod impl_from;
 mod impl_from_const;
 mod num;
+mod rect;
 
 /// The underlying non-growable array structure. A `Buffer` may be encapsulated in other structs.
 /// By default, the `Buffer` is a f32 CPU Buffer.
@@ -144,7 +146,6 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
     /// assert_eq!(buf.read_to_vec(), vec![1, 2, 3, 4]);
     /// ```
     #[inline]
-    #[cfg(not(feature = "no-std"))]
     pub fn read_to_vec(&self) -> Vec<T>
     where
         D: Read<T, D, S>,
@@ -219,7 +220,6 @@ impl<'a, T, D: Device, S: Shape> Buffer<'a, T, D, S> {
         self.clone()
     }
 
-    #[cfg(not(feature = "no-std"))]
     pub fn id(&self) -> crate::Ident {
         crate::Ident {
             idx: self.node.ident_idx as usize,
@@ -330,6 +330,7 @@ impl<'a, T> Buffer<'a, T> {
                 ptr,
                 len,
                 flag: AllocFlag::Wrapper,
+                align: core::mem::align_of::<T>(),
             },
             device: None,
             node: Default::default(),
@@ -349,6 +350,7 @@ impl<'a, T> Buffer<'a, T> {
                 ptr,
                 len,
                 flag: AllocFlag::Wrapper,
+                align: core::mem::align_of::<T>(),
             },
             device: Some(device),
             node: Default::default(),
@@ -366,6 +368,43 @@ impl<'a, T> Buffer<'a, T, crate::OpenCL> {
         );
         self.ptrs().1
     }
+
+    /// Returns the data of the buffer as a slice, if it was allocated in unified memory.
+    /// # Errors
+    /// [`DeviceError::NoUnifiedMemory`](crate::DeviceError::NoUnifiedMemory), if the device uses dedicated memory.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, OpenCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = OpenCL::new(0)?;
+    ///     let buf = Buffer::from((&device, [1, 2, 3]));
+    ///
+    ///     match buf.try_as_slice() {
+    ///         Ok(slice) => assert_eq!(slice, &[1, 2, 3]),
+    ///         Err(_) => assert!(!device.unified_mem()),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn try_as_slice(&self) -> crate::Result<&[T]> {
+        if self.ptr.host_ptr.is_null() {
+            return Err(crate::DeviceError::NoUnifiedMemory.into());
+        }
+        Ok(unsafe { core::slice::from_raw_parts(self.ptr.host_ptr, self.len()) })
+    }
+
+    /// Returns the data of the buffer as a mutable slice, if it was allocated in unified memory.
+    /// # Errors
+    /// [`DeviceError::NoUnifiedMemory`](crate::DeviceError::NoUnifiedMemory), if the device uses dedicated memory.
+    #[inline]
+    pub fn try_as_mut_slice(&mut self) -> crate::Result<&mut [T]> {
+        if self.ptr.host_ptr.is_null() {
+            return Err(crate::DeviceError::NoUnifiedMemory.into());
+        }
+        Ok(unsafe { core::slice::from_raw_parts_mut(self.ptr.host_ptr, self.len()) })
+    }
 }
 
 #[cfg(feature = "cuda")]
@@ -527,7 +566,6 @@ impl<T, D: MainMemory, S: Shape> core::ops::DerefMut for Buffer<'_, T, D, S> {
     }
 }
 
-#[cfg(not(feature = "no-std"))]
 impl<'a, T, D> Debug for Buffer<'a, T, D>
 where
     T: Debug + Default + Clone + 'a,
@@ -542,7 +580,7 @@ where
         writeln!(f, ",")?;
 
         if !self.ptrs().0.is_null() {
-            let slice = unsafe { std::slice::from_raw_parts(self.ptrs().0, self.len()) };
+            let slice = unsafe { core::slice::from_raw_parts(self.ptrs().0, self.len()) };
             writeln!(f, "CPU:    {slice:?}")?;
         }
 
@@ -633,15 +671,20 @@ mod tests {
     }
 
     #[cfg(feature = "opencl")]
-    #[cfg(unified_cl)]
     #[test]
-    fn test_deref_cl() -> crate::Result<()> {
-        use crate::OpenCL;
+    fn test_try_as_slice_cl() -> crate::Result<()> {
+        use crate::{DeviceError, ErrorKind, OpenCL};
 
         let device = OpenCL::new(0)?;
-        let buf = Buffer::from((&device, [1, 2, 3, 4]));
-        let slice = &*buf;
-        assert_eq!(slice, &[1, 2, 3, 4]);
+        let mut buf = Buffer::from((&device, [1, 2, 3, 4]));
+
+        if device.unified_mem() {
+            buf.try_as_mut_slice()?[0] = 5;
+            assert_eq!(buf.try_as_slice()?, &[5, 2, 3, 4]);
+        } else {
+            let err = buf.try_as_slice().unwrap_err();
+            assert_eq!(err.kind(), Some(&DeviceError::NoUnifiedMemory));
+        }
 
         Ok(())
     }
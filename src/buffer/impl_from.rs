@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::{shape::Shape, Alloc, Buffer, IsShapeIndep};
 
 impl<'a, T, D, const N: usize> From<(&'a D, [T; N])> for Buffer<'a, T, D>
@@ -47,7 +49,6 @@ where
     }
 }
 
-#[cfg(not(feature = "no-std"))]
 impl<'a, T, D, S: Shape> From<(&'a D, Vec<T>)> for Buffer<'a, T, D, S>
 where
     T: Clone,
@@ -63,7 +64,6 @@ where
     }
 }
 
-#[cfg(not(feature = "no-std"))]
 impl<'a, T, D, S: Shape> From<(&'a D, &Vec<T>)> for Buffer<'a, T, D, S>
 where
     T: Clone,
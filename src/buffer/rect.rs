@@ -0,0 +1,117 @@
+use crate::{Alloc, Buffer, CopyRect, Device, Dim2, Dim3, Rect};
+
+impl<'a, T, D: Device, const B: usize, const A: usize> Buffer<'a, T, D, Dim2<B, A>> {
+    /// Copies the `R`x`C` sub-matrix, which starts at `[row, col]`, into a new buffer.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{Buffer, Dim2, WithShape, CPU};
+    ///
+    /// let device = CPU::new();
+    /// let matrix = Buffer::<i32, _, Dim2<3, 3>>::with(&device, [
+    ///     [1, 2, 3],
+    ///     [4, 5, 6],
+    ///     [7, 8, 9],
+    /// ]);
+    ///
+    /// let sub = matrix.sub_matrix::<2, 2>([1, 0]);
+    /// assert_eq!(sub.read(), [4, 5, 7, 8]);
+    /// ```
+    pub fn sub_matrix<const R: usize, const C: usize>(
+        &self,
+        origin: [usize; 2],
+    ) -> Buffer<'a, T, D, Dim2<R, C>>
+    where
+        D: CopyRect<T, D, Dim2<B, A>> + Alloc<'a, T, Dim2<R, C>>,
+    {
+        let mut sub = Buffer::new(self.device(), R * C);
+        self.device().copy_rect(
+            self,
+            Rect::dim2::<B, A>(origin),
+            &mut sub,
+            Rect::packed(),
+            [C, R, 1],
+        );
+        sub
+    }
+
+    /// Overwrites the sub-matrix, which starts at `[row, col]`, with `sub`.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{Buffer, Dim2, WithShape, CPU};
+    ///
+    /// let device = CPU::new();
+    /// let mut matrix = Buffer::<i32, _, Dim2<3, 3>>::new(&device, 9);
+    /// let sub = Buffer::<i32, _, Dim2<2, 1>>::with(&device, [[1], [2]]);
+    ///
+    /// matrix.set_sub_matrix([1, 2], &sub);
+    /// assert_eq!(matrix.read(), [0, 0, 0, 0, 0, 1, 0, 0, 2]);
+    /// ```
+    pub fn set_sub_matrix<const R: usize, const C: usize>(
+        &mut self,
+        origin: [usize; 2],
+        sub: &Buffer<T, D, Dim2<R, C>>,
+    ) where
+        D: CopyRect<T, D, Dim2<R, C>>,
+    {
+        self.device().copy_rect(
+            sub,
+            Rect::packed(),
+            self,
+            Rect::dim2::<B, A>(origin),
+            [C, R, 1],
+        );
+    }
+}
+
+impl<'a, T, D: Device, const C: usize, const B: usize, const A: usize>
+    Buffer<'a, T, D, Dim3<C, B, A>>
+{
+    /// Copies the `M`x`R`x`N` block (`M` matrices with `R` rows and `N` columns), which starts at `[matrix, row, col]`, into a new buffer.
+    /// # Example
+    #[cfg_attr(feature = "cpu", doc = "```")]
+    #[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+    /// use custos::{Buffer, Dim3, CPU};
+    ///
+    /// let device = CPU::new();
+    /// let array = Buffer::from((&device, [1, 2, 3, 4, 5, 6, 7, 8])).to_dims::<Dim3<2, 2, 2>>();
+    ///
+    /// let block = array.sub_block::<2, 1, 1>([0, 1, 1]);
+    /// assert_eq!(block.read(), [4, 8]);
+    /// ```
+    pub fn sub_block<const M: usize, const R: usize, const N: usize>(
+        &self,
+        origin: [usize; 3],
+    ) -> Buffer<'a, T, D, Dim3<M, R, N>>
+    where
+        D: CopyRect<T, D, Dim3<C, B, A>> + Alloc<'a, T, Dim3<M, R, N>>,
+    {
+        let mut block = Buffer::new(self.device(), M * R * N);
+        self.device().copy_rect(
+            self,
+            Rect::dim3::<C, B, A>(origin),
+            &mut block,
+            Rect::packed(),
+            [N, R, M],
+        );
+        block
+    }
+
+    /// Overwrites the block, which starts at `[matrix, row, col]`, with `block`.
+    pub fn set_sub_block<const M: usize, const R: usize, const N: usize>(
+        &mut self,
+        origin: [usize; 3],
+        block: &Buffer<T, D, Dim3<M, R, N>>,
+    ) where
+        D: CopyRect<T, D, Dim3<M, R, N>>,
+    {
+        self.device().copy_rect(
+            block,
+            Rect::packed(),
+            self,
+            Rect::dim3::<C, B, A>(origin),
+            [N, R, M],
+        );
+    }
+}
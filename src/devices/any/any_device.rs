@@ -0,0 +1,238 @@
+use core::{
+    ops::{Range, RangeBounds},
+    str::FromStr,
+};
+
+use super::{AnyBackend, AnyPtr};
+use crate::{
+    any_dispatch, flag::AllocFlag, shape::Shape, Alloc, Buffer, CDatatype, ClearBuf, CloneBuf,
+    CopySlice, Device, DeviceError, IsShapeIndep, Read, WriteBuf,
+};
+
+#[cfg(feature = "cpu")]
+use crate::CPU;
+#[cfg(feature = "cuda")]
+use crate::{cuda::chosen_cu_idx, CUDA};
+#[cfg(feature = "opencl")]
+use crate::{opencl::chosen_cl_idx, OpenCL};
+#[cfg(feature = "wgpu")]
+use crate::{wgpu::chosen_wgpu_backends, WGPU};
+
+/// A device whose backend is selected at runtime, e.g. from a configuration file.
+/// All operations are dispatched to the wrapped backend device.
+///
+/// An [`AnyDevice`] can be parsed from a string (`"cpu"`, `"opencl"`, `"cuda"`, `"wgpu"`), optionally followed by a device index
+/// (`"opencl:1"`). Without an index, `CUSTOS_CL_DEVICE_IDX` or `CUSTOS_CU_DEVICE_IDX` is used.
+/// The index of `"wgpu"` selects an adapter of [`wgpu_adapters`](crate::wgpu::wgpu_adapters), otherwise the adapter is chosen like by `WGPU`'s `Device::new`.
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use custos::{AnyDevice, Buffer, ClearBuf};
+///
+/// fn main() -> custos::Result<()> {
+///     let backend = std::env::var("BACKEND").unwrap_or_else(|_| "cpu".into());
+///     let device: AnyDevice = backend.parse()?;
+///
+///     let mut buf = Buffer::from((&device, [1, 2, 3, 4]));
+///     assert_eq!(buf.read(), [1, 2, 3, 4]);
+///
+///     device.clear(&mut buf);
+///     assert_eq!(buf.read(), [0; 4]);
+///     Ok(())
+/// }
+/// ```
+// devices are rarely created, hence the size difference of the variants does not matter
+#[allow(clippy::large_enum_variant)]
+pub enum AnyDevice {
+    #[cfg(feature = "cpu")]
+    CPU(CPU),
+    #[cfg(feature = "opencl")]
+    OpenCL(OpenCL),
+    #[cfg(feature = "cuda")]
+    CUDA(CUDA),
+    #[cfg(feature = "wgpu")]
+    WGPU(WGPU),
+}
+
+impl AnyDevice {
+    /// Converts a buffer of the backend into a buffer of this device.
+    ///
+    /// `buf` should belong to the backend device of `self`, e.g. the result of an operation inside [`any_dispatch!`].
+    #[inline]
+    pub fn wrap<'a, T, D: AnyBackend>(&'a self, buf: Buffer<'a, T, D>) -> Buffer<'a, T, AnyDevice> {
+        let Buffer { ptr, node, .. } = buf;
+        Buffer {
+            ptr: D::into_any(ptr),
+            device: Some(self),
+            node,
+        }
+    }
+
+    /// Returns the name of the backend, as used by [`AnyDevice::from_str`].
+    pub fn backend_name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "cpu")]
+            AnyDevice::CPU(_) => "cpu",
+            #[cfg(feature = "opencl")]
+            AnyDevice::OpenCL(_) => "opencl",
+            #[cfg(feature = "cuda")]
+            AnyDevice::CUDA(_) => "cuda",
+            #[cfg(feature = "wgpu")]
+            AnyDevice::WGPU(_) => "wgpu",
+        }
+    }
+}
+
+impl FromStr for AnyDevice {
+    type Err = crate::Error;
+
+    fn from_str(backend: &str) -> crate::Result<AnyDevice> {
+        let backend = backend.trim().to_lowercase();
+
+        #[allow(unused_variables)]
+        let (name, idx) = match backend.split_once(':') {
+            Some((name, idx)) => (name, Some(idx.parse::<usize>()?)),
+            None => (backend.as_str(), None),
+        };
+
+        match name {
+            #[cfg(feature = "cpu")]
+            "cpu" if idx.is_none() => Ok(AnyDevice::CPU(CPU::new())),
+            #[cfg(feature = "opencl")]
+            "opencl" | "cl" => Ok(AnyDevice::OpenCL(OpenCL::new(
+                idx.unwrap_or_else(chosen_cl_idx),
+            )?)),
+            #[cfg(feature = "cuda")]
+            "cuda" | "cu" => Ok(AnyDevice::CUDA(CUDA::new(
+                idx.unwrap_or_else(chosen_cu_idx),
+            )?)),
+            #[cfg(feature = "wgpu")]
+            "wgpu" => Ok(AnyDevice::WGPU(match idx {
+                Some(idx) => WGPU::with_adapter_idx(chosen_wgpu_backends()?, idx)?,
+                None => <WGPU as Device>::new()?,
+            })),
+            _ => Err(DeviceError::UnknownBackend.into()),
+        }
+    }
+}
+
+impl Device for AnyDevice {
+    type Ptr<U, S: Shape> = AnyPtr<U>;
+    type Cache = ();
+
+    /// Creates the first enabled backend, in the order CPU, OpenCL, CUDA, WGPU.
+    fn new() -> crate::Result<Self> {
+        #[cfg(feature = "cpu")]
+        let device = AnyDevice::CPU(CPU::new());
+        #[cfg(all(not(feature = "cpu"), feature = "opencl"))]
+        let device = AnyDevice::OpenCL(OpenCL::new(chosen_cl_idx())?);
+        #[cfg(all(not(feature = "cpu"), not(feature = "opencl"), feature = "cuda"))]
+        let device = AnyDevice::CUDA(CUDA::new(chosen_cu_idx())?);
+        #[cfg(all(
+            not(feature = "cpu"),
+            not(feature = "opencl"),
+            not(feature = "cuda"),
+            feature = "wgpu"
+        ))]
+        let device = AnyDevice::WGPU(<WGPU as Device>::new()?);
+
+        Ok(device)
+    }
+}
+
+impl IsShapeIndep for AnyDevice {}
+
+impl<T> Alloc<'_, T> for AnyDevice {
+    #[inline]
+    fn alloc(&self, len: usize, flag: AllocFlag) -> AnyPtr<T> {
+        any_dispatch!(self, device => Alloc::<T>::alloc(device, len, flag).into())
+    }
+
+    #[inline]
+    fn with_slice(&self, data: &[T]) -> AnyPtr<T>
+    where
+        T: Clone,
+    {
+        any_dispatch!(self, device => Alloc::<T>::with_slice(device, data).into())
+    }
+
+    #[inline]
+    fn alloc_with_vec(&self, vec: Vec<T>) -> AnyPtr<T>
+    where
+        T: Clone,
+    {
+        any_dispatch!(self, device => Alloc::<T>::alloc_with_vec(device, vec).into())
+    }
+}
+
+impl<T: Clone + Default> Read<T, AnyDevice> for AnyDevice {
+    type Read<'a> = Vec<T> where T: 'a;
+
+    #[inline]
+    fn read(&self, buf: &Buffer<T, AnyDevice>) -> Vec<T> {
+        self.read_to_vec(buf)
+    }
+
+    #[inline]
+    fn read_to_vec(&self, buf: &Buffer<T, AnyDevice>) -> Vec<T> {
+        any_dispatch!(self, device => device.read_to_vec(&buf.backend(device)))
+    }
+}
+
+impl<T: Copy> WriteBuf<T, AnyDevice> for AnyDevice {
+    #[inline]
+    fn write(&self, buf: &mut Buffer<T, AnyDevice>, data: &[T]) {
+        any_dispatch!(self, device => device.write(&mut buf.backend_mut(device), data))
+    }
+
+    #[inline]
+    fn write_buf(&self, dst: &mut Buffer<T, AnyDevice>, src: &Buffer<T, AnyDevice>) {
+        any_dispatch!(self, device => {
+            device.copy_slice_to(&src.backend(device), .., &mut dst.backend_mut(device), ..)
+        })
+    }
+}
+
+impl<T: CDatatype> ClearBuf<T, AnyDevice> for AnyDevice {
+    #[inline]
+    fn clear(&self, buf: &mut Buffer<T, AnyDevice>) {
+        any_dispatch!(self, device => device.clear(&mut buf.backend_mut(device)))
+    }
+}
+
+impl<T: Copy> CopySlice<T> for AnyDevice {
+    #[inline]
+    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, AnyDevice>,
+        source_range: SR,
+        dest: &mut Buffer<T, AnyDevice>,
+        dest_range: DR,
+    ) {
+        any_dispatch!(self, device => device.copy_slice_to(
+            &source.backend(device),
+            source_range,
+            &mut dest.backend_mut(device),
+            dest_range,
+        ))
+    }
+
+    #[inline]
+    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
+        &self,
+        source: &Buffer<T, AnyDevice>,
+        dest: &mut Buffer<T, AnyDevice>,
+        ranges: I,
+    ) {
+        any_dispatch!(self, device => {
+            device.copy_slice_all(&source.backend(device), &mut dest.backend_mut(device), ranges)
+        })
+    }
+}
+
+impl<'a, T: Clone> CloneBuf<'a, T> for AnyDevice {
+    #[inline]
+    fn clone_buf(&'a self, buf: &Buffer<'a, T, AnyDevice>) -> Buffer<'a, T, AnyDevice> {
+        any_dispatch!(self, device => self.wrap(device.clone_buf(&buf.backend(device))))
+    }
+}
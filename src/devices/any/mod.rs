@@ -0,0 +1,367 @@
+//! A device that selects its backend at runtime.
+//!
+//! [`AnyDevice`] wraps one of the compiled-in devices (CPU, OpenCL, CUDA or WGPU) and dispatches every operation to it.
+//! Op traits can offer an [`AnyDevice`] implementation with [`any_dispatch!`](crate::any_dispatch):
+//! the buffers are viewed as buffers of the active backend via [`Buffer::backend`] / [`Buffer::backend_mut`]
+//! and a returned backend buffer is converted back with [`AnyDevice::wrap`].
+
+mod any_device;
+
+pub use any_device::*;
+
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+#[cfg(feature = "cpu")]
+use crate::cpu::CPUPtr;
+#[cfg(feature = "cuda")]
+use crate::cuda::CUDAPtr;
+#[cfg(feature = "opencl")]
+use crate::opencl::CLPtr;
+#[cfg(feature = "wgpu")]
+use crate::wgpu::WGPUBufPtr;
+use crate::{flag::AllocFlag, Buffer, Device, PtrType, ShallowCopy};
+
+/// A buffer of an [`AnyDevice`].
+pub type AnyBuffer<'a, T> = Buffer<'a, T, AnyDevice>;
+
+/// The pointer of an [`AnyBuffer`], which is the pointer of the backend that allocated the buffer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AnyPtr<T> {
+    #[cfg(feature = "cpu")]
+    CPU(CPUPtr<T>),
+    #[cfg(feature = "opencl")]
+    OpenCL(CLPtr<T>),
+    #[cfg(feature = "cuda")]
+    CUDA(CUDAPtr<T>),
+    #[cfg(feature = "wgpu")]
+    WGPU(WGPUBufPtr<T>),
+}
+
+impl<T> PtrType for AnyPtr<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            #[cfg(feature = "cpu")]
+            AnyPtr::CPU(ptr) => ptr.len(),
+            #[cfg(feature = "opencl")]
+            AnyPtr::OpenCL(ptr) => ptr.len(),
+            #[cfg(feature = "cuda")]
+            AnyPtr::CUDA(ptr) => ptr.len(),
+            #[cfg(feature = "wgpu")]
+            AnyPtr::WGPU(ptr) => ptr.len(),
+        }
+    }
+
+    #[inline]
+    fn flag(&self) -> AllocFlag {
+        match self {
+            #[cfg(feature = "cpu")]
+            AnyPtr::CPU(ptr) => ptr.flag(),
+            #[cfg(feature = "opencl")]
+            AnyPtr::OpenCL(ptr) => ptr.flag(),
+            #[cfg(feature = "cuda")]
+            AnyPtr::CUDA(ptr) => ptr.flag(),
+            #[cfg(feature = "wgpu")]
+            AnyPtr::WGPU(ptr) => ptr.flag(),
+        }
+    }
+}
+
+impl<T> ShallowCopy for AnyPtr<T> {
+    #[inline]
+    unsafe fn shallow(&self) -> Self {
+        match self {
+            #[cfg(feature = "cpu")]
+            AnyPtr::CPU(ptr) => AnyPtr::CPU(ptr.shallow()),
+            #[cfg(feature = "opencl")]
+            AnyPtr::OpenCL(ptr) => AnyPtr::OpenCL(ptr.shallow()),
+            #[cfg(feature = "cuda")]
+            AnyPtr::CUDA(ptr) => AnyPtr::CUDA(ptr.shallow()),
+            #[cfg(feature = "wgpu")]
+            AnyPtr::WGPU(ptr) => AnyPtr::WGPU(ptr.shallow()),
+        }
+    }
+}
+
+/// A device that can be wrapped by an [`AnyDevice`].
+pub trait AnyBackend: Device {
+    /// Converts the pointer of a backend buffer into an [`AnyPtr`].
+    fn into_any<T>(ptr: Self::Ptr<T, ()>) -> AnyPtr<T>;
+
+    /// Converts an [`AnyPtr`] into the pointer of this backend.
+    /// # Panics
+    /// If the pointer belongs to another backend.
+    fn from_any<T>(ptr: AnyPtr<T>) -> Self::Ptr<T, ()>;
+}
+
+macro_rules! impl_any_backend {
+    ($device:ident, $ptr:ident) => {
+        impl AnyBackend for crate::$device {
+            #[inline]
+            fn into_any<T>(ptr: Self::Ptr<T, ()>) -> AnyPtr<T> {
+                AnyPtr::$device(ptr)
+            }
+
+            #[inline]
+            #[allow(unreachable_patterns)]
+            fn from_any<T>(ptr: AnyPtr<T>) -> Self::Ptr<T, ()> {
+                match ptr {
+                    AnyPtr::$device(ptr) => ptr,
+                    _ => panic!(concat!(
+                        "The buffer was not allocated by ",
+                        stringify!($device),
+                        "."
+                    )),
+                }
+            }
+        }
+
+        impl<T> From<$ptr<T>> for AnyPtr<T> {
+            #[inline]
+            fn from(ptr: $ptr<T>) -> Self {
+                AnyPtr::$device(ptr)
+            }
+        }
+
+        impl From<crate::$device> for AnyDevice {
+            #[inline]
+            fn from(device: crate::$device) -> Self {
+                AnyDevice::$device(device)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "cpu")]
+impl_any_backend!(CPU, CPUPtr);
+#[cfg(feature = "opencl")]
+impl_any_backend!(OpenCL, CLPtr);
+#[cfg(feature = "cuda")]
+impl_any_backend!(CUDA, CUDAPtr);
+#[cfg(feature = "wgpu")]
+impl_any_backend!(WGPU, WGPUBufPtr);
+
+/// A non-owning view of an [`AnyBuffer`] as a buffer of its backend, returned by [`Buffer::backend`].
+pub struct BackendRef<'b, 'a, T, D: Device> {
+    buf: Buffer<'a, T, D>,
+    _borrow: PhantomData<&'b ()>,
+}
+
+impl<'a, T, D: Device> Deref for BackendRef<'_, 'a, T, D> {
+    type Target = Buffer<'a, T, D>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.buf
+    }
+}
+
+/// A non-owning, mutable view of an [`AnyBuffer`] as a buffer of its backend, returned by [`Buffer::backend_mut`].
+pub struct BackendMut<'b, 'a, T, D: Device> {
+    buf: Buffer<'a, T, D>,
+    _borrow: PhantomData<&'b mut ()>,
+}
+
+impl<'a, T, D: Device> Deref for BackendMut<'_, 'a, T, D> {
+    type Target = Buffer<'a, T, D>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.buf
+    }
+}
+
+impl<T, D: Device> DerefMut for BackendMut<'_, '_, T, D> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buf
+    }
+}
+
+impl<T> Buffer<'_, T, AnyDevice> {
+    /// Returns a view of this buffer as a buffer of the backend `device`.
+    /// # Panics
+    /// If the buffer was not allocated by the backend of `device`.
+    #[inline]
+    pub fn backend<'d, D: AnyBackend>(&self, device: &'d D) -> BackendRef<'_, 'd, T, D> {
+        BackendRef {
+            buf: Buffer {
+                // the shallow copy does not free the memory
+                ptr: D::from_any(unsafe { self.ptr.shallow() }),
+                device: Some(device),
+                node: self.node,
+            },
+            _borrow: PhantomData,
+        }
+    }
+
+    /// Returns a mutable view of this buffer as a buffer of the backend `device`.
+    /// # Panics
+    /// If the buffer was not allocated by the backend of `device`.
+    #[inline]
+    pub fn backend_mut<'d, D: AnyBackend>(&mut self, device: &'d D) -> BackendMut<'_, 'd, T, D> {
+        BackendMut {
+            buf: Buffer {
+                ptr: D::from_any(unsafe { self.ptr.shallow() }),
+                device: Some(device),
+                node: self.node,
+            },
+            _borrow: PhantomData,
+        }
+    }
+}
+
+/// Runs `$body` with `$device` bound to the active backend of an [`AnyDevice`](crate::AnyDevice).
+///
+/// The body is compiled for every enabled backend, hence all of them have to implement the used operations.
+/// # Example
+#[cfg_attr(
+    all(
+        feature = "cpu",
+        not(feature = "opencl"),
+        not(feature = "cuda"),
+        not(feature = "wgpu")
+    ),
+    doc = "```"
+)]
+#[cfg_attr(
+    not(all(
+        feature = "cpu",
+        not(feature = "opencl"),
+        not(feature = "cuda"),
+        not(feature = "wgpu")
+    )),
+    doc = "```ignore"
+)]
+/// use core::ops::Add;
+/// use custos::{any_dispatch, AnyDevice, Buffer, Device, CPU};
+///
+/// pub trait AddBuf<T>: Device {
+///     fn add(&self, lhs: &Buffer<T, Self>, rhs: &Buffer<T, Self>) -> Buffer<T, Self>;
+/// }
+///
+/// impl<T: Copy + Add<Output = T>> AddBuf<T> for CPU {
+///     fn add(&self, lhs: &Buffer<T, CPU>, rhs: &Buffer<T, CPU>) -> Buffer<T, CPU> {
+///         let mut out = Buffer::new(self, lhs.len());
+///         for ((out, lhs), rhs) in out.iter_mut().zip(lhs.iter()).zip(rhs.iter()) {
+///             *out = *lhs + *rhs;
+///         }
+///         out
+///     }
+/// }
+///
+/// // add the other backends (e.g. OpenCL: AddBuf<T>) to the bounds
+/// impl<T> AddBuf<T> for AnyDevice
+/// where
+///     CPU: AddBuf<T>,
+/// {
+///     fn add(&self, lhs: &Buffer<T, Self>, rhs: &Buffer<T, Self>) -> Buffer<T, Self> {
+///         any_dispatch!(self, device => {
+///             self.wrap(device.add(&lhs.backend(device), &rhs.backend(device)))
+///         })
+///     }
+/// }
+///
+/// let device: AnyDevice = "cpu".parse().unwrap();
+/// let lhs = Buffer::from((&device, [1, 2, 3]));
+/// let rhs = Buffer::from((&device, [4, 5, 6]));
+///
+/// assert_eq!(device.add(&lhs, &rhs).read(), [5, 7, 9]);
+/// ```
+#[macro_export]
+macro_rules! any_dispatch {
+    ($any:expr, $device:ident => $body:expr) => {{
+        let any: &$crate::AnyDevice = $any;
+
+        #[allow(irrefutable_let_patterns)]
+        let output = 'dispatch: {
+            $crate::__any_cpu! {
+                if let $crate::AnyDevice::CPU($device) = any {
+                    let output = $body;
+                    break 'dispatch output;
+                }
+            }
+            $crate::__any_opencl! {
+                if let $crate::AnyDevice::OpenCL($device) = any {
+                    let output = $body;
+                    break 'dispatch output;
+                }
+            }
+            $crate::__any_cuda! {
+                if let $crate::AnyDevice::CUDA($device) = any {
+                    let output = $body;
+                    break 'dispatch output;
+                }
+            }
+            $crate::__any_wgpu! {
+                if let $crate::AnyDevice::WGPU($device) = any {
+                    let output = $body;
+                    break 'dispatch output;
+                }
+            }
+            unreachable!()
+        };
+        output
+    }};
+}
+
+// The features are checked here, as a `cfg` in the expansion of `any_dispatch!` would check the features of the calling crate.
+
+#[doc(hidden)]
+#[cfg(feature = "cpu")]
+#[macro_export]
+macro_rules! __any_cpu {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "cpu"))]
+#[macro_export]
+macro_rules! __any_cpu {
+    ($($tokens:tt)*) => {};
+}
+
+#[doc(hidden)]
+#[cfg(feature = "opencl")]
+#[macro_export]
+macro_rules! __any_opencl {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "opencl"))]
+#[macro_export]
+macro_rules! __any_opencl {
+    ($($tokens:tt)*) => {};
+}
+
+#[doc(hidden)]
+#[cfg(feature = "cuda")]
+#[macro_export]
+macro_rules! __any_cuda {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "cuda"))]
+#[macro_export]
+macro_rules! __any_cuda {
+    ($($tokens:tt)*) => {};
+}
+
+#[doc(hidden)]
+#[cfg(feature = "wgpu")]
+#[macro_export]
+macro_rules! __any_wgpu {
+    ($($tokens:tt)*) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "wgpu"))]
+#[macro_export]
+macro_rules! __any_wgpu {
+    ($($tokens:tt)*) => {};
+}
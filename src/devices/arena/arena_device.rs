@@ -0,0 +1,373 @@
+use alloc::vec::Vec;
+use core::{
+    cell::Cell,
+    marker::PhantomData,
+    mem::{align_of, size_of},
+};
+
+use super::{ArenaPtr, Checkpoint};
+use crate::{
+    flag::AllocFlag, shape::Shape, Alloc, Buffer, ClearBuf, Device, DeviceError, IsShapeIndep,
+    MainMemory, Read, WriteBuf,
+};
+
+/// A device, which carves all of its buffers out of a fixed memory region using bump allocation.
+/// It never allocates on the heap and works without the standard library.
+///
+/// Individual buffers are never freed. Instead, the whole arena is cleared with [`Arena::reset`]
+/// or rolled back to a [`Checkpoint`] (see [`Arena::scope`] and [`Arena::rollback`]).
+///
+/// If the arena is exhausted, [`Arena::try_buf`] and [`Arena::try_alloc`] return [`DeviceError::ArenaExhausted`].
+/// [`Buffer::new`] and other constructors using [`Alloc`] panic with this error instead.
+///
+/// # Example
+/// ```
+/// use custos::{Arena, Buffer, ClearBuf, DeviceError, ErrorKind, Read};
+///
+/// let mut memory = [0u8; 64];
+/// let device = Arena::new(&mut memory);
+///
+/// let mut buf = Buffer::from((&device, [1f32, 2., 3., 4.]));
+/// device.clear(&mut buf);
+/// assert_eq!(device.read(&buf), [0.; 4]);
+///
+/// let err = device.try_buf::<f32>(100).err().unwrap();
+/// assert_eq!(err.kind(), Some(&DeviceError::ArenaExhausted));
+/// ```
+#[derive(Debug)]
+pub struct Arena<'m> {
+    start: *mut u8,
+    capacity: usize,
+    offset: Cell<usize>,
+    _memory: PhantomData<&'m mut [u8]>,
+}
+
+impl<'m> Arena<'m> {
+    /// Creates an arena, which allocates its buffers in `memory`.
+    #[inline]
+    pub fn new(memory: &'m mut [u8]) -> Arena<'m> {
+        Arena {
+            start: memory.as_mut_ptr(),
+            capacity: memory.len(),
+            offset: Cell::new(0),
+            _memory: PhantomData,
+        }
+    }
+
+    /// The size of the memory region in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of used bytes, including the padding for alignment.
+    #[inline]
+    pub fn used(&self) -> usize {
+        self.offset.get()
+    }
+
+    /// The number of bytes, which are not used yet.
+    /// Because of alignment, an allocation of this size may fail anyway.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.capacity - self.used()
+    }
+
+    /// Allocates `len` zeroed elements of type `T`, aligned for `T`.
+    /// # Errors
+    /// [`DeviceError::ArenaExhausted`], if the remaining memory is too small.
+    pub fn try_alloc<T>(&self, len: usize, flag: AllocFlag) -> crate::Result<ArenaPtr<T>> {
+        let addr = self.start as usize + self.offset.get();
+
+        // align_of is always a power of two
+        let start = addr
+            .checked_add(align_of::<T>() - 1)
+            .map(|addr| (addr & !(align_of::<T>() - 1)) - self.start as usize);
+
+        let end = start.and_then(|start| {
+            let end = start.checked_add(len.checked_mul(size_of::<T>())?)?;
+            (end <= self.capacity).then_some(end)
+        });
+
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(DeviceError::ArenaExhausted.into());
+        };
+
+        // Safety: start..end is within the memory region and was not handed out before (or was rolled back)
+        let ptr = unsafe {
+            let ptr = self.start.add(start);
+            ptr.write_bytes(0, end - start);
+            ptr.cast::<T>()
+        };
+
+        self.offset.set(end);
+        Ok(ArenaPtr { ptr, len, flag })
+    }
+
+    /// Creates a zeroed `Buffer` with the given length.
+    /// Unlike [`Buffer::new`], this does not panic if the arena is exhausted.
+    /// # Errors
+    /// [`DeviceError::ArenaExhausted`], if the remaining memory is too small.
+    #[inline]
+    pub fn try_buf<T>(&self, len: usize) -> crate::Result<Buffer<'_, T, Arena<'m>>> {
+        Ok(Buffer {
+            ptr: self.try_alloc(len, AllocFlag::None)?,
+            device: Some(self),
+            node: Default::default(),
+        })
+    }
+
+    /// Returns the current fill level, which can be restored with [`Arena::rollback`].
+    #[inline]
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.used())
+    }
+
+    /// Frees all buffers allocated after `checkpoint` was created.
+    /// Buffers allocated before remain valid, which allows e.g. reusing scratch memory in a loop.
+    ///
+    /// # Safety
+    /// Buffers allocated after `checkpoint` must not be used anymore, as their memory is handed out again.
+    /// Use [`Arena::scope`] for a safe alternative.
+    #[inline]
+    pub unsafe fn rollback(&self, checkpoint: Checkpoint) {
+        if checkpoint.0 < self.used() {
+            self.offset.set(checkpoint.0);
+        }
+    }
+
+    /// Frees all buffers.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.offset.set(0);
+    }
+
+    /// Calls `f` with an arena, which uses the remaining memory of this arena.
+    /// All of its buffers are freed when `f` returns, while the buffers of this arena stay valid.
+    ///
+    /// While `f` runs, this arena is exhausted.
+    ///
+    /// # Example
+    /// ```
+    /// use custos::{Arena, Buffer};
+    ///
+    /// let mut memory = [0u8; 64];
+    /// let device = Arena::new(&mut memory);
+    /// let weights = Buffer::from((&device, [1i32, 2, 3]));
+    ///
+    /// for _ in 0..100 {
+    ///     let sum = device.scope(|scratch| {
+    ///         let mut tmp = Buffer::<i32, _>::new(scratch, 3);
+    ///         tmp.copy_from_slice(&weights);
+    ///         tmp.iter().sum::<i32>()
+    ///     });
+    ///     assert_eq!(sum, 6);
+    /// }
+    /// assert_eq!(device.used(), 12);
+    /// ```
+    pub fn scope<R>(&self, f: impl FnOnce(&Arena<'_>) -> R) -> R {
+        let used = self.used();
+
+        let scratch = Arena {
+            // Safety: used <= capacity
+            start: unsafe { self.start.add(used) },
+            capacity: self.remaining(),
+            offset: Cell::new(0),
+            _memory: PhantomData,
+        };
+
+        self.offset.set(self.capacity);
+        let output = f(&scratch);
+        self.offset.set(used);
+
+        output
+    }
+}
+
+impl Device for Arena<'_> {
+    type Ptr<U, S: Shape> = ArenaPtr<U>;
+    type Cache = ();
+
+    fn new() -> crate::Result<Self> {
+        Err(DeviceError::MissingArenaMemory.into())
+    }
+}
+
+impl IsShapeIndep for Arena<'_> {}
+
+impl<T, S: Shape> Alloc<'_, T, S> for Arena<'_> {
+    /// # Panics
+    /// If the arena is exhausted. Use [`Arena::try_alloc`] to handle this case.
+    fn alloc(&self, mut len: usize, flag: AllocFlag) -> ArenaPtr<T> {
+        assert!(len > 0, "invalid buffer len: 0");
+
+        if S::LEN > len {
+            len = S::LEN
+        }
+
+        match self.try_alloc(len, flag) {
+            Ok(ptr) => ptr,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    fn with_slice(&self, data: &[T]) -> ArenaPtr<T>
+    where
+        T: Clone,
+    {
+        let ptr = Alloc::<T, S>::alloc(self, data.len(), AllocFlag::None);
+
+        // Safety: the memory of the pointer is allocated for data.len() elements
+        let slice = unsafe { core::slice::from_raw_parts_mut(ptr.ptr, data.len()) };
+        slice.clone_from_slice(data);
+        ptr
+    }
+}
+
+impl MainMemory for Arena<'_> {
+    #[inline]
+    fn as_ptr<T, S: Shape>(ptr: &Self::Ptr<T, S>) -> *const T {
+        ptr.ptr
+    }
+
+    #[inline]
+    fn as_ptr_mut<T, S: Shape>(ptr: &mut Self::Ptr<T, S>) -> *mut T {
+        ptr.ptr
+    }
+}
+
+impl<T, D: MainMemory, S: Shape> Read<T, D, S> for Arena<'_> {
+    type Read<'a> = &'a [T] where T: 'a, D: 'a, S: 'a;
+
+    #[inline]
+    fn read<'a>(&self, buf: &'a Buffer<T, D, S>) -> Self::Read<'a> {
+        buf.as_slice()
+    }
+
+    #[inline]
+    fn read_to_vec(&self, buf: &Buffer<T, D, S>) -> Vec<T>
+    where
+        T: Default + Clone,
+    {
+        buf.to_vec()
+    }
+}
+
+impl<T: Copy, D: MainMemory, S: Shape> WriteBuf<T, D, S> for Arena<'_> {
+    #[inline]
+    fn write(&self, buf: &mut Buffer<T, D, S>, data: &[T]) {
+        buf.copy_from_slice(data)
+    }
+
+    #[inline]
+    fn write_buf(&self, dst: &mut Buffer<T, Self, S>, src: &Buffer<T, Self, S>) {
+        dst.copy_from_slice(src)
+    }
+}
+
+impl<T: Default, D: MainMemory, S: Shape> ClearBuf<T, D, S> for Arena<'_> {
+    #[inline]
+    fn clear(&self, buf: &mut Buffer<T, D, S>) {
+        for value in buf.iter_mut() {
+            *value = T::default();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::align_of;
+
+    use crate::{Arena, Buffer, DeviceError, ErrorKind};
+
+    #[test]
+    fn test_arena_alignment() {
+        let mut memory = [0u8; 64];
+        let device = Arena::new(&mut memory);
+
+        let a = Buffer::<u8, _>::new(&device, 3);
+        let b = Buffer::<u64, _>::new(&device, 2);
+        let c = Buffer::<u16, _>::new(&device, 1);
+
+        assert_eq!(b.ptr.ptr as usize % align_of::<u64>(), 0);
+        assert_eq!(c.ptr.ptr as usize % align_of::<u16>(), 0);
+        assert!(a.ptr.ptr as usize + 3 <= b.ptr.ptr as usize);
+        assert_eq!(c.ptr.ptr as usize, b.ptr.ptr as usize + 16);
+    }
+
+    #[test]
+    fn test_arena_exhausted() {
+        let mut memory = [0u8; 16];
+        let device = Arena::new(&mut memory);
+
+        let _a = device.try_buf::<u32>(3).unwrap();
+        let err = device.try_buf::<u32>(2).err().unwrap();
+        assert_eq!(err.kind(), Some(&DeviceError::ArenaExhausted));
+
+        // the failed allocation does not use memory
+        assert_eq!(device.used(), 12);
+        assert!(device.try_buf::<u32>(1).is_ok());
+        assert_eq!(device.remaining(), 0);
+
+        assert!(device.try_buf::<u32>(usize::MAX).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_arena_exhausted_panics() {
+        let mut memory = [0u8; 8];
+        let device = Arena::new(&mut memory);
+        Buffer::<f32, _>::new(&device, 3);
+    }
+
+    #[test]
+    fn test_arena_rollback() {
+        let mut memory = [0u8; 32];
+        let device = Arena::new(&mut memory);
+
+        let a = Buffer::from((&device, [1, 2]));
+        let checkpoint = device.checkpoint();
+
+        for _ in 0..10 {
+            device.try_buf::<i32>(6).unwrap().copy_from_slice(&[3; 6]);
+            unsafe { device.rollback(checkpoint) };
+        }
+        assert_eq!(device.used(), 8);
+
+        // rolled back memory is zeroed again
+        assert_eq!(device.try_buf::<i32>(6).unwrap().as_slice(), &[0; 6]);
+        assert_eq!(a.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_arena_reset() {
+        let mut memory = [0u8; 8];
+        let mut device = Arena::new(&mut memory);
+
+        Buffer::<u64, _>::new(&device, 1);
+        assert!(device.try_buf::<u8>(1).is_err());
+
+        device.reset();
+        assert_eq!(device.used(), 0);
+        assert!(device.try_buf::<u64>(1).is_ok());
+    }
+
+    #[test]
+    fn test_arena_scope() {
+        let mut memory = [0u8; 32];
+        let device = Arena::new(&mut memory);
+
+        let _a = Buffer::<u32, _>::new(&device, 2);
+
+        let remaining = device.scope(|scratch| {
+            assert_eq!(scratch.capacity(), 24);
+            assert!(device.try_buf::<u8>(1).is_err());
+
+            let _b = Buffer::<u32, _>::new(scratch, 4);
+            scratch.remaining()
+        });
+
+        assert_eq!(remaining, 8);
+        assert_eq!(device.used(), 8);
+    }
+}
@@ -0,0 +1,73 @@
+use core::ptr::null_mut;
+
+pub use arena_device::*;
+
+use crate::{flag::AllocFlag, CommonPtrs, PtrType, ShallowCopy};
+
+mod arena_device;
+
+/// The pointer type of the [`Arena`] device.
+/// The memory belongs to the arena: dropping an `ArenaPtr` does not free anything (see [`Arena::reset`]).
+#[derive(Debug, PartialEq, Eq)]
+pub struct ArenaPtr<T> {
+    pub ptr: *mut T,
+    pub len: usize,
+    pub flag: AllocFlag,
+}
+
+impl<T> Default for ArenaPtr<T> {
+    fn default() -> Self {
+        Self {
+            ptr: null_mut(),
+            len: 0,
+            flag: AllocFlag::default(),
+        }
+    }
+}
+
+impl<T> PtrType for ArenaPtr<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn flag(&self) -> AllocFlag {
+        self.flag
+    }
+}
+
+impl<T> CommonPtrs<T> for ArenaPtr<T> {
+    #[inline]
+    fn ptrs(&self) -> (*const T, *mut core::ffi::c_void, u64) {
+        (self.ptr, null_mut(), 0)
+    }
+
+    #[inline]
+    fn ptrs_mut(&mut self) -> (*mut T, *mut core::ffi::c_void, u64) {
+        (self.ptr, null_mut(), 0)
+    }
+}
+
+impl<T> ShallowCopy for ArenaPtr<T> {
+    #[inline]
+    unsafe fn shallow(&self) -> Self {
+        ArenaPtr {
+            ptr: self.ptr,
+            len: self.len,
+            flag: AllocFlag::Wrapper,
+        }
+    }
+}
+
+/// The fill level of an [`Arena`] at a point in time, which can be restored with [`Arena::rollback`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Checkpoint(pub(super) usize);
+
+impl Checkpoint {
+    /// The number of bytes, which were used when the checkpoint was created.
+    #[inline]
+    pub fn used(&self) -> usize {
+        self.0
+    }
+}
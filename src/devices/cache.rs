@@ -1,7 +1,12 @@
+use alloc::rc::Rc;
 use core::{cell::RefMut, marker::PhantomData};
-use std::collections::HashMap;
 
-use std::rc::Rc;
+#[cfg(not(feature = "no-std"))]
+use std::collections::HashMap as Map;
+
+// without the standard library, there is no HashMap (and no random state)
+#[cfg(feature = "no-std")]
+use alloc::collections::BTreeMap as Map;
 
 use crate::{
     bump_count, flag::AllocFlag, shape::Shape, AddGraph, Alloc, Buffer, CacheAble, Device,
@@ -24,7 +29,7 @@ pub trait RawConv: Device + CacheReturn {
 
 #[derive(Debug)]
 pub struct Cache<D: RawConv> {
-    pub nodes: HashMap<Ident, Rc<D::CT>>,
+    pub nodes: Map<Ident, Rc<D::CT>>,
     _p: PhantomData<D>,
 }
 
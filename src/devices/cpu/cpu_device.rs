@@ -2,12 +2,13 @@ use crate::{
     cache::RawConv,
     devices::cache::{Cache, CacheReturn},
     flag::AllocFlag,
-    op_traits::{bounds_to_range, CacheBuf, ClearBuf, CloneBuf, CopySlice},
+    op_traits::{bounds_to_range, copy_rect_slices, CacheBuf, ClearBuf, CloneBuf, CopySlice},
     shape::Shape,
-    Alloc, Buffer, CachedLeaf, Device, DevicelessAble, Graph, GraphReturn, MainMemory, Read,
-    WriteBuf,
+    Alloc, Buffer, CachedLeaf, CopyRect, Device, DevicelessAble, Graph, GraphReturn, MainMemory,
+    Node, Read, Rect, WriteBuf,
 };
 
+use alloc::vec::Vec;
 use core::{
     cell::{RefCell, RefMut},
     fmt::Debug,
@@ -17,7 +18,7 @@ use core::{
 
 use super::{CPUPtr, RawCpuBuf};
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 /// A CPU is used to perform calculations on the host CPU.
 /// To make new operations invocable, a trait providing new functions should be implemented for [CPU].
 ///
@@ -35,6 +36,7 @@ use super::{CPUPtr, RawCpuBuf};
 pub struct CPU {
     pub cache: RefCell<Cache<CPU>>,
     pub graph: RefCell<Graph>,
+    align: usize,
 }
 
 impl CPU {
@@ -44,10 +46,71 @@ impl CPU {
         CPU {
             cache: RefCell::new(Cache::default()),
             graph: RefCell::new(Graph::new()),
+            align: 1,
+        }
+    }
+
+    /// Every allocation of this [CPU] is aligned to at least `align` bytes, e.g. for SIMD kernels or FFI libraries.
+    /// Types with a larger alignment keep their natural alignment.
+    /// # Panics
+    /// If `align` is not a power of two.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, CPU};
+    ///
+    /// let device = CPU::new().with_align(64);
+    /// let buf = Buffer::<f32>::new(&device, 10);
+    ///
+    /// assert_eq!(buf.ptr.ptr as usize % 64, 0);
+    /// ```
+    #[must_use]
+    pub fn with_align(mut self, align: usize) -> CPU {
+        assert!(
+            align.is_power_of_two(),
+            "The alignment must be a power of two."
+        );
+        self.align = align;
+        self
+    }
+
+    /// The minimum alignment of the allocations in bytes.
+    #[inline]
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Allocates a [`Buffer`] with `len` elements without zeroing the memory.
+    /// This is useful for buffers, which are about to be overwritten.
+    /// # Safety
+    /// Every element must be written before it is read.
+    /// # Example
+    /// ```
+    /// use custos::CPU;
+    ///
+    /// let device = CPU::new();
+    /// let mut buf = unsafe { device.uninit_buf::<i32>(3) };
+    /// buf.copy_from_slice(&[1, 2, 3]);
+    ///
+    /// assert_eq!(buf.read(), [1, 2, 3]);
+    /// ```
+    pub unsafe fn uninit_buf<T>(&self, len: usize) -> Buffer<'_, T> {
+        assert!(len > 0, "invalid buffer len: 0");
+
+        Buffer {
+            ptr: CPUPtr::new_uninit(len, self.align, AllocFlag::None),
+            device: Some(self),
+            node: Node::default(),
         }
     }
 }
 
+impl Default for CPU {
+    #[inline]
+    fn default() -> Self {
+        CPU::new()
+    }
+}
+
 impl Device for CPU {
     type Ptr<U, S: Shape> = CPUPtr<U>;
     type Cache = Cache<CPU>; //<CPU as CacheReturn>::CT
@@ -63,7 +126,7 @@ impl RawConv for CPU {
         RawCpuBuf {
             ptr: ptr.ptr.cast(),
             len,
-            align: align_of::<T>(),
+            align: ptr.align,
             size: size_of::<T>(),
             node,
         }
@@ -76,6 +139,7 @@ impl RawConv for CPU {
                 ptr: ct.ptr as *mut T,
                 len: ct.len,
                 flag,
+                align: ct.align,
             },
             ct.node,
         )
@@ -92,7 +156,7 @@ impl<T, S: Shape> Alloc<'_, T, S> for CPU {
             len = S::LEN
         }
 
-        CPUPtr::new(len, flag)
+        CPUPtr::new_aligned(len, self.align, flag)
     }
 
     fn with_slice(&self, data: &[T]) -> CPUPtr<T>
@@ -102,22 +166,33 @@ impl<T, S: Shape> Alloc<'_, T, S> for CPU {
         assert!(!data.is_empty(), "invalid buffer len: 0");
         let cpu_ptr = Alloc::<T>::alloc(self, data.len(), AllocFlag::None);
         //= self.alloc(data.len());
-        let slice = unsafe { std::slice::from_raw_parts_mut(cpu_ptr.ptr, data.len()) };
+        let slice = unsafe { core::slice::from_raw_parts_mut(cpu_ptr.ptr, data.len()) };
         slice.clone_from_slice(data);
 
         cpu_ptr
     }
-    fn alloc_with_vec(&self, mut vec: Vec<T>) -> CPUPtr<T> {
+    fn alloc_with_vec(&self, vec: Vec<T>) -> CPUPtr<T> {
         assert!(!vec.is_empty(), "invalid buffer len: 0");
 
-        let ptr = vec.as_mut_ptr();
+        // the allocation of the vector does not satisfy the requested alignment
+        if self.align > align_of::<T>() {
+            let cpu_ptr =
+                unsafe { CPUPtr::<T>::new_uninit(vec.len(), self.align, AllocFlag::None) };
+            for (idx, value) in vec.into_iter().enumerate() {
+                unsafe { cpu_ptr.ptr.add(idx).write(value) };
+            }
+            return cpu_ptr;
+        }
+
+        // the capacity must match the length for the deallocation
         let len = vec.len();
-        core::mem::forget(vec);
+        let ptr = alloc::boxed::Box::into_raw(vec.into_boxed_slice());
 
         CPUPtr {
-            ptr,
+            ptr: ptr.cast(),
             len,
             flag: AllocFlag::None,
+            align: align_of::<T>(),
         }
     }
 }
@@ -201,6 +276,44 @@ where
     }
 }
 
+impl<T: Copy, D: MainMemory, S: Shape> CopyRect<T, D, S> for CPU {
+    #[inline]
+    fn copy_rect<DS: Shape>(
+        &self,
+        source: &Buffer<T, D, S>,
+        source_rect: Rect,
+        dest: &mut Buffer<T, Self, DS>,
+        dest_rect: Rect,
+        region: [usize; 3],
+    ) {
+        copy_rect_slices(source, source_rect, dest, dest_rect, region);
+    }
+
+    #[inline]
+    fn read_rect(
+        &self,
+        buf: &Buffer<T, D, S>,
+        buf_rect: Rect,
+        host: &mut [T],
+        host_rect: Rect,
+        region: [usize; 3],
+    ) {
+        copy_rect_slices(buf, buf_rect, host, host_rect, region);
+    }
+
+    #[inline]
+    fn write_rect(
+        &self,
+        buf: &mut Buffer<T, D, S>,
+        buf_rect: Rect,
+        host: &[T],
+        host_rect: Rect,
+        region: [usize; 3],
+    ) {
+        copy_rect_slices(host, host_rect, buf, buf_rect, region);
+    }
+}
+
 #[inline]
 pub fn cpu_cached<T: Clone>(device: &CPU, len: usize) -> Buffer<T, CPU> {
     device.cached(len)
@@ -0,0 +1,57 @@
+use crate::{Backend, DeviceInfo, DeviceProperties, CPU};
+
+/// Returns the properties of the host CPU.
+/// On Linux, the name, vendor and memory size are read from `/proc`.
+pub fn cpu_properties() -> DeviceProperties {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap_or_default();
+
+    DeviceProperties {
+        backend: Backend::CPU,
+        name: proc_value(&cpuinfo, "model name")
+            .unwrap_or(std::env::consts::ARCH)
+            .to_string(),
+        vendor: proc_value(&cpuinfo, "vendor_id")
+            .unwrap_or_default()
+            .to_string(),
+        // e.g. "MemTotal:       16314484 kB"
+        global_mem_size: proc_value(&meminfo, "MemTotal")
+            .and_then(|total| total.trim_end_matches("kB").trim().parse::<u64>().ok())
+            .map(|kb| kb * 1024),
+        max_alloc_size: Some(isize::MAX as u64),
+        unified_mem: true,
+        f64_support: true,
+    }
+}
+
+/// Returns the value of the first `key: value` line with the given key.
+fn proc_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    content.lines().find_map(|line| {
+        let (line_key, value) = line.split_once(':')?;
+        (line_key.trim() == key).then(|| value.trim())
+    })
+}
+
+impl DeviceInfo for CPU {
+    #[inline]
+    fn info(&self) -> crate::Result<DeviceProperties> {
+        Ok(cpu_properties())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::proc_value;
+
+    #[test]
+    fn test_proc_value() {
+        let content =
+            "processor\t: 0\nvendor_id\t: GenuineIntel\nmodel name\t: Some CPU @ 3.00GHz\n";
+        assert_eq!(proc_value(content, "vendor_id"), Some("GenuineIntel"));
+        assert_eq!(
+            proc_value(content, "model name"),
+            Some("Some CPU @ 3.00GHz")
+        );
+        assert_eq!(proc_value(content, "flags"), None);
+    }
+}
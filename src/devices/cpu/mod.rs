@@ -1,51 +1,91 @@
 use crate::{CommonPtrs, Node, PtrType, ShallowCopy};
+use alloc::alloc::{alloc, alloc_zeroed, dealloc, handle_alloc_error};
 #[cfg(feature = "blas")]
 pub use blas::*;
-use core::{alloc::Layout, mem::size_of, ptr::null_mut};
+use core::{alloc::Layout, mem::align_of, ptr::null_mut};
 pub use cpu_device::*;
-use std::alloc::handle_alloc_error;
+#[cfg(not(feature = "no-std"))]
+pub use info::*;
 
 use crate::flag::AllocFlag;
 
 #[cfg(feature = "blas")]
 mod blas;
 mod cpu_device;
+#[cfg(not(feature = "no-std"))]
+mod info;
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct CPUPtr<T> {
     pub ptr: *mut T,
     pub len: usize,
     pub flag: AllocFlag,
+    /// The alignment of the allocation in bytes. It is used for deallocation.
+    pub align: usize,
 }
 
 impl<T> CPUPtr<T> {
+    /// Allocates `len` zeroed elements with the global allocator.
+    /// Without the standard library, a `#[global_allocator]` must be provided by the user.
+    #[inline]
     pub fn new(len: usize, flag: AllocFlag) -> CPUPtr<T> {
-        let layout = Layout::array::<T>(len).unwrap();
-        let ptr = unsafe { std::alloc::alloc(layout) };
+        CPUPtr::new_aligned(len, align_of::<T>(), flag)
+    }
 
-        // initialize block of memory
-        for element in unsafe { std::slice::from_raw_parts_mut(ptr, len * size_of::<T>()) } {
-            *element = 0;
-        }
+    /// Allocates `len` zeroed elements, which are aligned to `align` bytes (or `align_of::<T>()`, if it is larger).
+    /// # Panics
+    /// If `align` is not a power of two.
+    #[inline]
+    pub fn new_aligned(len: usize, align: usize, flag: AllocFlag) -> CPUPtr<T> {
+        // Safety: the memory is zeroed
+        unsafe { CPUPtr::alloc(len, align, flag, true) }
+    }
+
+    /// Allocates `len` elements like [`CPUPtr::new_aligned`], but skips zeroing the memory.
+    /// # Safety
+    /// Every element must be written before it is read.
+    #[inline]
+    pub unsafe fn new_uninit(len: usize, align: usize, flag: AllocFlag) -> CPUPtr<T> {
+        CPUPtr::alloc(len, align, flag, false)
+    }
+
+    unsafe fn alloc(len: usize, align: usize, flag: AllocFlag, zeroed: bool) -> CPUPtr<T> {
+        let layout = layout::<T>(len, align);
+
+        let ptr = if zeroed {
+            alloc_zeroed(layout)
+        } else {
+            alloc(layout)
+        };
 
         if ptr.is_null() {
             handle_alloc_error(layout);
         }
 
         CPUPtr {
-            ptr: ptr as *mut T,
+            ptr: ptr.cast(),
             len,
             flag,
+            align: layout.align(),
         }
     }
 }
 
+/// The layout of `len` elements of type `T`, which are aligned to at least `align` bytes.
+fn layout<T>(len: usize, align: usize) -> Layout {
+    Layout::array::<T>(len)
+        .unwrap()
+        .align_to(align)
+        .expect("The alignment must be a power of two.")
+}
+
 impl<T> Default for CPUPtr<T> {
     fn default() -> Self {
         Self {
             ptr: null_mut(),
             flag: AllocFlag::default(),
             len: 0,
+            align: align_of::<T>(),
         }
     }
 }
@@ -60,10 +100,8 @@ impl<T> Drop for CPUPtr<T> {
             return;
         }
 
-        let layout = Layout::array::<T>(self.len).unwrap();
-
         unsafe {
-            std::alloc::dealloc(self.ptr as *mut u8, layout);
+            dealloc(self.ptr as *mut u8, layout::<T>(self.len, self.align));
         }
     }
 }
@@ -88,7 +126,7 @@ impl<T> CommonPtrs<T> for CPUPtr<T> {
 
     #[inline]
     fn ptrs_mut(&mut self) -> (*mut T, *mut core::ffi::c_void, u64) {
-        (self.ptr as *mut T, null_mut(), 0)
+        (self.ptr, null_mut(), 0)
     }
 }
 
@@ -99,6 +137,7 @@ impl<T> ShallowCopy for CPUPtr<T> {
             ptr: self.ptr,
             len: self.len,
             flag: AllocFlag::Wrapper,
+            align: self.align,
         }
     }
 }
@@ -116,7 +155,7 @@ impl Drop for RawCpuBuf {
     fn drop(&mut self) {
         unsafe {
             let layout = Layout::from_size_align(self.len * self.size, self.align).unwrap();
-            std::alloc::dealloc(self.ptr, layout);
+            dealloc(self.ptr, layout);
         }
     }
 }
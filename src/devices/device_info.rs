@@ -0,0 +1,66 @@
+/// The backend of a device, as reported by [`DeviceInfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    CPU,
+    Stack,
+    OpenCL,
+    WGPU,
+}
+
+/// Describes the capabilities of a device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceProperties {
+    pub backend: Backend,
+    pub name: String,
+    pub vendor: String,
+    /// The size of the device memory in bytes, if it is known.
+    pub global_mem_size: Option<u64>,
+    /// The maximum size of a single allocation in bytes, if it is known.
+    pub max_alloc_size: Option<u64>,
+    /// Whether the device shares its memory with the host.
+    pub unified_mem: bool,
+    /// Whether the device supports `f64` calculations.
+    pub f64_support: bool,
+}
+
+/// Queries the capabilities of a device.
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use custos::{DeviceInfo, CPU};
+///
+/// let device = CPU::new();
+/// let info = device.info().unwrap();
+/// assert!(info.unified_mem && info.f64_support);
+/// ```
+pub trait DeviceInfo {
+    fn info(&self) -> crate::Result<DeviceProperties>;
+}
+
+/// Lists the properties of all devices available with the activated features, without creating any device.
+///
+/// The CPU is always listed first (if the `cpu` feature is enabled), followed by all OpenCL devices of all platforms
+/// ([`cl_devices`](crate::opencl::cl_devices)) and all WGPU adapters ([`wgpu_adapters`](crate::wgpu::wgpu_adapters)).
+/// Backends, which fail to enumerate their devices (e.g. without an installed OpenCL runtime), are skipped.
+#[allow(clippy::vec_init_then_push)]
+pub fn available_devices() -> Vec<DeviceProperties> {
+    #[allow(unused_mut)]
+    let mut devices = Vec::new();
+
+    #[cfg(feature = "cpu")]
+    devices.push(crate::cpu::cpu_properties());
+
+    #[cfg(feature = "opencl")]
+    if let Ok(cl_devices) = crate::opencl::cl_devices() {
+        devices.extend(cl_devices.into_iter().map(|entry| entry.props));
+    }
+
+    #[cfg(feature = "wgpu")]
+    devices.extend(
+        crate::wgpu::wgpu_adapters(wgpu::Backends::all())
+            .into_iter()
+            .map(|entry| entry.props),
+    );
+
+    devices
+}
@@ -1,30 +1,47 @@
+#[cfg(not(feature = "no-std"))]
 use core::cell::Cell;
-use std::thread_local;
+#[cfg(feature = "no-std")]
+use core::sync::atomic::{AtomicUsize, Ordering};
 
-thread_local! {
+#[cfg(not(feature = "no-std"))]
+std::thread_local! {
     pub static COUNT: Cell<usize> = Cell::new(0);
 }
 
+/// Without the standard library, there are no thread locals. Hence, the count is shared between all threads.
+/// Only atomic loads and stores are used, which are available on targets without compare-and-swap as well.
+#[cfg(feature = "no-std")]
+pub static COUNT: AtomicUsize = AtomicUsize::new(0);
+
 /// Sets current cache identifier / index.
 /// This function is usually called after an iteration in a loop -> [Count](crate::Count) or [range](crate::range)
 #[inline]
 pub fn set_count(count: usize) {
+    #[cfg(not(feature = "no-std"))]
     COUNT.with(|c| c.set(count));
+
+    #[cfg(feature = "no-std")]
+    COUNT.store(count, Ordering::Relaxed);
 }
 
 /// Returns current cache identifier / index
+#[cfg(not(feature = "no-std"))]
 #[inline]
 pub fn get_count() -> usize {
     COUNT.with(|c| c.get())
 }
 
+/// Returns current cache identifier / index
+#[cfg(feature = "no-std")]
+#[inline]
+pub fn get_count() -> usize {
+    COUNT.load(Ordering::Relaxed)
+}
+
 #[inline]
 /// Increases the cache identifier / index by 1.
 pub fn bump_count() {
-    COUNT.with(|c| {
-        let count = c.get();
-        c.set(count + 1);
-    })
+    set_count(get_count() + 1)
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -36,9 +53,9 @@ pub struct Ident {
 
 impl Ident {
     pub fn new(len: usize) -> Ident {
-        crate::COUNT.with(|count| Ident {
-            idx: count.get(),
+        Ident {
+            idx: get_count(),
             len,
-        })
+        }
     }
 }
@@ -0,0 +1,205 @@
+use core::cell::Cell;
+use std::path::{Path, PathBuf};
+
+use super::{MMapPtr, MapMode, Mapping};
+use crate::{
+    flag::AllocFlag, shape::Shape, Alloc, Buffer, CDatatype, Device, IsShapeIndep, MainMemory,
+    Read, WriteBuf,
+};
+
+/// A device whose buffers are memory mapped regions of files.
+/// Buffers, which are larger than the available RAM, can be used this way.
+///
+/// As [`MemMap`] implements [`MainMemory`], all `CPU` operations, which accept `D: MainMemory`, work directly on the mapped data.
+///
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use custos::{Buffer, ClearBuf, MemMap, CPU};
+///
+/// let device = MemMap::new(std::env::temp_dir());
+/// let mut buf = Buffer::from((&device, [1f32, 2., 3., 4.]));
+/// assert_eq!(buf.read(), [1., 2., 3., 4.]);
+///
+/// // a CPU operation running on mapped memory
+/// CPU::new().clear(&mut buf);
+/// assert_eq!(buf.read(), [0.; 4]);
+/// ```
+#[derive(Debug)]
+pub struct MemMap {
+    /// Buffers created via [`Alloc`] are stored in this directory. These files are removed when the buffer is dropped.
+    pub dir: PathBuf,
+    count: Cell<usize>,
+}
+
+impl MemMap {
+    /// Creates a [`MemMap`] device, which stores the files of allocated buffers in `dir`.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> MemMap {
+        MemMap {
+            dir: dir.into(),
+            count: Cell::new(0),
+        }
+    }
+
+    fn next_path(&self) -> PathBuf {
+        let count = self.count.get();
+        self.count.set(count + 1);
+
+        self.dir.join(format!(
+            "custos-{pid}-{device:p}-{count}.mmap",
+            pid = std::process::id(),
+            device = self
+        ))
+    }
+
+    /// Creates (or truncates) the file at `path` and returns a zeroed [`Buffer`] of length `len` mapped to it.
+    /// The file persists after the buffer is dropped and can be opened again with [`MemMap::open`].
+    /// # Example
+    /// ```
+    /// use custos::{MemMap, mmap::MapMode};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let path = std::env::temp_dir().join("custos_doc_create.mmap");
+    ///     let device = MemMap::new(std::env::temp_dir());
+    ///
+    ///     let mut buf = device.create::<f32>(&path, 3)?;
+    ///     buf.copy_from_slice(&[1., 2., 3.]);
+    ///     device.flush(&buf)?;
+    ///     drop(buf);
+    ///
+    ///     let buf = device.open::<f32>(&path, MapMode::ReadOnly)?;
+    ///     assert_eq!(buf.read(), [1., 2., 3.]);
+    ///     # drop(buf);
+    ///     # std::fs::remove_file(path)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn create<T: CDatatype>(
+        &self,
+        path: impl AsRef<Path>,
+        len: usize,
+    ) -> crate::Result<Buffer<'_, T, MemMap>> {
+        assert!(len > 0, "invalid buffer len: 0");
+        let mapping = Mapping::create::<T>(path.as_ref(), len, false)?;
+
+        Ok(Buffer {
+            ptr: MMapPtr::new(mapping, len, AllocFlag::None),
+            device: Some(self),
+            node: Default::default(),
+        })
+    }
+
+    /// Maps an existing file into memory.
+    /// The file must start with a header, which records the datatype and the length of the stored data.
+    ///
+    /// # Errors
+    /// - The file does not exist or can't be opened with the specified [`MapMode`]
+    /// - The header is missing or invalid
+    /// - The datatype recorded in the header is not `T`
+    pub fn open<T: CDatatype>(
+        &self,
+        path: impl AsRef<Path>,
+        mode: MapMode,
+    ) -> crate::Result<Buffer<'_, T, MemMap>> {
+        let (mapping, len) = Mapping::open::<T>(path.as_ref(), mode)?;
+
+        Ok(Buffer {
+            ptr: MMapPtr::new(mapping, len, AllocFlag::None),
+            device: Some(self),
+            node: Default::default(),
+        })
+    }
+
+    /// Writes outstanding modifications of the buffer back to its file.
+    ///
+    /// # Errors
+    /// - The buffer was opened with [`MapMode::ReadOnly`]
+    /// - The buffer does not own its mapping (e.g. it is a shallow copy)
+    /// - An I/O error occurred
+    pub fn flush<T, S: Shape>(&self, buf: &Buffer<T, MemMap, S>) -> crate::Result<()> {
+        buf.ptr
+            .mapping
+            .as_ref()
+            .ok_or(crate::DeviceError::MemMapFlush)?
+            .flush()
+    }
+}
+
+impl Device for MemMap {
+    type Ptr<U, S: Shape> = MMapPtr<U>;
+    type Cache = ();
+
+    fn new() -> crate::Result<Self> {
+        Ok(MemMap::new(std::env::temp_dir()))
+    }
+}
+
+impl IsShapeIndep for MemMap {}
+
+impl<T: CDatatype, S: Shape> Alloc<'_, T, S> for MemMap {
+    fn alloc(&self, mut len: usize, flag: AllocFlag) -> MMapPtr<T> {
+        assert!(len > 0, "invalid buffer len: 0");
+
+        if S::LEN > len {
+            len = S::LEN
+        }
+
+        let mapping = Mapping::create::<T>(&self.next_path(), len, true)
+            .expect("Could not create a memory mapped file.");
+        MMapPtr::new(mapping, len, flag)
+    }
+
+    fn with_slice(&self, data: &[T]) -> MMapPtr<T>
+    where
+        T: Clone,
+    {
+        assert!(!data.is_empty(), "invalid buffer len: 0");
+        let ptr = Alloc::<T, S>::alloc(self, data.len(), AllocFlag::None);
+        let slice = unsafe { std::slice::from_raw_parts_mut(ptr.ptr, data.len()) };
+        slice.clone_from_slice(data);
+
+        ptr
+    }
+}
+
+impl MainMemory for MemMap {
+    #[inline]
+    fn as_ptr<T, S: Shape>(ptr: &Self::Ptr<T, S>) -> *const T {
+        ptr.ptr
+    }
+
+    #[inline]
+    fn as_ptr_mut<T, S: Shape>(ptr: &mut Self::Ptr<T, S>) -> *mut T {
+        ptr.ptr
+    }
+}
+
+impl<T, S: Shape> Read<T, MemMap, S> for MemMap {
+    type Read<'a> = &'a [T] where T: 'a, S: 'a;
+
+    #[inline]
+    fn read<'a>(&self, buf: &'a Buffer<T, MemMap, S>) -> Self::Read<'a> {
+        buf.as_slice()
+    }
+
+    #[inline]
+    fn read_to_vec(&self, buf: &Buffer<T, MemMap, S>) -> Vec<T>
+    where
+        T: Default + Clone,
+    {
+        buf.to_vec()
+    }
+}
+
+impl<T: Copy, S: Shape> WriteBuf<T, MemMap, S> for MemMap {
+    #[inline]
+    fn write(&self, buf: &mut Buffer<T, MemMap, S>, data: &[T]) {
+        buf.copy_from_slice(data)
+    }
+
+    #[inline]
+    fn write_buf(&self, dst: &mut Buffer<T, Self, S>, src: &Buffer<T, Self, S>) {
+        dst.copy_from_slice(src)
+    }
+}
@@ -0,0 +1,298 @@
+use core::{mem::size_of, ptr::null_mut};
+use std::{
+    fs::{File, OpenOptions},
+    path::{Path, PathBuf},
+};
+
+use memmap2::{MmapMut, MmapOptions};
+
+pub use mmap_device::*;
+
+use crate::{flag::AllocFlag, CDatatype, CommonPtrs, DeviceError, PtrType, ShallowCopy};
+
+mod mmap_device;
+
+/// The size of the header in front of the data of every mapped file.
+/// 64 bytes keep the data aligned for every primitive (and most SIMD) type, because a mapping always starts at a page boundary.
+pub const HEADER_SIZE: usize = 64;
+
+const MAGIC: &[u8; 8] = b"CUSTOSMM";
+const VERSION: u32 = 1;
+
+/// Describes how a file is mapped into memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapMode {
+    /// The file is opened read-only. Writes to the buffer are private (copy-on-write) and never reach the file.
+    ReadOnly,
+    /// Writes to the buffer are written back to the file.
+    ReadWrite,
+}
+
+/// The header that is stored at the beginning of every file used by a [`MemMap`] device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapHeader {
+    /// The C type name of the stored elements, e.g. "float" (see [`CDatatype`]).
+    pub dtype: [u8; 16],
+    /// The size of one element in bytes.
+    pub elem_size: u32,
+    /// The number of stored elements.
+    pub len: u64,
+}
+
+impl MapHeader {
+    pub fn new<T: CDatatype>(len: usize) -> MapHeader {
+        let mut dtype = [0; 16];
+        let name = T::as_c_type_str().as_bytes();
+        dtype[..name.len()].copy_from_slice(name);
+
+        MapHeader {
+            dtype,
+            elem_size: size_of::<T>() as u32,
+            len: len as u64,
+        }
+    }
+
+    /// Returns the C type name of the stored elements.
+    pub fn dtype(&self) -> &str {
+        let end = self.dtype.iter().position(|&b| b == 0).unwrap_or(16);
+        core::str::from_utf8(&self.dtype[..end]).unwrap_or_default()
+    }
+
+    /// Checks whether the header describes elements of type `T`.
+    pub fn matches<T: CDatatype>(&self) -> bool {
+        self.dtype() == T::as_c_type_str() && self.elem_size as usize == size_of::<T>()
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[..8].copy_from_slice(MAGIC);
+        bytes[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.elem_size.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.len.to_le_bytes());
+        bytes[24..40].copy_from_slice(&self.dtype);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> crate::Result<MapHeader> {
+        if bytes.len() < HEADER_SIZE || &bytes[..8] != MAGIC {
+            return Err(DeviceError::MemMapHeader.into());
+        }
+
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(DeviceError::MemMapHeader.into());
+        }
+
+        Ok(MapHeader {
+            elem_size: u32::from_le_bytes(bytes[12..16].try_into().unwrap()),
+            len: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+            dtype: bytes[24..40].try_into().unwrap(),
+        })
+    }
+}
+
+/// Owns the mapping of a file.
+#[derive(Debug)]
+pub struct Mapping {
+    map: Option<MmapMut>,
+    mode: MapMode,
+    path: PathBuf,
+    // files created by `Alloc` are only used as scratch memory and are removed on drop
+    temporary: bool,
+}
+
+impl Mapping {
+    /// Creates (or truncates) the file at `path` and maps `len` zeroed elements of type `T` into memory.
+    pub fn create<T: CDatatype>(path: &Path, len: usize, temporary: bool) -> crate::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+
+        // the file is zero-filled by set_len
+        file.set_len((HEADER_SIZE + len * size_of::<T>()) as u64)?;
+
+        let mut map = unsafe { MmapOptions::new().map_mut(&file)? };
+        map[..HEADER_SIZE].copy_from_slice(&MapHeader::new::<T>(len).to_bytes());
+
+        Ok(Mapping {
+            map: Some(map),
+            mode: MapMode::ReadWrite,
+            path: path.to_path_buf(),
+            temporary,
+        })
+    }
+
+    /// Maps an existing file, which starts with a valid [`MapHeader`] describing elements of type `T`.
+    pub fn open<T: CDatatype>(path: &Path, mode: MapMode) -> crate::Result<(Self, usize)> {
+        let file = match mode {
+            MapMode::ReadOnly => File::open(path)?,
+            MapMode::ReadWrite => OpenOptions::new().read(true).write(true).open(path)?,
+        };
+
+        let map = unsafe {
+            match mode {
+                // a private mapping: writes do not reach the file (and do not fault)
+                MapMode::ReadOnly => MmapOptions::new().map_copy(&file)?,
+                MapMode::ReadWrite => MmapOptions::new().map_mut(&file)?,
+            }
+        };
+
+        let header = MapHeader::from_bytes(&map)?;
+        if !header.matches::<T>() {
+            return Err(DeviceError::MemMapDatatype.into());
+        }
+
+        // the length is read from the file, hence it must not overflow the size calculation
+        let len = usize::try_from(header.len).map_err(|_| DeviceError::MemMapHeader)?;
+        let size = len
+            .checked_mul(size_of::<T>())
+            .and_then(|bytes| bytes.checked_add(HEADER_SIZE))
+            .ok_or(DeviceError::MemMapHeader)?;
+
+        if map.len() < size {
+            return Err(DeviceError::MemMapHeader.into());
+        }
+
+        Ok((
+            Mapping {
+                map: Some(map),
+                mode,
+                path: path.to_path_buf(),
+                temporary: false,
+            },
+            len,
+        ))
+    }
+
+    #[inline]
+    pub fn mode(&self) -> MapMode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the pointer to the data behind the header.
+    #[inline]
+    pub fn data_ptr<T>(&self) -> *mut T {
+        let map = self.map.as_ref().unwrap();
+        unsafe { map.as_ptr().add(HEADER_SIZE) as *mut T }
+    }
+
+    /// Writes outstanding modifications back to the file.
+    pub fn flush(&self) -> crate::Result<()> {
+        if self.mode == MapMode::ReadOnly {
+            return Err(DeviceError::MemMapFlush.into());
+        }
+        self.map.as_ref().unwrap().flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // unmap before removing the file
+        drop(self.map.take());
+
+        if self.temporary {
+            std::fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+/// The pointer type of the [`MemMap`] device.
+#[derive(Debug)]
+pub struct MMapPtr<T> {
+    pub ptr: *mut T,
+    pub len: usize,
+    pub flag: AllocFlag,
+    /// `None` for shallow copies, which do not own the mapping.
+    pub mapping: Option<Mapping>,
+}
+
+impl<T> MMapPtr<T> {
+    pub fn new(mapping: Mapping, len: usize, flag: AllocFlag) -> MMapPtr<T> {
+        MMapPtr {
+            ptr: mapping.data_ptr(),
+            len,
+            flag,
+            mapping: Some(mapping),
+        }
+    }
+}
+
+impl<T> Default for MMapPtr<T> {
+    fn default() -> Self {
+        Self {
+            ptr: null_mut(),
+            len: 0,
+            flag: AllocFlag::default(),
+            mapping: None,
+        }
+    }
+}
+
+impl<T> PtrType for MMapPtr<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn flag(&self) -> AllocFlag {
+        self.flag
+    }
+}
+
+impl<T> CommonPtrs<T> for MMapPtr<T> {
+    #[inline]
+    fn ptrs(&self) -> (*const T, *mut core::ffi::c_void, u64) {
+        (self.ptr, null_mut(), 0)
+    }
+
+    #[inline]
+    fn ptrs_mut(&mut self) -> (*mut T, *mut core::ffi::c_void, u64) {
+        (self.ptr, null_mut(), 0)
+    }
+}
+
+impl<T> ShallowCopy for MMapPtr<T> {
+    #[inline]
+    unsafe fn shallow(&self) -> Self {
+        MMapPtr {
+            ptr: self.ptr,
+            len: self.len,
+            flag: AllocFlag::Wrapper,
+            mapping: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MapHeader, HEADER_SIZE};
+
+    #[test]
+    fn test_header_roundtrip() {
+        let header = MapHeader::new::<f32>(12);
+        let bytes = header.to_bytes();
+        assert_eq!(bytes.len(), HEADER_SIZE);
+
+        let parsed = MapHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, header);
+        assert_eq!(parsed.dtype(), "float");
+        assert!(parsed.matches::<f32>());
+        assert!(!parsed.matches::<i32>());
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        assert!(MapHeader::from_bytes(&[0; HEADER_SIZE]).is_err());
+        assert!(MapHeader::from_bytes(b"CUSTOSMM").is_err());
+    }
+}
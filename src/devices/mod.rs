@@ -11,10 +11,8 @@ use crate::{shape::Shape, AddGraph, Alloc, Buffer, Device};
 #[cfg(feature = "cuda")]
 use cuda::api::cublas::{cublasDgemm_v2, cublasOperation_t, cublasSgemm_v2, CublasHandle};
 
-#[cfg(not(feature = "no-std"))]
 pub mod cache;
 //pub mod cache;
-#[cfg(not(feature = "no-std"))]
 pub use cache::*;
 //pub use cache::{Cache, CacheReturn};
 
@@ -36,15 +34,39 @@ pub mod wgpu;
 #[cfg(feature = "network")]
 pub mod network;
 
+#[cfg(feature = "mmap")]
+pub mod mmap;
+
+#[cfg(feature = "trace")]
+pub mod trace;
+
+#[cfg(feature = "sim-gpu")]
+pub mod sim_gpu;
+
+#[cfg(feature = "arena")]
+pub mod arena;
+
+#[cfg(not(feature = "no-std"))]
+#[cfg(any(
+    feature = "cpu",
+    feature = "opencl",
+    feature = "cuda",
+    feature = "wgpu"
+))]
+pub mod any;
+
+#[cfg(not(feature = "no-std"))]
+mod device_info;
+#[cfg(not(feature = "no-std"))]
+pub use device_info::*;
+
 mod stack_array;
 pub use stack_array::*;
 
 mod cdatatype;
 pub use cdatatype::*;
 
-#[cfg(not(feature = "no-std"))]
 mod ident;
-#[cfg(not(feature = "no-std"))]
 pub use ident::*;
 
 #[cfg(feature = "cuda")]
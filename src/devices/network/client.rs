@@ -0,0 +1,79 @@
+use std::{
+    io::{BufReader, BufWriter, Write},
+    net::{TcpStream, ToSocketAddrs},
+};
+
+use super::protocol::{read_bytes, read_status, read_u64, Request};
+
+/// The connection of a [`Network`](super::Network) device to a server.
+#[derive(Debug)]
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+}
+
+impl Client {
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> crate::Result<Client> {
+        let stream = TcpStream::connect(addr)?;
+        // requests are small and answered immediately
+        stream.set_nodelay(true)?;
+
+        Ok(Client {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: BufWriter::new(stream),
+        })
+    }
+
+    /// Sends `request` and waits for the status of the response.
+    /// The response payload can be read from `self.reader` afterwards.
+    fn request(&mut self, request: &Request) -> crate::Result<()> {
+        request.write_to(&mut self.writer)?;
+        self.writer.flush()?;
+        read_status(&mut self.reader)
+    }
+
+    /// Allocates `bytes` zeroed bytes on the server and returns the id of the buffer.
+    pub fn alloc(&mut self, bytes: usize) -> crate::Result<u64> {
+        self.request(&Request::Alloc {
+            bytes: bytes as u64,
+        })?;
+        Ok(read_u64(&mut self.reader)?)
+    }
+
+    pub fn free(&mut self, id: u64) -> crate::Result<()> {
+        self.request(&Request::Free { id })
+    }
+
+    pub fn read(&mut self, id: u64) -> crate::Result<Vec<u8>> {
+        self.request(&Request::Read { id })?;
+        Ok(read_bytes(&mut self.reader)?)
+    }
+
+    pub fn write(&mut self, id: u64, data: &[u8]) -> crate::Result<()> {
+        self.request(&Request::Write {
+            id,
+            data: data.to_vec(),
+        })
+    }
+
+    pub fn clear(&mut self, id: u64) -> crate::Result<()> {
+        self.request(&Request::Clear { id })
+    }
+
+    pub fn copy_slice(
+        &mut self,
+        source: u64,
+        source_offset: usize,
+        dest: u64,
+        dest_offset: usize,
+        bytes: usize,
+    ) -> crate::Result<()> {
+        self.request(&Request::CopySlice {
+            source,
+            source_offset: source_offset as u64,
+            dest,
+            dest_offset: dest_offset as u64,
+            bytes: bytes as u64,
+        })
+    }
+}
@@ -1,3 +1,100 @@
-mod network_device;
+use core::{cell::RefCell, marker::PhantomData, ptr::null_mut};
+use std::rc::Rc;
 
+pub use client::Client;
 pub use network_device::*;
+pub use server::*;
+
+use crate::{flag::AllocFlag, CommonPtrs, PtrType, ShallowCopy};
+
+mod client;
+mod network_device;
+pub mod protocol;
+mod server;
+
+/// The pointer type of the [`Network`] device.
+/// It identifies a buffer on the server.
+#[derive(Debug)]
+pub struct NetworkArray<T> {
+    pub id: u64,
+    pub len: usize,
+    pub flag: AllocFlag,
+    client: Option<Rc<RefCell<Client>>>,
+    _p: PhantomData<T>,
+}
+
+impl<T> NetworkArray<T> {
+    pub fn new(client: &Rc<RefCell<Client>>, id: u64, len: usize, flag: AllocFlag) -> Self {
+        NetworkArray {
+            id,
+            len,
+            flag,
+            client: Some(client.clone()),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for NetworkArray<T> {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            len: 0,
+            flag: AllocFlag::default(),
+            client: None,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for NetworkArray<T> {
+    fn drop(&mut self) {
+        if self.flag != AllocFlag::None {
+            return;
+        }
+
+        if let Some(client) = &self.client {
+            // the server frees all buffers of a client anyway, when the connection is closed
+            if let Ok(mut client) = client.try_borrow_mut() {
+                client.free(self.id).ok();
+            }
+        }
+    }
+}
+
+impl<T> PtrType for NetworkArray<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn flag(&self) -> AllocFlag {
+        self.flag
+    }
+}
+
+impl<T> CommonPtrs<T> for NetworkArray<T> {
+    #[inline]
+    fn ptrs(&self) -> (*const T, *mut core::ffi::c_void, u64) {
+        (core::ptr::null(), null_mut(), self.id)
+    }
+
+    #[inline]
+    fn ptrs_mut(&mut self) -> (*mut T, *mut core::ffi::c_void, u64) {
+        (null_mut(), null_mut(), self.id)
+    }
+}
+
+impl<T> ShallowCopy for NetworkArray<T> {
+    #[inline]
+    unsafe fn shallow(&self) -> Self {
+        NetworkArray {
+            id: self.id,
+            len: self.len,
+            flag: AllocFlag::Wrapper,
+            client: self.client.clone(),
+            _p: PhantomData,
+        }
+    }
+}
@@ -1,113 +1,203 @@
-use crate::{
-    Alloc, Buffer, ClearBuf, Dealloc, Device, DeviceError, Error, Graph, GraphReturn, Read,
-};
 use core::{
     cell::{RefCell, RefMut},
-    marker::PhantomData,
+    mem::{size_of, size_of_val},
+    ops::{Range, RangeBounds},
+};
+use std::{net::ToSocketAddrs, rc::Rc};
+
+use super::{Client, NetworkArray};
+use crate::{
+    bounds_to_range, flag::AllocFlag, shape::Shape, Alloc, Buffer, ClearBuf, CopySlice, Device,
+    DeviceError, Graph, GraphReturn, IsShapeIndep, Read, WriteBuf,
 };
-use cuw::AsDataType;
-use cuwanto_server as cuw;
-use std::net::ToSocketAddrs;
 
-pub struct Network<'a> {
-    pub cuw_client: RefCell<cuw::client::Client>,
+/// A device whose buffers are stored on a remote server (see [`serve`](super::serve) and the `custos-server` binary).
+///
+/// As all operations are sent over TCP, a failing connection causes a panic.
+///
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use std::net::TcpListener;
+/// use custos::{network::serve, Buffer, ClearBuf, Network, CPU};
+///
+/// fn main() -> custos::Result<()> {
+///     let listener = TcpListener::bind("127.0.0.1:0")?;
+///     let addr = listener.local_addr()?;
+///     std::thread::spawn(move || serve::<CPU>(listener));
+///
+///     let device = Network::new(addr)?;
+///
+///     let mut buf = Buffer::from((&device, [1f32, 2., 3.]));
+///     assert_eq!(buf.read(), vec![1., 2., 3.]);
+///
+///     device.clear(&mut buf);
+///     assert_eq!(buf.read(), vec![0.; 3]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Network {
+    pub client: Rc<RefCell<Client>>,
     pub graph: RefCell<Graph>,
-    _p: PhantomData<&'a u8>,
 }
 
-impl<'a> Network<'a> {
-    pub fn new<A: ToSocketAddrs>(addr: A, device: cuw::DeviceType) -> crate::Result<Network<'a>> {
-        let mut cuw_client = cuw::client::Client::connect(addr).map_err(|error| Error { error })?;
-
-        // should receive error if this is missing
-        cuw_client
-            .create_device(device, 0)
-            .map_err(|error| Error { error })?;
-
+impl Network {
+    /// Connects to the server at `addr`.
+    pub fn new<A: ToSocketAddrs>(addr: A) -> crate::Result<Network> {
         Ok(Network {
-            cuw_client: RefCell::new(cuw_client),
+            client: Rc::new(RefCell::new(Client::connect(addr)?)),
             graph: RefCell::new(Graph::new()),
-            _p: PhantomData,
         })
     }
+
+    #[inline]
+    fn client(&self) -> RefMut<'_, Client> {
+        self.client.borrow_mut()
+    }
 }
 
-impl<'a> Device for Network<'a> {
-    type Ptr<U, const N: usize> = NetworkArray<'a, U>;
-    type Cache<const N: usize> = ();
+impl Device for Network {
+    type Ptr<U, S: Shape> = NetworkArray<U>;
+    type Cache = ();
 
     fn new() -> crate::Result<Self> {
         Err(DeviceError::MissingAddress.into())
     }
 }
 
-impl<'a, T: cuw::AsDataType> Alloc<'a, T> for Network<'a> {
-    fn alloc(&'a self, len: usize) -> <Self as Device>::Ptr<T, 0> {
-        let id = self
-            .cuw_client
-            .borrow_mut()
-            .alloc_buf::<T>(len as u32)
-            .unwrap();
-
-        NetworkArray {
-            id,
-            client: &self.cuw_client,
-            _p: PhantomData,
+impl IsShapeIndep for Network {}
+
+impl GraphReturn for Network {
+    #[inline]
+    fn graph(&self) -> RefMut<'_, Graph> {
+        self.graph.borrow_mut()
+    }
+}
+
+fn as_bytes<T: Copy>(data: &[T]) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(data.as_ptr().cast(), size_of_val(data)) }
+}
+
+fn from_bytes<T: Copy>(bytes: &[u8], len: usize) -> crate::Result<Vec<T>> {
+    if bytes.len() != len * size_of::<T>() {
+        return Err(DeviceError::NetworkProtocol.into());
+    }
+
+    let mut data = Vec::<T>::with_capacity(len);
+    unsafe {
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), data.as_mut_ptr().cast(), bytes.len());
+        data.set_len(len);
+    }
+    Ok(data)
+}
+
+impl<T: Copy, S: Shape> Alloc<'_, T, S> for Network {
+    fn alloc(&self, mut len: usize, flag: AllocFlag) -> NetworkArray<T> {
+        assert!(len > 0, "invalid buffer len: 0");
+
+        if S::LEN > len {
+            len = S::LEN
         }
+
+        let id = self
+            .client()
+            .alloc(len * size_of::<T>())
+            .expect("Could not allocate a buffer on the remote device.");
+        NetworkArray::new(&self.client, id, len, flag)
     }
 
-    fn with_slice(&'a self, data: &[T]) -> <Self as Device>::Ptr<T, 0>
+    fn with_slice(&self, data: &[T]) -> NetworkArray<T>
     where
         T: Clone,
     {
-        let array: NetworkArray<T> = self.alloc(data.len());
-        self.cuw_client
-            .borrow_mut()
-            .write_buf(array.id, data)
-            .unwrap();
+        let array = Alloc::<T, S>::alloc(self, data.len(), AllocFlag::None);
+        self.client()
+            .write(array.id, as_bytes(data))
+            .expect("Could not write to the remote device.");
         array
     }
 }
 
-impl<'a> GraphReturn for Network<'a> {
+impl<T: Copy + Default, S: Shape> Read<T, Network, S> for Network {
+    type Read<'a> = Vec<T> where T: 'a, S: 'a;
+
     #[inline]
-    fn graph(&self) -> RefMut<Graph> {
-        self.graph.borrow_mut()
+    fn read(&self, buf: &Buffer<T, Network, S>) -> Vec<T> {
+        self.read_to_vec(buf)
     }
-}
 
-impl<'b, T: AsDataType + Clone + Default> Read<T, Network<'b>> for Network<'b> {
-    type Read<'a> = Vec<T>
-    where
-        T: 'a,
-        Network<'b>: 'a;
+    fn read_to_vec(&self, buf: &Buffer<T, Network, S>) -> Vec<T> {
+        let bytes = self
+            .client()
+            .read(buf.ptr.id)
+            .expect("Could not read from the remote device.");
+        from_bytes(&bytes, buf.len()).unwrap()
+    }
+}
 
-    fn read<'a>(&self, buf: &'a Buffer<T, Network<'b>>) -> Self::Read<'a> {
-        self.read_to_vec(buf)
+impl<T: Copy, S: Shape> WriteBuf<T, Network, S> for Network {
+    fn write(&self, buf: &mut Buffer<T, Network, S>, data: &[T]) {
+        self.client()
+            .write(buf.ptr.id, as_bytes(data))
+            .expect("Could not write to the remote device.");
     }
 
-    fn read_to_vec(&self, buf: &Buffer<T, Network>) -> Vec<T>
-    where
-        T: Default + Clone,
-    {
-        self.cuw_client.borrow_mut().read_buf(buf.ptr.id).unwrap()
+    fn write_buf(&self, dst: &mut Buffer<T, Self, S>, src: &Buffer<T, Self, S>) {
+        self.client()
+            .copy_slice(src.ptr.id, 0, dst.ptr.id, 0, src.len() * size_of::<T>())
+            .expect("Could not copy on the remote device.");
     }
 }
 
-impl<'a, T> ClearBuf<T, Network<'a>> for Network<'a> {
-    fn clear(&self, buf: &mut Buffer<T, Network<'a>>) {
-        todo!()
+impl<T, S: Shape> ClearBuf<T, Network, S> for Network {
+    /// Sets all bytes of the buffer to zero.
+    fn clear(&self, buf: &mut Buffer<T, Network, S>) {
+        self.client()
+            .clear(buf.ptr.id)
+            .expect("Could not clear a buffer on the remote device.");
     }
 }
 
-pub struct NetworkArray<'a, T> {
-    id: cuw::client::BufId,
-    client: &'a RefCell<cuw::client::Client>,
-    _p: PhantomData<T>,
-}
+impl<T> CopySlice<T> for Network {
+    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, Network>,
+        source_range: SR,
+        dest: &mut Buffer<T, Network>,
+        dest_range: DR,
+    ) {
+        let source_range = bounds_to_range(source_range, source.len());
+        let dest_range = bounds_to_range(dest_range, dest.len());
+
+        assert_eq!(
+            source_range.end - source_range.start,
+            dest_range.end - dest_range.start,
+        );
+
+        self.copy_slice_all(source, dest, [(source_range, dest_range)]);
+    }
 
-impl<'a, T> Dealloc<T> for NetworkArray<'a, T> {
-    unsafe fn dealloc(&mut self, _len: usize) {
-        self.client.borrow_mut().dealloc_buf(self.id).unwrap();
+    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
+        &self,
+        source: &Buffer<T, Network>,
+        dest: &mut Buffer<T, Network>,
+        ranges: I,
+    ) {
+        let mut client = self.client();
+
+        for (source_range, dest_range) in ranges {
+            assert_eq!(source_range.len(), dest_range.len());
+
+            client
+                .copy_slice(
+                    source.ptr.id,
+                    source_range.start * size_of::<T>(),
+                    dest.ptr.id,
+                    dest_range.start * size_of::<T>(),
+                    source_range.len() * size_of::<T>(),
+                )
+                .expect("Could not copy on the remote device.");
+        }
     }
 }
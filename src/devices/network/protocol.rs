@@ -0,0 +1,302 @@
+//! The binary protocol spoken between a [`Network`](super::Network) device and a [`custos-server`](super::serve).
+//!
+//! Every request starts with a one byte [`Op`], followed by its arguments.
+//! Integers are encoded as little endian `u64`s, byte strings are prefixed by their length.
+//!
+//! | op          | arguments                                   | response payload |
+//! |-------------|---------------------------------------------|------------------|
+//! | `Alloc`     | bytes                                       | id               |
+//! | `Free`      | id                                          | -                |
+//! | `Read`      | id                                          | data             |
+//! | `Write`     | id, data                                    | -                |
+//! | `Clear`     | id                                          | -                |
+//! | `CopySlice` | source id, source offset, dest id, dest offset, bytes | -      |
+//!
+//! Every response starts with a status byte: [`STATUS_OK`] is followed by the response payload,
+//! [`STATUS_ERR`] by an error message.
+//!
+//! The server only stores bytes, it never interprets the element type of a buffer.
+//! Byte strings and buffers are limited to [`MAX_MESSAGE_SIZE`] bytes, as their sizes are chosen by the peer.
+
+use std::io::{self, Read, Write};
+
+use crate::DeviceError;
+
+pub const STATUS_OK: u8 = 0;
+pub const STATUS_ERR: u8 = 1;
+
+/// The maximum size of a byte string and of a single buffer in bytes (1 GiB).
+pub const MAX_MESSAGE_SIZE: u64 = 1 << 30;
+
+/// The operations a server can execute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Op {
+    Alloc = 0,
+    Free = 1,
+    Read = 2,
+    Write = 3,
+    Clear = 4,
+    CopySlice = 5,
+}
+
+impl TryFrom<u8> for Op {
+    type Error = DeviceError;
+
+    fn try_from(op: u8) -> Result<Self, Self::Error> {
+        Ok(match op {
+            0 => Op::Alloc,
+            1 => Op::Free,
+            2 => Op::Read,
+            3 => Op::Write,
+            4 => Op::Clear,
+            5 => Op::CopySlice,
+            _ => return Err(DeviceError::NetworkProtocol),
+        })
+    }
+}
+
+/// A request sent from a [`Network`](super::Network) device to a server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Alloc {
+        bytes: u64,
+    },
+    Free {
+        id: u64,
+    },
+    Read {
+        id: u64,
+    },
+    Write {
+        id: u64,
+        data: Vec<u8>,
+    },
+    Clear {
+        id: u64,
+    },
+    CopySlice {
+        source: u64,
+        source_offset: u64,
+        dest: u64,
+        dest_offset: u64,
+        bytes: u64,
+    },
+}
+
+impl Request {
+    pub fn op(&self) -> Op {
+        match self {
+            Request::Alloc { .. } => Op::Alloc,
+            Request::Free { .. } => Op::Free,
+            Request::Read { .. } => Op::Read,
+            Request::Write { .. } => Op::Write,
+            Request::Clear { .. } => Op::Clear,
+            Request::CopySlice { .. } => Op::CopySlice,
+        }
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        write_u8(writer, self.op() as u8)?;
+
+        match self {
+            Request::Alloc { bytes } => write_u64(writer, *bytes),
+            Request::Free { id } | Request::Read { id } | Request::Clear { id } => {
+                write_u64(writer, *id)
+            }
+            Request::Write { id, data } => {
+                write_u64(writer, *id)?;
+                write_bytes(writer, data)
+            }
+            Request::CopySlice {
+                source,
+                source_offset,
+                dest,
+                dest_offset,
+                bytes,
+            } => {
+                for value in [*source, *source_offset, *dest, *dest_offset, *bytes] {
+                    write_u64(writer, value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Reads the next request.
+    /// Returns `Ok(None)` if the connection was closed before a new request was started.
+    pub fn read_from(reader: &mut impl Read) -> crate::Result<Option<Request>> {
+        let op = match read_u8(reader) {
+            Ok(op) => Op::try_from(op)?,
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let request = match op {
+            Op::Alloc => Request::Alloc {
+                bytes: read_u64(reader)?,
+            },
+            Op::Free => Request::Free {
+                id: read_u64(reader)?,
+            },
+            Op::Read => Request::Read {
+                id: read_u64(reader)?,
+            },
+            Op::Write => Request::Write {
+                id: read_u64(reader)?,
+                data: read_bytes(reader)?,
+            },
+            Op::Clear => Request::Clear {
+                id: read_u64(reader)?,
+            },
+            Op::CopySlice => Request::CopySlice {
+                source: read_u64(reader)?,
+                source_offset: read_u64(reader)?,
+                dest: read_u64(reader)?,
+                dest_offset: read_u64(reader)?,
+                bytes: read_u64(reader)?,
+            },
+        };
+        Ok(Some(request))
+    }
+}
+
+#[inline]
+pub fn write_u8(writer: &mut impl Write, value: u8) -> io::Result<()> {
+    writer.write_all(&[value])
+}
+
+#[inline]
+pub fn write_u64(writer: &mut impl Write, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+pub fn write_bytes(writer: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    write_u64(writer, bytes.len() as u64)?;
+    writer.write_all(bytes)
+}
+
+#[inline]
+pub fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut value = [0; 1];
+    reader.read_exact(&mut value)?;
+    Ok(value[0])
+}
+
+#[inline]
+pub fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut value = [0; 8];
+    reader.read_exact(&mut value)?;
+    Ok(u64::from_le_bytes(value))
+}
+
+/// Reads a byte string, which is at most [`MAX_MESSAGE_SIZE`] bytes long.
+pub fn read_bytes(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let len = read_u64(reader)?;
+    if len > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("A message of {len} bytes exceeds the limit of {MAX_MESSAGE_SIZE} bytes."),
+        ));
+    }
+
+    // the memory grows with the received data, instead of trusting the announced length
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+
+    if bytes.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+/// Reads the status byte of a response.
+/// Returns the error message of the server as an error.
+pub fn read_status(reader: &mut impl Read) -> crate::Result<()> {
+    match read_u8(reader)? {
+        STATUS_OK => Ok(()),
+        STATUS_ERR => {
+            let message =
+                String::from_utf8(read_bytes(reader)?).map_err(|_| DeviceError::NetworkProtocol)?;
+            Err(message.into())
+        }
+        _ => Err(DeviceError::NetworkProtocol.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        read_bytes, read_status, read_u64, write_bytes, write_u64, Op, Request, MAX_MESSAGE_SIZE,
+        STATUS_ERR,
+    };
+
+    #[test]
+    fn test_roundtrip() {
+        let mut message = Vec::new();
+        write_u64(&mut message, 42).unwrap();
+        write_bytes(&mut message, &[1, 2, 3]).unwrap();
+
+        let mut reader = &message[..];
+        assert_eq!(read_u64(&mut reader).unwrap(), 42);
+        assert_eq!(read_bytes(&mut reader).unwrap(), [1, 2, 3]);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_read_bytes_limit() {
+        let mut message = Vec::new();
+        write_u64(&mut message, MAX_MESSAGE_SIZE + 1).unwrap();
+        assert!(read_bytes(&mut &message[..]).is_err());
+
+        // the announced length is not sent
+        let mut message = Vec::new();
+        write_u64(&mut message, 1024).unwrap();
+        message.extend_from_slice(&[1, 2, 3]);
+        assert!(read_bytes(&mut &message[..]).is_err());
+    }
+
+    #[test]
+    fn test_request_roundtrip() {
+        let requests = [
+            Request::Alloc { bytes: 16 },
+            Request::Write {
+                id: 3,
+                data: vec![1, 2, 3, 4],
+            },
+            Request::CopySlice {
+                source: 1,
+                source_offset: 4,
+                dest: 2,
+                dest_offset: 0,
+                bytes: 8,
+            },
+        ];
+
+        let mut message = Vec::new();
+        for request in &requests {
+            request.write_to(&mut message).unwrap();
+        }
+
+        let mut reader = &message[..];
+        for request in requests {
+            assert_eq!(Request::read_from(&mut reader).unwrap(), Some(request));
+        }
+        assert_eq!(Request::read_from(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_error_status() {
+        let mut message = vec![STATUS_ERR];
+        write_bytes(&mut message, b"unknown buffer").unwrap();
+
+        let err = read_status(&mut &message[..]).unwrap_err();
+        assert_eq!(err.to_string(), "unknown buffer");
+    }
+
+    #[test]
+    fn test_op_from_u8() {
+        assert_eq!(Op::try_from(5), Ok(Op::CopySlice));
+        assert!(Op::try_from(6).is_err());
+    }
+}
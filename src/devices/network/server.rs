@@ -0,0 +1,215 @@
+use std::{
+    collections::HashMap,
+    io::{BufReader, BufWriter, Write},
+    net::{TcpListener, TcpStream},
+};
+
+use super::protocol::{
+    write_bytes, write_u64, write_u8, Request, MAX_MESSAGE_SIZE, STATUS_ERR, STATUS_OK,
+};
+use crate::{Alloc, Buffer, ClearBuf, CopySlice, Device, Read, WriteBuf};
+
+/// The operations a device must support to be hosted by a server.
+pub trait ServeAble:
+    Device + for<'a> Alloc<'a, u8> + Read<u8> + WriteBuf<u8> + ClearBuf<u8> + CopySlice<u8>
+{
+}
+
+impl<D> ServeAble for D where
+    D: Device + for<'a> Alloc<'a, u8> + Read<u8> + WriteBuf<u8> + ClearBuf<u8> + CopySlice<u8>
+{
+}
+
+/// Accepts connections of [`Network`](super::Network) devices.
+/// Every connection is handled in its own thread, which creates its own device `D` via [`Device::new`].
+///
+/// This function only returns if accepting a connection fails.
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```no_run")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use std::net::TcpListener;
+/// use custos::{network::serve, CPU};
+///
+/// let listener = TcpListener::bind("127.0.0.1:11001").unwrap();
+/// serve::<CPU>(listener).unwrap();
+/// ```
+pub fn serve<D: ServeAble + 'static>(listener: TcpListener) -> crate::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        std::thread::spawn(move || {
+            // a failing connection only ends its own thread
+            handle_connection::<D>(stream).ok();
+        });
+    }
+    Ok(())
+}
+
+/// Executes the requests of a single client until the connection is closed.
+pub fn handle_connection<D: ServeAble>(stream: TcpStream) -> crate::Result<()> {
+    let device = D::new()?;
+    let mut host = Host::new(&device);
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = BufWriter::new(stream);
+
+    while let Some(request) = Request::read_from(&mut reader)? {
+        let mut payload = Vec::new();
+
+        match host.execute(request, &mut payload) {
+            Ok(()) => {
+                write_u8(&mut writer, STATUS_OK)?;
+                writer.write_all(&payload)?;
+            }
+            Err(err) => {
+                write_u8(&mut writer, STATUS_ERR)?;
+                write_bytes(&mut writer, err.to_string().as_bytes())?;
+            }
+        }
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// The default limit of the bytes, which a single client may allocate at the same time (4 GiB).
+pub const DEFAULT_HOST_LIMIT: u64 = 1 << 32;
+
+/// Stores the buffers of a client.
+pub struct Host<'a, D: Device> {
+    device: &'a D,
+    buffers: HashMap<u64, Buffer<'a, u8, D>>,
+    next_id: u64,
+    allocated: u64,
+    limit: u64,
+}
+
+impl<'a, D: ServeAble> Host<'a, D> {
+    #[inline]
+    pub fn new(device: &'a D) -> Self {
+        Host::with_limit(device, DEFAULT_HOST_LIMIT)
+    }
+
+    /// A host, whose buffers are at most `limit` bytes large in total.
+    pub fn with_limit(device: &'a D, limit: u64) -> Self {
+        Host {
+            device,
+            buffers: HashMap::new(),
+            next_id: 0,
+            allocated: 0,
+            limit,
+        }
+    }
+
+    fn buf(&self, id: u64) -> crate::Result<&Buffer<'a, u8, D>> {
+        self.buffers
+            .get(&id)
+            .ok_or_else(|| format!("Unknown buffer id: {id}").into())
+    }
+
+    fn buf_mut(&mut self, id: u64) -> crate::Result<&mut Buffer<'a, u8, D>> {
+        self.buffers
+            .get_mut(&id)
+            .ok_or_else(|| format!("Unknown buffer id: {id}").into())
+    }
+
+    /// Executes `request` and writes the response payload to `payload`.
+    pub fn execute(&mut self, request: Request, payload: &mut Vec<u8>) -> crate::Result<()> {
+        match request {
+            Request::Alloc { bytes } => {
+                if bytes == 0 {
+                    return Err("Cannot allocate a buffer of 0 bytes.".into());
+                }
+
+                if bytes > MAX_MESSAGE_SIZE {
+                    return Err(format!(
+                        "Cannot allocate {bytes} bytes, a buffer is limited to {MAX_MESSAGE_SIZE} bytes."
+                    )
+                    .into());
+                }
+
+                if self.allocated + bytes > self.limit {
+                    return Err(format!(
+                        "Cannot allocate {bytes} bytes, {} of {} bytes are already allocated.",
+                        self.allocated, self.limit
+                    )
+                    .into());
+                }
+                self.allocated += bytes;
+
+                let id = self.next_id;
+                self.next_id += 1;
+
+                self.buffers
+                    .insert(id, Buffer::new(self.device, bytes as usize));
+                write_u64(payload, id)?;
+            }
+            Request::Free { id } => {
+                let buf = self
+                    .buffers
+                    .remove(&id)
+                    .ok_or_else(|| format!("Unknown buffer id: {id}"))?;
+                self.allocated -= buf.len() as u64;
+            }
+            Request::Read { id } => {
+                let data = self.device.read_to_vec(self.buf(id)?);
+                write_bytes(payload, &data)?;
+            }
+            Request::Write { id, data } => {
+                let device = self.device;
+                let buf = self.buf_mut(id)?;
+
+                if buf.len() != data.len() {
+                    return Err(format!(
+                        "Cannot write {} bytes to a buffer of {} bytes.",
+                        data.len(),
+                        buf.len()
+                    )
+                    .into());
+                }
+                device.write(buf, &data);
+            }
+            Request::Clear { id } => {
+                let device = self.device;
+                device.clear(self.buf_mut(id)?);
+            }
+            Request::CopySlice {
+                source,
+                source_offset,
+                dest,
+                dest_offset,
+                bytes,
+            } => {
+                let source_range =
+                    source_offset as usize..source_offset.saturating_add(bytes) as usize;
+                let dest_range = dest_offset as usize..dest_offset.saturating_add(bytes) as usize;
+
+                if source_range.end > self.buf(source)?.len()
+                    || dest_range.end > self.buf(dest)?.len()
+                {
+                    return Err("The copied range is out of bounds.".into());
+                }
+
+                if bytes == 0 {
+                    return Ok(());
+                }
+
+                // temporarily remove the destination, as source and destination are borrowed at the same time
+                let mut dest_buf = self.buffers.remove(&dest).unwrap();
+
+                if source == dest {
+                    let mut copied = Buffer::new(self.device, bytes as usize);
+                    self.device
+                        .copy_slice_to(&dest_buf, source_range, &mut copied, ..);
+                    self.device
+                        .copy_slice_to(&copied, .., &mut dest_buf, dest_range);
+                } else {
+                    let source_buf = self.buf(source)?;
+                    self.device
+                        .copy_slice_to(source_buf, source_range, &mut dest_buf, dest_range);
+                }
+
+                self.buffers.insert(dest, dest_buf);
+            }
+        }
+        Ok(())
+    }
+}
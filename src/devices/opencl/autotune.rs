@@ -0,0 +1,377 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use super::{
+    binary_cache::{fnv1a, write_atomic},
+    enqueue,
+    info::{device_string, CL_DEVICE_NAME, CL_DRIVER_VERSION},
+    AsClCvoidPtr, CLKernel, OpenCL, WorkGroupInfo,
+};
+
+const HEADER: &str = "# custos autotune v1";
+
+/// How often every candidate is timed. The fastest run counts.
+const RUNS: usize = 3;
+
+/// Stores the fastest local work size per kernel, problem size and device, which is found by [`enqueue_kernel_tuned`].
+///
+/// If the autotuner has a file, the tuned local work sizes are loaded from it and every new result is stored in it.
+/// Hence, a kernel is only benchmarked once, even across processes.
+///
+/// The autotuner of an [`OpenCL`] device is set with [`OpenCL::set_autotuner`].
+/// By default, it uses the file in the environment variable `CUSTOS_CL_TUNE_FILE` or keeps the results in memory only.
+#[derive(Debug, Clone)]
+pub struct Autotuner {
+    file: Option<PathBuf>,
+    /// `None` means that the local work size chosen by the OpenCL implementation is the fastest.
+    tuned: HashMap<u64, Option<[usize; 3]>>,
+}
+
+impl Default for Autotuner {
+    /// The tuned local work sizes are stored in the file in the environment variable `CUSTOS_CL_TUNE_FILE`, if it is set.
+    #[inline]
+    fn default() -> Self {
+        match std::env::var_os("CUSTOS_CL_TUNE_FILE") {
+            Some(file) => Autotuner::with_file(file),
+            None => Autotuner::new(),
+        }
+    }
+}
+
+impl Autotuner {
+    /// An autotuner, which keeps its results in memory only.
+    #[inline]
+    pub fn new() -> Autotuner {
+        Autotuner {
+            file: None,
+            tuned: HashMap::new(),
+        }
+    }
+
+    /// An autotuner, which loads and stores its results in `file`.
+    /// A missing or invalid file is not an error, the kernels are just benchmarked again.
+    pub fn with_file(file: impl Into<PathBuf>) -> Autotuner {
+        let file = file.into();
+        let tuned = fs::read_to_string(&file)
+            .map(|contents| parse(&contents))
+            .unwrap_or_default();
+
+        Autotuner {
+            file: Some(file),
+            tuned,
+        }
+    }
+
+    #[inline]
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// The number of tuned kernels and problem sizes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tuned.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tuned.is_empty()
+    }
+
+    /// Forgets all results in memory. The results in the file are kept, as they may be used by other processes.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.tuned.clear();
+    }
+
+    /// Other processes may have stored results in the file since it was loaded.
+    /// Hence, the results in the file are merged with the results in memory before the file is overwritten.
+    fn insert(&mut self, key: u64, lws: Option<[usize; 3]>) {
+        self.tuned.insert(key, lws);
+
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        let mut merged = fs::read_to_string(file)
+            .map(|contents| parse(&contents))
+            .unwrap_or_default();
+        merged.extend(self.tuned.iter().map(|(key, lws)| (*key, *lws)));
+
+        // failing to write the file is not an error, the kernel is just benchmarked again next time
+        let _ = write_atomic(file, format(&merged).as_bytes());
+    }
+}
+
+/// Every component, which invalidates a tuned local work size if it changes.
+fn tune_key(device: &OpenCL, src: &str, gws: [usize; 3]) -> crate::Result<u64> {
+    let key = format!(
+        "{}\n{}\n{:?}\n{}",
+        device_string(device.device().0, CL_DEVICE_NAME)?,
+        device_string(device.device().0, CL_DRIVER_VERSION)?,
+        gws,
+        src
+    );
+    Ok(fnv1a(key.as_bytes()))
+}
+
+/// File layout: a header line, then one line per result: the key (hex) and the local work size or `default`.
+fn format(tuned: &HashMap<u64, Option<[usize; 3]>>) -> String {
+    let mut entries = tuned.iter().collect::<Vec<_>>();
+    entries.sort_unstable_by_key(|(key, _)| **key);
+
+    let mut file = format!("{HEADER}\n");
+    for (key, lws) in entries {
+        match lws {
+            Some([x, y, z]) => writeln!(file, "{key:016x} {x} {y} {z}"),
+            None => writeln!(file, "{key:016x} default"),
+        }
+        .unwrap();
+    }
+    file
+}
+
+/// Skips invalid lines. A file with another header is ignored completely.
+fn parse(file: &str) -> HashMap<u64, Option<[usize; 3]>> {
+    let mut lines = file.lines();
+    if lines.next() != Some(HEADER) {
+        return HashMap::new();
+    }
+
+    lines
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let key = u64::from_str_radix(parts.next()?, 16).ok()?;
+
+            let values = parts.collect::<Vec<_>>();
+            let lws = match values.as_slice() {
+                ["default"] => None,
+                [x, y, z] => Some([x.parse().ok()?, y.parse().ok()?, z.parse().ok()?]),
+                _ => return None,
+            };
+            Some((key, lws))
+        })
+        .collect()
+}
+
+/// The local work sizes, which are benchmarked: `None` (chosen by the OpenCL implementation)
+/// and every combination of powers of two, which divides the global work size and fits into a work-group.
+fn candidates(gws: [usize; 3], max_size: usize) -> Vec<Option<[usize; 3]>> {
+    let dims = gws.iter().take_while(|&&size| size != 0).count();
+
+    let mut candidates = vec![None];
+    let mut lws = [1; 3];
+    push_candidates(&mut candidates, &mut lws, 0, dims, gws, max_size);
+    candidates
+}
+
+fn push_candidates(
+    candidates: &mut Vec<Option<[usize; 3]>>,
+    lws: &mut [usize; 3],
+    dim: usize,
+    dims: usize,
+    gws: [usize; 3],
+    max_size: usize,
+) {
+    if dim == dims {
+        if dims > 0 {
+            candidates.push(Some(*lws));
+        }
+        return;
+    }
+
+    let others = lws[..dim].iter().product::<usize>();
+    let mut size = 1;
+    while others * size <= max_size && size <= gws[dim] {
+        if gws[dim] % size == 0 {
+            lws[dim] = size;
+            push_candidates(candidates, lws, dim + 1, dims, gws, max_size);
+        }
+        size *= 2;
+    }
+    lws[dim] = 1;
+}
+
+/// Returns the fastest candidate. Candidates, which cannot be launched, e.g. because they exceed a device limit, are skipped.
+fn tune(
+    device: &OpenCL,
+    kernel: &CLKernel,
+    gws: [usize; 3],
+    args: &[&dyn AsClCvoidPtr],
+) -> crate::Result<Option<[usize; 3]>> {
+    let info = WorkGroupInfo::new(device, kernel)?;
+
+    let mut fastest = (Duration::MAX, None);
+    for lws in candidates(gws, info.max_size) {
+        // warm up, which also checks whether the candidate can be launched at all
+        if enqueue(device, kernel, gws, lws, args, &[])
+            .and_then(|event| event.wait())
+            .is_err()
+        {
+            continue;
+        }
+
+        for _ in 0..RUNS {
+            let start = Instant::now();
+            enqueue(device, kernel, gws, lws, args, &[])?.wait()?;
+
+            let elapsed = start.elapsed();
+            if elapsed < fastest.0 {
+                fastest = (elapsed, lws);
+            }
+        }
+    }
+    Ok(fastest.1)
+}
+
+/// Launches a kernel with the fastest local work size for the kernel, the global work size and the device.
+/// At the first launch, the kernel is benchmarked with several local work sizes and the fastest one is stored in the [`Autotuner`] of the device.
+///
+/// While benchmarking, the kernel is launched several times with the same arguments.
+/// Hence, it must produce the same result on every launch, e.g. it must not accumulate into its inputs.
+/// # Example
+/// ```
+/// use custos::{opencl::enqueue_kernel_tuned, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let lhs = Buffer::<f32, _>::from((&device, [1.; 256]));
+///     let rhs = Buffer::<f32, _>::from((&device, [2.; 256]));
+///     let out = Buffer::<f32, _>::new(&device, 256);
+///
+///     let src = "
+///         __kernel void add(__global const float* lhs, __global const float* rhs, __global float* out) {
+///             size_t id = get_global_id(0);
+///             out[id] = lhs[id] + rhs[id];
+///         }
+///     ";
+///     enqueue_kernel_tuned(&device, src, [256, 0, 0], &[&lhs, &rhs, &out])?;
+///
+///     assert_eq!(out.read(), vec![3.; 256]);
+///     assert_eq!(device.autotuner.borrow().len(), 1);
+///     Ok(())
+/// }
+/// ```
+pub fn enqueue_kernel_tuned(
+    device: &OpenCL,
+    src: &str,
+    gws: [usize; 3],
+    args: &[&dyn AsClCvoidPtr],
+) -> crate::Result<()> {
+    let kernel = device.kernel_cache.borrow_mut().kernel_cache(device, src)?;
+    let key = tune_key(device, src, gws)?;
+
+    let tuned = device.autotuner.borrow().tuned.get(&key).copied();
+    let lws = match tuned {
+        Some(lws) => lws,
+        None => {
+            let lws = tune(device, &kernel, gws, args)?;
+            device.autotuner.borrow_mut().insert(key, lws);
+            lws
+        }
+    };
+
+    enqueue(device, &kernel, gws, lws, args, &[])?.wait()
+}
+
+impl OpenCL {
+    /// Sets the autotuner, which stores the local work sizes found by [`enqueue_kernel_tuned`].
+    #[inline]
+    pub fn set_autotuner(&self, autotuner: Autotuner) {
+        *self.autotuner.borrow_mut() = autotuner;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{candidates, format, parse, Autotuner, HEADER};
+
+    #[test]
+    fn test_format_parse() {
+        let tuned = HashMap::from([(1, Some([64, 1, 1])), (0xff, None), (2, Some([8, 8, 1]))]);
+
+        let file = format(&tuned);
+        assert_eq!(
+            file,
+            format!(
+                "{HEADER}\n\
+                0000000000000001 64 1 1\n\
+                0000000000000002 8 8 1\n\
+                00000000000000ff default\n"
+            )
+        );
+        assert_eq!(parse(&file), tuned);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse("").is_empty());
+        assert!(parse("# another file\n0000000000000001 64 1 1\n").is_empty());
+
+        let file = format!("{HEADER}\nno key\n0000000000000001 64 1\n0000000000000002 default\n");
+        assert_eq!(parse(&file), HashMap::from([(2, None)]));
+    }
+
+    #[test]
+    fn test_insert_merges_file() {
+        let file = std::env::temp_dir().join(format!("custos-tune-merge-{}", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+
+        // both autotuners are created before anything is stored, like in concurrent processes
+        let mut first = Autotuner::with_file(&file);
+        let mut second = Autotuner::with_file(&file);
+
+        first.insert(1, Some([64, 1, 1]));
+        second.insert(2, None);
+        assert_eq!(second.len(), 1);
+
+        let stored = parse(&std::fs::read_to_string(&file).unwrap());
+        assert_eq!(stored, HashMap::from([(1, Some([64, 1, 1])), (2, None)]));
+
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn test_candidates_1d() {
+        assert_eq!(
+            candidates([24, 0, 0], 256),
+            vec![
+                None,
+                Some([1, 1, 1]),
+                Some([2, 1, 1]),
+                Some([4, 1, 1]),
+                Some([8, 1, 1])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_candidates_2d() {
+        let candidates = candidates([4, 2, 0], 4);
+        assert_eq!(
+            candidates,
+            vec![
+                None,
+                Some([1, 1, 1]),
+                Some([1, 2, 1]),
+                Some([2, 1, 1]),
+                Some([2, 2, 1]),
+                Some([4, 1, 1])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_candidates_limits() {
+        // the work-group size of the kernel is unknown
+        assert_eq!(candidates([64, 0, 0], 0), vec![None]);
+        assert_eq!(candidates([0, 0, 0], 256), vec![None]);
+    }
+}
@@ -0,0 +1,288 @@
+use core::ptr::null_mut;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use min_cl::api::{clGetProgramInfo, CLIntDevice, OCLErrorKind, Program};
+
+use super::{
+    build_program_with_log, compiler_options,
+    ffi::{check, clCreateProgramWithBinary, CL_PROGRAM_BINARIES, CL_PROGRAM_BINARY_SIZES},
+    info::{device_string, CL_DEVICE_NAME, CL_DRIVER_VERSION},
+    BuildOptions, OpenCL,
+};
+
+const MAGIC: &[u8; 8] = b"CUSTOSCL";
+
+/// A directory, which stores the binaries of built OpenCL programs.
+/// Hence, a program is only compiled from source once, even across processes.
+///
+/// A binary is identified by the source code, the device name, the driver version and the build options.
+/// If any of these change, the program is compiled from source again.
+///
+/// The cache of an [`OpenCL`] device is set with [`OpenCL::set_binary_cache`] or the environment variable `CUSTOS_CL_CACHE_DIR`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryCache {
+    pub dir: PathBuf,
+}
+
+impl BinaryCache {
+    #[inline]
+    pub fn new(dir: impl Into<PathBuf>) -> BinaryCache {
+        BinaryCache { dir: dir.into() }
+    }
+
+    /// Uses the directory in the environment variable `CUSTOS_CL_CACHE_DIR`, if it is set.
+    #[inline]
+    pub fn from_env() -> Option<BinaryCache> {
+        std::env::var_os("CUSTOS_CL_CACHE_DIR").map(BinaryCache::new)
+    }
+
+    /// Loads the binary of the program from the cache and builds it.
+    /// If there is no valid binary, the program is built from source and its binary is stored.
+    /// Failing to write the cache is not an error, the program is just compiled again next time.
+    pub fn build(
+        &self,
+        device: &OpenCL,
+        src: &str,
+        options: &BuildOptions,
+    ) -> crate::Result<Program> {
+        let key = cache_key(device.device(), src, options)?;
+        let path = self.path(&key);
+
+        let cached = fs::read(&path).ok();
+        if let Some(binary) = cached.as_deref().and_then(|file| decode(&key, file)) {
+            if let Ok(program) = program_from_binary(device, binary, src, options) {
+                return Ok(program);
+            }
+        }
+
+        let program = min_cl::api::create_program_with_source(&device.ctx(), src)?;
+        build_program_with_log(&program, device.device(), src, options)?;
+
+        if let Ok(binary) = program_binary(&program) {
+            let _ = write_atomic(&path, &encode(&key, &binary));
+        }
+        Ok(program)
+    }
+
+    /// The path of the file, which contains the binary for the given key.
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.bin", fnv1a(key.as_bytes())))
+    }
+}
+
+/// Every component, which invalidates a binary if it changes.
+fn cache_key(device: CLIntDevice, src: &str, options: &BuildOptions) -> crate::Result<String> {
+    Ok(format!(
+        "{}\n{}\n{}\n{}",
+        device_string(device.0, CL_DEVICE_NAME)?,
+        device_string(device.0, CL_DRIVER_VERSION)?,
+        compiler_options(options),
+        src
+    ))
+}
+
+/// 64-bit FNV-1a, which is stable across Rust versions unlike `DefaultHasher`.
+pub(super) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// File layout: magic, key length (u64 le), key, binary.
+/// The whole key is stored to detect hash collisions.
+fn encode(key: &str, binary: &[u8]) -> Vec<u8> {
+    let mut file = Vec::with_capacity(MAGIC.len() + 8 + key.len() + binary.len());
+    file.extend_from_slice(MAGIC);
+    file.extend_from_slice(&(key.len() as u64).to_le_bytes());
+    file.extend_from_slice(key.as_bytes());
+    file.extend_from_slice(binary);
+    file
+}
+
+/// Returns the binary, if the file is valid and belongs to the key.
+fn decode<'a>(key: &str, file: &'a [u8]) -> Option<&'a [u8]> {
+    let file = file.strip_prefix(MAGIC.as_slice())?;
+
+    let (len, file) = file.split_at(8.min(file.len()));
+    let len = u64::from_le_bytes(len.try_into().ok()?) as usize;
+
+    if len != key.len() || file.get(..len)? != key.as_bytes() {
+        return None;
+    }
+
+    let binary = &file[len..];
+    (!binary.is_empty()).then_some(binary)
+}
+
+/// Distinguishes the temporary files of threads in the same process.
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Concurrent processes and threads never read a partially written file.
+pub(super) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp = path.with_extension(format!(
+        "tmp{}-{}",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
+
+fn program_from_binary(
+    device: &OpenCL,
+    binary: &[u8],
+    src: &str,
+    options: &BuildOptions,
+) -> crate::Result<Program> {
+    let mut binary_status = 0;
+    let mut err = 0;
+
+    let program = unsafe {
+        clCreateProgramWithBinary(
+            device.ctx().0,
+            1,
+            &device.device().0,
+            &binary.len(),
+            &binary.as_ptr(),
+            &mut binary_status,
+            &mut err,
+        )
+    };
+    check(err)?;
+    let program = Program(program);
+    check(binary_status)?;
+
+    build_program_with_log(&program, device.device(), src, options)?;
+    Ok(program)
+}
+
+/// Returns the binary of a program, which was built for a single device.
+fn program_binary(program: &Program) -> crate::Result<Vec<u8>> {
+    let mut size = 0usize;
+    check(unsafe {
+        clGetProgramInfo(
+            program.0,
+            CL_PROGRAM_BINARY_SIZES,
+            core::mem::size_of::<usize>(),
+            (&mut size as *mut usize).cast(),
+            null_mut(),
+        )
+    })?;
+
+    if size == 0 {
+        return Err(OCLErrorKind::InvalidValue.into());
+    }
+
+    let mut binary = vec![0u8; size];
+    let mut binary_ptr = binary.as_mut_ptr();
+    check(unsafe {
+        clGetProgramInfo(
+            program.0,
+            CL_PROGRAM_BINARIES,
+            core::mem::size_of::<*mut u8>(),
+            (&mut binary_ptr as *mut *mut u8).cast(),
+            null_mut(),
+        )
+    })?;
+
+    Ok(binary)
+}
+
+impl OpenCL {
+    /// Sets the on-disk cache for the binaries of the built programs (see [`BinaryCache`]).
+    /// `None` disables the cache.
+    /// # Example
+    /// ```
+    /// use custos::{opencl::BinaryCache, OpenCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = OpenCL::new(0)?;
+    ///     device.set_binary_cache(Some(BinaryCache::new(std::env::temp_dir().join("custos-cl"))));
+    ///
+    ///     let buf = custos::Buffer::<f32, _>::from((&device, [1., 2., 3.]));
+    ///     assert_eq!(buf.read(), vec![1., 2., 3.]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn set_binary_cache(&self, cache: Option<BinaryCache>) {
+        self.kernel_cache.borrow_mut().binary_cache = cache;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, fnv1a, write_atomic, BinaryCache};
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(fnv1a(b"-cl-std=CL1.2"), fnv1a(b"-cl-std=CL2.0"));
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let file = encode("device\ndriver\n-cl-std=CL1.2\nsrc", &[1, 2, 3]);
+
+        assert_eq!(
+            decode("device\ndriver\n-cl-std=CL1.2\nsrc", &file),
+            Some([1, 2, 3].as_slice())
+        );
+
+        // another driver version
+        assert_eq!(decode("device\ndriver2\n-cl-std=CL1.2\nsrc", &file), None);
+
+        // truncated or invalid files
+        assert_eq!(
+            decode("device\ndriver\n-cl-std=CL1.2\nsrc", &file[..20]),
+            None
+        );
+        assert_eq!(decode("key", b"CUSTOSCL"), None);
+        assert_eq!(decode("key", b"no cache file"), None);
+        assert_eq!(decode("key", &encode("key", &[])), None);
+    }
+
+    #[test]
+    fn test_cache_path() {
+        let cache = BinaryCache::new("cache");
+        assert_eq!(cache.path("a"), cache.path("a"));
+        assert_ne!(cache.path("a"), cache.path("b"));
+        assert_eq!(
+            cache.path("a").extension().and_then(|ext| ext.to_str()),
+            Some("bin")
+        );
+    }
+
+    #[test]
+    fn test_write_atomic_threads() {
+        let path = std::env::temp_dir()
+            .join(format!("custos-write-atomic-{}", std::process::id()))
+            .join("file.bin");
+
+        let threads = (0..8u8)
+            .map(|idx| {
+                let path = path.clone();
+                std::thread::spawn(move || write_atomic(&path, &[idx; 1024]))
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap().unwrap();
+        }
+
+        let contents = std::fs::read(&path).unwrap();
+        assert_eq!(contents.len(), 1024);
+        assert!(contents.iter().all(|byte| *byte == contents[0]));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
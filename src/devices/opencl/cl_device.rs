@@ -1,20 +1,26 @@
-use core::ops::{Range, RangeBounds};
+use core::{
+    ffi::c_void,
+    ops::{Range, RangeBounds},
+    ptr::null_mut,
+};
 
 use min_cl::CLDevice;
 
 use min_cl::api::{
-    create_buffer, enqueue_copy_buffer, enqueue_copy_buffers, enqueue_full_copy_buffer,
-    enqueue_read_buffer, enqueue_write_buffer, wait_for_event, CLIntDevice, CommandQueue, Context,
-    MemFlags,
+    cl_command_queue, create_buffer, create_command_queue, create_context, enqueue_read_buffer,
+    enqueue_write_buffer, unified_ptr, CLIntDevice, CommandQueue, Context, MemFlags,
 };
 
-use super::{chosen_cl_idx, cl_clear, CLPtr, KernelCacheCL, RawCL};
+use super::{
+    chosen_cl_idx, cl_clear, use_unified_mem, Autotuner, CLEvent, CLPtr, CLRead, CommandKind,
+    KernelCacheCL, Profiler, RawCL,
+};
 use crate::{
     cache::{Cache, CacheReturn, RawConv},
     flag::AllocFlag,
-    op_traits::{bounds_to_range, CacheBuf, ClearBuf, CloneBuf, CopySlice},
-    Alloc, Buffer, CDatatype, CachedLeaf, Device, Error, Graph, GraphReturn, Read, Shape, WriteBuf,
-    CPU,
+    op_traits::{CacheBuf, ClearBuf, CloneBuf, CopySlice},
+    Alloc, Buffer, CDatatype, CachedLeaf, Device, DeviceError, Error, Graph, GraphReturn, Read,
+    Shape, WriteBuf, CPU,
 };
 
 use std::{
@@ -22,9 +28,6 @@ use std::{
     fmt::Debug,
 };
 
-#[cfg(unified_cl)]
-use min_cl::api::unified_ptr;
-
 /// Used to perform calculations with an OpenCL capable device.
 /// To make new calculations invocable, a trait providing new operations should be implemented for [CLDevice].
 /// # Example
@@ -47,6 +50,12 @@ pub struct OpenCL {
     pub inner: RefCell<CLDevice>,
     pub graph: RefCell<Graph>,
     pub cpu: CPU,
+    /// Records the commands, if profiling is enabled (see [`OpenCL::enable_profiling`]).
+    pub profiler: RefCell<Option<Profiler>>,
+    /// Stores the local work sizes found by [`enqueue_kernel_tuned`](crate::opencl::enqueue_kernel_tuned).
+    pub autotuner: RefCell<Autotuner>,
+    /// The live queues created by [`OpenCL::create_queue`], which are finished at the end of an [`async_scope`](OpenCL::async_scope).
+    pub queues: RefCell<Vec<cl_command_queue>>,
 }
 
 /// Short form for `OpenCL`
@@ -58,13 +67,47 @@ impl OpenCL {
     /// - No device is found at the given device index
     /// - some other OpenCL related errors
     pub fn new(device_idx: usize) -> Result<OpenCL, Error> {
-        let inner = RefCell::new(CLDevice::new(device_idx)?);
+        let mut device = CLDevice::new(device_idx)?;
+        device.unified_mem = use_unified_mem(device.unified_mem)?;
+
+        Ok(OpenCL {
+            inner: RefCell::new(device),
+            kernel_cache: Default::default(),
+            cache: Default::default(),
+            graph: Default::default(),
+            cpu: Default::default(),
+            profiler: Default::default(),
+            autotuner: Default::default(),
+            queues: Default::default(),
+        })
+    }
+
+    /// Returns an [OpenCL] device, which uses the specified device, e.g. one listed by [`cl_devices`](crate::opencl::cl_devices).
+    /// # Errors
+    /// - The context or command queue could not be created
+    pub fn from_cl_device(device: CLIntDevice) -> Result<OpenCL, Error> {
+        OpenCL::with_context(device, create_context(&[device])?)
+    }
+
+    /// Returns an [OpenCL] device, which uses the specified device in `ctx`. The device must belong to the context.
+    pub(super) fn with_context(device: CLIntDevice, ctx: Context) -> Result<OpenCL, Error> {
+        let queue = create_command_queue(&ctx, device)?;
+        let unified_mem = use_unified_mem(device.unified_mem()?)?;
+
         Ok(OpenCL {
-            inner,
+            inner: RefCell::new(CLDevice {
+                device,
+                ctx,
+                queue,
+                unified_mem,
+            }),
             kernel_cache: Default::default(),
             cache: Default::default(),
             graph: Default::default(),
             cpu: Default::default(),
+            profiler: Default::default(),
+            autotuner: Default::default(),
+            queues: Default::default(),
         })
     }
 
@@ -110,18 +153,32 @@ impl OpenCL {
         self.device().get_version()
     }
 
-    /// Checks whether the device supports unified memory.
+    /// Checks whether the device uses unified memory.
+    /// If it does, buffers are allocated in host-mapped memory and can be accessed from the host without a transfer.
     #[inline]
     pub fn unified_mem(&self) -> bool {
         self.inner.borrow().unified_mem
     }
 
-    #[deprecated(
-        since = "0.6.0",
-        note = "Use the environment variable 'CUSTOS_USE_UNIFIED' set to 'true', 'false' or 'default'[=hardware dependent] instead."
-    )]
-    pub fn set_unified_mem(&self, unified_mem: bool) {
+    /// Selects host-mapped allocations (`true`) or explicit transfers (`false`) for all buffers, which are allocated afterwards.
+    /// Already allocated buffers keep their kind of memory.
+    /// # Errors
+    /// - `unified_mem` is `true`, but the device does not support unified memory.
+    ///   The mapped memory of such a device would not show the writes of kernels.
+    pub fn set_unified_mem(&self, unified_mem: bool) -> crate::Result<()> {
+        if unified_mem && !self.device().unified_mem()? {
+            return Err(DeviceError::UnifiedMemUnsupported.into());
+        }
         self.inner.borrow_mut().unified_mem = unified_mem;
+        Ok(())
+    }
+
+    /// Maps the OpenCL buffer into host memory, if the device uses unified memory.
+    fn host_ptr<T>(&self, ptr: *mut c_void, len: usize) -> *mut T {
+        if !self.unified_mem() {
+            return null_mut();
+        }
+        unified_ptr::<T>(&self.queue(), ptr, len).unwrap()
     }
 }
 
@@ -186,11 +243,7 @@ impl<T, S: Shape> Alloc<'_, T, S> for OpenCL {
         let ptr =
             create_buffer::<T>(&self.ctx(), MemFlags::MemReadWrite as u64, len, None).unwrap();
 
-        #[cfg(unified_cl)]
-        let host_ptr = unified_ptr::<T>(&self.queue(), ptr, len).unwrap();
-
-        #[cfg(not(unified_cl))]
-        let host_ptr = std::ptr::null_mut();
+        let host_ptr = self.host_ptr(ptr, len);
 
         CLPtr {
             ptr,
@@ -209,11 +262,7 @@ impl<T, S: Shape> Alloc<'_, T, S> for OpenCL {
         )
         .unwrap();
 
-        #[cfg(unified_cl)]
-        let host_ptr = unified_ptr::<T>(&self.queue(), ptr, data.len()).unwrap();
-
-        #[cfg(not(unified_cl))]
-        let host_ptr = std::ptr::null_mut();
+        let host_ptr = self.host_ptr(ptr, data.len());
 
         CLPtr {
             ptr,
@@ -226,8 +275,10 @@ impl<T, S: Shape> Alloc<'_, T, S> for OpenCL {
 
 impl<'a, T> CloneBuf<'a, T> for OpenCL {
     fn clone_buf(&'a self, buf: &Buffer<'a, T, OpenCL>) -> Buffer<'a, T, OpenCL> {
-        let cloned = Buffer::new(self, buf.len());
-        enqueue_full_copy_buffer::<T>(&self.queue(), buf.ptr.ptr, cloned.ptr.ptr, buf.len())
+        let mut cloned = Buffer::new(self, buf.len());
+        // Safety: the copy is waited for, before the buffers can be accessed again
+        unsafe { self.copy_async(buf, .., &mut cloned, ..) }
+            .and_then(CLEvent::wait)
             .unwrap();
         cloned
     }
@@ -258,19 +309,6 @@ impl GraphReturn for OpenCL {
     }
 }
 
-#[cfg(unified_cl)]
-impl crate::MainMemory for OpenCL {
-    #[inline]
-    fn as_ptr<T, S: Shape>(ptr: &Self::Ptr<T, S>) -> *const T {
-        ptr.host_ptr
-    }
-
-    #[inline]
-    fn as_ptr_mut<T, S: Shape>(ptr: &mut Self::Ptr<T, S>) -> *mut T {
-        ptr.host_ptr
-    }
-}
-
 #[cfg(feature = "opt-cache")]
 impl crate::GraphOpt for OpenCL {}
 
@@ -294,23 +332,10 @@ impl<T> CopySlice<T> for OpenCL {
         dest: &mut Buffer<T, Self>,
         dest_range: DR,
     ) {
-        let source_range = bounds_to_range(source_range, source.len());
-        let dest_range = bounds_to_range(dest_range, dest.len());
-
-        assert_eq!(
-            source_range.end - source_range.start,
-            dest_range.end - dest_range.start
-        );
-
-        enqueue_copy_buffer::<T>(
-            &self.queue(),
-            source.ptr.ptr,
-            dest.ptr.ptr,
-            source_range.start,
-            dest_range.start,
-            source_range.end - source_range.start,
-        )
-        .unwrap();
+        // Safety: the copy is waited for, before the buffers can be accessed again
+        unsafe { self.copy_async(source, source_range, dest, dest_range) }
+            .and_then(CLEvent::wait)
+            .unwrap();
     }
 
     fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
@@ -319,54 +344,52 @@ impl<T> CopySlice<T> for OpenCL {
         dest: &mut Buffer<T, Self>,
         ranges: I,
     ) {
-        let ranges = ranges.into_iter().map(|(from, to)| {
-            let len = from.end - from.start;
-            assert_eq!(len, to.end - to.start);
-            (from.start, to.start, len)
-        });
+        let mut events = Vec::new();
+        let mut enqueued = Ok(());
+
+        for (from, to) in ranges {
+            // Safety: every enqueued copy is waited for below, before the buffers can be accessed again
+            match unsafe { self.copy_async(source, from, dest, to) } {
+                Ok(event) => events.push(event),
+                Err(err) => {
+                    enqueued = Err(err);
+                    break;
+                }
+            }
+        }
 
-        enqueue_copy_buffers::<T, _>(&self.queue(), source.ptr.ptr, dest.ptr.ptr, ranges).unwrap();
+        for event in events {
+            event.wait().unwrap();
+        }
+        enqueued.unwrap();
     }
 }
 
 impl<T> WriteBuf<T, OpenCL> for OpenCL {
     fn write(&self, buf: &mut Buffer<T, OpenCL>, data: &[T]) {
-        let event =
-            unsafe { enqueue_write_buffer(&self.queue(), buf.cl_ptr(), data, true).unwrap() };
+        let event = unsafe {
+            CLEvent::from_raw(
+                enqueue_write_buffer(&self.queue(), buf.cl_ptr(), data, true)
+                    .unwrap()
+                    .0,
+            )
+        };
+        self.record_event(CommandKind::Write, &event, || "write".into());
 
-        wait_for_event(event).unwrap();
+        event.wait().unwrap();
     }
 }
 
-/*#[cfg(not(unified_cl))]
 impl<T: Clone + Default> Read<T, OpenCL> for OpenCL {
-    type Read<'a> = Vec<T> where T: 'a;
+    type Read<'a> = CLRead<'a, T> where T: 'a;
 
-    fn read<'a>(&self, buf: &'a Buffer<T, OpenCL>) -> Self::Read<'a> {
-        self.read_to_vec(buf)
-    }
-
-    #[inline]
-    fn read_to_vec(&self, buf: &crate::Buffer<T, OpenCL>) -> Vec<T> {
-        read_cl_buf_to_vec(self, buf).unwrap()
-    }
-}*/
-
-impl<T: Clone + Default> Read<T, OpenCL> for OpenCL {
-    #[cfg(not(unified_cl))]
-    type Read<'a> = Vec<T> where T: 'a;
-    #[cfg(unified_cl)]
-    type Read<'a> = &'a [T] where T: 'a;
-
-    #[cfg(not(unified_cl))]
-    fn read<'a>(&self, buf: &'a Buffer<T, OpenCL>) -> Self::Read<'a> {
-        self.read_to_vec(buf)
-    }
-
-    #[cfg(unified_cl)]
+    /// Borrows the data of buffers in unified memory. Otherwise, the data is transferred into a `Vec`.
     #[inline]
     fn read<'a>(&self, buf: &'a Buffer<T, OpenCL>) -> Self::Read<'a> {
-        buf.as_slice()
+        match buf.try_as_slice() {
+            Ok(slice) => CLRead::Borrowed(slice),
+            Err(_) => CLRead::Owned(self.read_to_vec(buf)),
+        }
     }
 
     #[inline]
@@ -380,8 +403,11 @@ fn read_cl_buf_to_vec<T: Clone + Default>(
     buf: &Buffer<T, OpenCL>,
 ) -> crate::Result<Vec<T>> {
     let mut read = vec![T::default(); buf.len()];
-    let event = unsafe { enqueue_read_buffer(&device.queue(), buf.cl_ptr(), &mut read, false)? };
-    wait_for_event(event).unwrap();
+    let event = unsafe {
+        CLEvent::from_raw(enqueue_read_buffer(&device.queue(), buf.cl_ptr(), &mut read, false)?.0)
+    };
+    device.record_event(CommandKind::Read, &event, || "read".into());
+    event.wait()?;
     Ok(read)
 }
 
@@ -400,6 +426,9 @@ mod tests {
             inner: RefCell::new(device),
             graph: Default::default(),
             cpu: Default::default(),
+            profiler: Default::default(),
+            autotuner: Default::default(),
+            queues: Default::default(),
         };
 
         let buf = Buffer::from((&cl, &[1, 2, 3, 4, 5, 6, 7]));
@@ -413,6 +442,9 @@ mod tests {
             inner: RefCell::new(device),
             graph: Default::default(),
             cpu: Default::default(),
+            profiler: Default::default(),
+            autotuner: Default::default(),
+            queues: Default::default(),
         };
 
         let buf = Buffer::from((&cl1, &[2, 2, 4, 4, 2, 1, 3]));
@@ -0,0 +1,336 @@
+use core::{
+    ffi::c_void,
+    future::Future,
+    marker::PhantomData,
+    mem::size_of_val,
+    ops::RangeBounds,
+    pin::Pin,
+    ptr::{null, null_mut},
+    task::{Context, Poll, Waker},
+};
+use std::sync::{Arc, Mutex};
+
+use min_cl::api::{
+    clEnqueueCopyBuffer, clEnqueueReadBuffer, clEnqueueWriteBuffer, clFinish, clReleaseEvent,
+    clWaitForEvents, cl_event, cl_int, OCLErrorKind,
+};
+
+use super::{
+    ffi::{
+        check, clGetEventInfo, clSetEventCallback, CL_COMPLETE, CL_EVENT_COMMAND_EXECUTION_STATUS,
+    },
+    CommandKind,
+};
+use crate::{op_traits::bounds_to_range, Buffer, OpenCL};
+
+/// An event of an enqueued OpenCL command, e.g. returned by [`AsyncScope::read_async`], [`OpenCL::copy_async`] or [`enqueue_kernel_after`](super::enqueue_kernel_after).
+///
+/// The event can be waited for ([`CLEvent::wait`]), polled ([`CLEvent::is_complete`]), awaited or passed as a dependency to [`enqueue_kernel_after`](super::enqueue_kernel_after).
+/// Dropping the event does not wait for the command.
+#[derive(Debug)]
+pub struct CLEvent {
+    event: cl_event,
+    /// The waker of the task, which awaits the event. It is woken by the completion callback of the event.
+    waker: Option<Arc<Mutex<Option<Waker>>>>,
+}
+
+impl CLEvent {
+    /// Wraps a raw OpenCL event. The event is released when the `CLEvent` is dropped.
+    /// # Safety
+    /// The event must be valid and must not be released by someone else.
+    #[inline]
+    pub unsafe fn from_raw(event: cl_event) -> CLEvent {
+        CLEvent { event, waker: None }
+    }
+
+    #[inline]
+    pub fn as_raw(&self) -> cl_event {
+        self.event
+    }
+
+    /// Returns `true` if the command has finished.
+    /// # Errors
+    /// If the command was terminated abnormally.
+    pub fn is_complete(&self) -> crate::Result<bool> {
+        let mut status = 0i32;
+        check(unsafe {
+            clGetEventInfo(
+                self.event,
+                CL_EVENT_COMMAND_EXECUTION_STATUS,
+                core::mem::size_of::<i32>(),
+                &mut status as *mut i32 as *mut _,
+                null_mut(),
+            )
+        })?;
+
+        if status < 0 {
+            return Err(OCLErrorKind::from_value(status).into());
+        }
+        Ok(status == CL_COMPLETE)
+    }
+
+    /// Blocks until the command has finished.
+    #[inline]
+    pub fn wait(self) -> crate::Result<()> {
+        check(unsafe { clWaitForEvents(1, &self.event) })
+    }
+}
+
+impl Drop for CLEvent {
+    fn drop(&mut self) {
+        unsafe { clReleaseEvent(self.event) };
+    }
+}
+
+/// Waits for the command of a raw event, which was returned together with `status`, and records it, if profiling is enabled.
+pub(super) fn wait_recorded(
+    device: &OpenCL,
+    status: cl_int,
+    event: cl_event,
+    kind: CommandKind,
+    name: &str,
+) -> crate::Result<()> {
+    check(status)?;
+
+    let event = unsafe { CLEvent::from_raw(event) };
+    device.record_event(kind, &event, || name.into());
+    event.wait()
+}
+
+/// Called by OpenCL, when the command of an awaited event has finished or was terminated abnormally.
+extern "system" fn wake_task(_event: cl_event, _status: cl_int, user_data: *mut c_void) {
+    // Safety: `user_data` was created by `Arc::into_raw` in `poll`. The callback is called exactly once.
+    let waker = unsafe { Arc::from_raw(user_data as *const Mutex<Option<Waker>>) };
+
+    // a poisoned lock must not panic across the FFI boundary
+    let task = match waker.lock() {
+        Ok(mut task) => task.take(),
+        Err(_) => None,
+    };
+
+    if let Some(task) = task {
+        task.wake();
+    }
+}
+
+/// A pending event registers a completion callback (`clSetEventCallback`), which wakes the task.
+impl Future for CLEvent {
+    type Output = crate::Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        match this.is_complete() {
+            Ok(true) => return Poll::Ready(Ok(())),
+            Ok(false) => (),
+            Err(err) => return Poll::Ready(Err(err)),
+        }
+
+        match &this.waker {
+            // the callback is already registered, but the task may have moved to another waker
+            Some(waker) => *waker.lock().unwrap() = Some(cx.waker().clone()),
+            None => {
+                let waker = Arc::new(Mutex::new(Some(cx.waker().clone())));
+                let user_data = Arc::into_raw(waker.clone()) as *mut c_void;
+
+                let status =
+                    unsafe { clSetEventCallback(this.event, CL_COMPLETE, wake_task, user_data) };
+                if let Err(err) = check(status) {
+                    // Safety: the callback was not registered, hence the reference is released here.
+                    drop(unsafe { Arc::from_raw(user_data as *const Mutex<Option<Waker>>) });
+                    return Poll::Ready(Err(err));
+                }
+                this.waker = Some(waker);
+            }
+        }
+
+        // the command may have finished before the waker was stored
+        match this.is_complete() {
+            Ok(true) => Poll::Ready(Ok(())),
+            Ok(false) => Poll::Pending,
+            Err(err) => Poll::Ready(Err(err)),
+        }
+    }
+}
+
+/// Enqueues non-blocking transfers between host memory and OpenCL buffers.
+/// Created by [`OpenCL::async_scope`].
+///
+/// The host slices are borrowed for the whole scope, because all enqueued commands are waited for at the end of the scope.
+/// Hence, forgetting or dropping a [`CLEvent`] cannot lead to a dangling host slice.
+pub struct AsyncScope<'scope, 'env: 'scope> {
+    device: &'env OpenCL,
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'scope, 'env> AsyncScope<'scope, 'env> {
+    #[inline]
+    pub fn device(&self) -> &'env OpenCL {
+        self.device
+    }
+
+    /// Enqueues a non-blocking read of `buf` into `data`.
+    /// `data` contains the contents of `buf` after the returned event has completed, but can only be accessed after the scope.
+    /// # Panics
+    /// If `data` is longer than `buf`.
+    pub fn read_async<T>(
+        &'scope self,
+        buf: &Buffer<T, OpenCL>,
+        data: &'scope mut [T],
+    ) -> crate::Result<CLEvent> {
+        assert!(
+            data.len() <= buf.len(),
+            "The host slice is longer than the buffer."
+        );
+
+        let mut event = null_mut();
+        check(unsafe {
+            clEnqueueReadBuffer(
+                self.device.queue().0,
+                buf.cl_ptr(),
+                0,
+                0,
+                size_of_val(data),
+                data.as_mut_ptr().cast(),
+                0,
+                null(),
+                &mut event,
+            )
+        })?;
+
+        let event = unsafe { CLEvent::from_raw(event) };
+        self.device
+            .record_event(CommandKind::Read, &event, || "read".into());
+        Ok(event)
+    }
+
+    /// Enqueues a non-blocking write of `data` into `buf`.
+    /// # Panics
+    /// If `data` is longer than `buf`.
+    pub fn write_async<T>(
+        &'scope self,
+        buf: &mut Buffer<T, OpenCL>,
+        data: &'scope [T],
+    ) -> crate::Result<CLEvent> {
+        assert!(
+            data.len() <= buf.len(),
+            "The host slice is longer than the buffer."
+        );
+
+        let mut event = null_mut();
+        check(unsafe {
+            clEnqueueWriteBuffer(
+                self.device.queue().0,
+                buf.cl_ptr(),
+                0,
+                0,
+                size_of_val(data),
+                data.as_ptr().cast(),
+                0,
+                null(),
+                &mut event,
+            )
+        })?;
+
+        let event = unsafe { CLEvent::from_raw(event) };
+        self.device
+            .record_event(CommandKind::Write, &event, || "write".into());
+        Ok(event)
+    }
+}
+
+/// Finishes every enqueued command of the device, including the commands of the queues created by [`OpenCL::create_queue`], even if the scope panics.
+struct FinishGuard<'a>(&'a OpenCL);
+
+impl Drop for FinishGuard<'_> {
+    fn drop(&mut self) {
+        // the host slices must not be accessed until the transfers have finished
+        unsafe { clFinish(self.0.queue().0) };
+
+        for &queue in self.0.queues.borrow().iter() {
+            unsafe { clFinish(queue) };
+        }
+    }
+}
+
+impl OpenCL {
+    /// Creates a scope for non-blocking reads and writes of host memory (see [`AsyncScope`]).
+    /// Every enqueued command of this device, on any of its queues, is finished when the scope ends.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, OpenCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = OpenCL::new(0)?;
+    ///     let mut buf = Buffer::<f32, _>::new(&device, 4);
+    ///
+    ///     let input = [1., 2., 3., 4.];
+    ///     let mut output = [0.; 4];
+    ///
+    ///     device.async_scope(|scope| {
+    ///         let write = scope.write_async(&mut buf, &input)?;
+    ///         // do some work on the host while the data is transferred
+    ///         write.wait()?;
+    ///         scope.read_async(&buf, &mut output)?.wait()
+    ///     })?;
+    ///
+    ///     assert_eq!(output, [1., 2., 3., 4.]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn async_scope<'env, F, R>(&'env self, f: F) -> R
+    where
+        F: for<'scope> FnOnce(&'scope AsyncScope<'scope, 'env>) -> R,
+    {
+        let _finish = FinishGuard(self);
+        let scope = AsyncScope {
+            device: self,
+            scope: PhantomData,
+            env: PhantomData,
+        };
+        f(&scope)
+    }
+
+    /// Enqueues a non-blocking copy from `source` to `dest`.
+    /// Both buffers are retained by OpenCL until the copy has finished.
+    /// # Safety
+    /// The returned event does not borrow the buffers. Until the copy has finished (e.g. [`CLEvent::wait`]):
+    /// - `dest` must not be accessed by the host, e.g. with [`Buffer::try_as_slice`] on unified memory
+    /// - `source` must not be written by the host
+    /// # Panics
+    /// If the ranges have different lengths.
+    pub unsafe fn copy_async<T, SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, OpenCL>,
+        source_range: SR,
+        dest: &mut Buffer<T, OpenCL>,
+        dest_range: DR,
+    ) -> crate::Result<CLEvent> {
+        let source_range = bounds_to_range(source_range, source.len());
+        let dest_range = bounds_to_range(dest_range, dest.len());
+
+        let len = source_range.end - source_range.start;
+        assert_eq!(len, dest_range.end - dest_range.start);
+
+        let size = core::mem::size_of::<T>();
+        let mut event = null_mut();
+        check(unsafe {
+            clEnqueueCopyBuffer(
+                self.queue().0,
+                source.cl_ptr(),
+                dest.cl_ptr(),
+                source_range.start * size,
+                dest_range.start * size,
+                len * size,
+                0,
+                null(),
+                &mut event,
+            )
+        })?;
+
+        let event = unsafe { CLEvent::from_raw(event) };
+        self.record_event(CommandKind::Copy, &event, || "copy".into());
+        Ok(event)
+    }
+}
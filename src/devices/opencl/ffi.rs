@@ -0,0 +1,383 @@
+//! OpenCL functions and constants, which are not provided by `min-cl`.
+
+#![allow(non_camel_case_types, non_snake_case)]
+
+use core::ffi::c_void;
+
+use min_cl::api::{
+    cl_bool, cl_command_queue, cl_context, cl_device_id, cl_event, cl_int, cl_kernel, cl_mem,
+    cl_mem_flags, cl_program, cl_uint, size_t, OCLErrorKind,
+};
+
+pub type cl_event_info = cl_uint;
+pub type cl_kernel_info = cl_uint;
+pub type cl_profiling_info = cl_uint;
+
+pub const CL_EVENT_COMMAND_EXECUTION_STATUS: cl_event_info = 0x11D3;
+pub const CL_COMPLETE: cl_int = 0x0;
+
+pub const CL_KERNEL_FUNCTION_NAME: cl_kernel_info = 0x1190;
+pub const CL_KERNEL_NUM_ARGS: cl_kernel_info = 0x1191;
+
+pub type cl_kernel_arg_info = cl_uint;
+pub type cl_kernel_arg_address_qualifier = cl_uint;
+
+pub const CL_KERNEL_ARG_ADDRESS_QUALIFIER: cl_kernel_arg_info = 0x1196;
+pub const CL_KERNEL_ARG_TYPE_NAME: cl_kernel_arg_info = 0x1198;
+pub const CL_KERNEL_ARG_NAME: cl_kernel_arg_info = 0x119A;
+
+pub const CL_KERNEL_ARG_ADDRESS_GLOBAL: cl_kernel_arg_address_qualifier = 0x119B;
+pub const CL_KERNEL_ARG_ADDRESS_LOCAL: cl_kernel_arg_address_qualifier = 0x119C;
+pub const CL_KERNEL_ARG_ADDRESS_CONSTANT: cl_kernel_arg_address_qualifier = 0x119D;
+pub const CL_KERNEL_ARG_ADDRESS_PRIVATE: cl_kernel_arg_address_qualifier = 0x119E;
+
+pub type cl_kernel_work_group_info = cl_uint;
+
+pub const CL_KERNEL_WORK_GROUP_SIZE: cl_kernel_work_group_info = 0x11B0;
+pub const CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE: cl_kernel_work_group_info = 0x11B3;
+
+pub const CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE: u64 = 1 << 0;
+pub const CL_QUEUE_PROFILING_ENABLE: u64 = 1 << 1;
+
+pub const CL_PROFILING_COMMAND_QUEUED: cl_profiling_info = 0x1280;
+pub const CL_PROFILING_COMMAND_SUBMIT: cl_profiling_info = 0x1281;
+pub const CL_PROFILING_COMMAND_START: cl_profiling_info = 0x1282;
+pub const CL_PROFILING_COMMAND_END: cl_profiling_info = 0x1283;
+
+pub const CL_PROGRAM_BINARY_SIZES: cl_uint = 0x1165;
+pub const CL_PROGRAM_BINARIES: cl_uint = 0x1166;
+
+pub type cl_channel_order = cl_uint;
+pub type cl_channel_type = cl_uint;
+pub type cl_mem_object_type = cl_uint;
+
+pub const CL_R: cl_channel_order = 0x10B0;
+pub const CL_RG: cl_channel_order = 0x10B2;
+pub const CL_RGBA: cl_channel_order = 0x10B5;
+
+pub const CL_SIGNED_INT8: cl_channel_type = 0x10D7;
+pub const CL_SIGNED_INT16: cl_channel_type = 0x10D8;
+pub const CL_SIGNED_INT32: cl_channel_type = 0x10D9;
+pub const CL_UNSIGNED_INT8: cl_channel_type = 0x10DA;
+pub const CL_UNSIGNED_INT16: cl_channel_type = 0x10DB;
+pub const CL_UNSIGNED_INT32: cl_channel_type = 0x10DC;
+pub const CL_FLOAT: cl_channel_type = 0x10DE;
+
+pub const CL_MEM_OBJECT_IMAGE2D: cl_mem_object_type = 0x10F1;
+pub const CL_MEM_OBJECT_IMAGE3D: cl_mem_object_type = 0x10F2;
+
+pub type cl_buffer_create_type = cl_uint;
+
+pub const CL_BUFFER_CREATE_TYPE_REGION: cl_buffer_create_type = 0x1220;
+pub const CL_DEVICE_MEM_BASE_ADDR_ALIGN: cl_uint = 0x1019;
+
+#[repr(C)]
+pub struct cl_buffer_region {
+    pub origin: size_t,
+    pub size: size_t,
+}
+
+pub type cl_svm_mem_flags = cl_mem_flags;
+pub type cl_map_flags = u64;
+
+pub const CL_DEVICE_SVM_CAPABILITIES: cl_uint = 0x1053;
+pub const CL_DEVICE_SVM_COARSE_GRAIN_BUFFER: u64 = 1 << 0;
+pub const CL_DEVICE_SVM_FINE_GRAIN_BUFFER: u64 = 1 << 1;
+
+pub const CL_MEM_READ_WRITE: cl_svm_mem_flags = 1 << 0;
+pub const CL_MEM_SVM_FINE_GRAIN_BUFFER: cl_svm_mem_flags = 1 << 10;
+
+pub const CL_MAP_READ: cl_map_flags = 1 << 0;
+pub const CL_MAP_WRITE: cl_map_flags = 1 << 1;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct cl_image_format {
+    pub image_channel_order: cl_channel_order,
+    pub image_channel_data_type: cl_channel_type,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct cl_image_desc {
+    pub image_type: cl_mem_object_type,
+    pub image_width: size_t,
+    pub image_height: size_t,
+    pub image_depth: size_t,
+    pub image_array_size: size_t,
+    pub image_row_pitch: size_t,
+    pub image_slice_pitch: size_t,
+    pub num_mip_levels: cl_uint,
+    pub num_samples: cl_uint,
+    pub buffer: cl_mem,
+}
+
+#[cfg_attr(target_os = "macos", link(name = "OpenCL", kind = "framework"))]
+#[cfg_attr(not(target_os = "macos"), link(name = "OpenCL"))]
+extern "system" {
+    pub fn clGetEventInfo(
+        event: cl_event,
+        param_name: cl_event_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+
+    pub fn clRetainEvent(event: cl_event) -> cl_int;
+
+    pub fn clRetainContext(context: cl_context) -> cl_int;
+
+    pub fn clGetEventProfilingInfo(
+        event: cl_event,
+        param_name: cl_profiling_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+
+    pub fn clCreateProgramWithBinary(
+        context: cl_context,
+        num_devices: cl_uint,
+        device_list: *const cl_device_id,
+        lengths: *const size_t,
+        binaries: *const *const u8,
+        binary_status: *mut cl_int,
+        errcode_ret: *mut cl_int,
+    ) -> cl_program;
+
+    pub fn clGetKernelInfo(
+        kernel: cl_kernel,
+        param_name: cl_kernel_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+
+    pub fn clEnqueueCopyBufferRect(
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: *const size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        src_row_pitch: size_t,
+        src_slice_pitch: size_t,
+        dst_row_pitch: size_t,
+        dst_slice_pitch: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clEnqueueReadBufferRect(
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        blocking_read: cl_bool,
+        buffer_origin: *const size_t,
+        host_origin: *const size_t,
+        region: *const size_t,
+        buffer_row_pitch: size_t,
+        buffer_slice_pitch: size_t,
+        host_row_pitch: size_t,
+        host_slice_pitch: size_t,
+        ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clEnqueueWriteBufferRect(
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        blocking_write: cl_bool,
+        buffer_origin: *const size_t,
+        host_origin: *const size_t,
+        region: *const size_t,
+        buffer_row_pitch: size_t,
+        buffer_slice_pitch: size_t,
+        host_row_pitch: size_t,
+        host_slice_pitch: size_t,
+        ptr: *const c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clCreateImage(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_format: *const cl_image_format,
+        image_desc: *const cl_image_desc,
+        host_ptr: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem;
+
+    pub fn clEnqueueReadImage(
+        command_queue: cl_command_queue,
+        image: cl_mem,
+        blocking_read: cl_bool,
+        origin: *const size_t,
+        region: *const size_t,
+        row_pitch: size_t,
+        slice_pitch: size_t,
+        ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clEnqueueWriteImage(
+        command_queue: cl_command_queue,
+        image: cl_mem,
+        blocking_write: cl_bool,
+        origin: *const size_t,
+        region: *const size_t,
+        input_row_pitch: size_t,
+        input_slice_pitch: size_t,
+        ptr: *const c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clEnqueueCopyBufferToImage(
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_image: cl_mem,
+        src_offset: size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clEnqueueCopyImageToBuffer(
+        command_queue: cl_command_queue,
+        src_image: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: *const size_t,
+        region: *const size_t,
+        dst_offset: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    pub fn clFlush(command_queue: cl_command_queue) -> cl_int;
+
+    pub fn clGetKernelArgInfo(
+        kernel: cl_kernel,
+        arg_index: cl_uint,
+        param_name: cl_kernel_arg_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+
+    pub fn clGetKernelWorkGroupInfo(
+        kernel: cl_kernel,
+        device: cl_device_id,
+        param_name: cl_kernel_work_group_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+
+    pub fn clEnqueueMarkerWithWaitList(
+        command_queue: cl_command_queue,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clEnqueueBarrierWithWaitList(
+        command_queue: cl_command_queue,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clSetEventCallback(
+        event: cl_event,
+        command_exec_callback_type: cl_int,
+        pfn_notify: extern "system" fn(cl_event, cl_int, *mut c_void),
+        user_data: *mut c_void,
+    ) -> cl_int;
+
+    pub fn clCreateSubBuffer(
+        buffer: cl_mem,
+        flags: cl_mem_flags,
+        buffer_create_type: cl_buffer_create_type,
+        buffer_create_info: *const c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem;
+}
+
+// OpenCL 2.0 entry points, which are not provided by the OpenCL 1.2 framework of macOS
+#[cfg(not(target_os = "macos"))]
+#[link(name = "OpenCL")]
+extern "system" {
+    pub fn clSVMAlloc(
+        context: cl_context,
+        flags: cl_svm_mem_flags,
+        size: size_t,
+        alignment: cl_uint,
+    ) -> *mut c_void;
+
+    pub fn clSVMFree(context: cl_context, svm_pointer: *mut c_void);
+
+    pub fn clSetKernelArgSVMPointer(
+        kernel: cl_kernel,
+        arg_index: cl_uint,
+        arg_value: *const c_void,
+    ) -> cl_int;
+
+    pub fn clEnqueueSVMMemcpy(
+        command_queue: cl_command_queue,
+        blocking_copy: cl_bool,
+        dst_ptr: *mut c_void,
+        src_ptr: *const c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clEnqueueSVMMemFill(
+        command_queue: cl_command_queue,
+        svm_ptr: *mut c_void,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clEnqueueSVMMap(
+        command_queue: cl_command_queue,
+        blocking_map: cl_bool,
+        flags: cl_map_flags,
+        svm_ptr: *mut c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    pub fn clEnqueueSVMUnmap(
+        command_queue: cl_command_queue,
+        svm_ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+}
+
+/// Converts an OpenCL status code into a `Result`.
+pub fn check(value: cl_int) -> crate::Result<()> {
+    if value != 0 {
+        return Err(OCLErrorKind::from_value(value).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,430 @@
+use core::{
+    ffi::c_void,
+    fmt::Debug,
+    marker::PhantomData,
+    ptr::{null, null_mut},
+};
+
+use min_cl::api::{release_mem_object, MemFlags};
+
+use super::{
+    event::wait_recorded,
+    ffi::{
+        check, clCreateImage, clEnqueueCopyBufferToImage, clEnqueueCopyImageToBuffer,
+        clEnqueueReadImage, clEnqueueWriteImage, cl_channel_order, cl_channel_type, cl_image_desc,
+        cl_image_format, cl_mem_object_type, CL_FLOAT, CL_MEM_OBJECT_IMAGE2D,
+        CL_MEM_OBJECT_IMAGE3D, CL_R, CL_RG, CL_RGBA, CL_SIGNED_INT16, CL_SIGNED_INT32,
+        CL_SIGNED_INT8, CL_UNSIGNED_INT16, CL_UNSIGNED_INT32, CL_UNSIGNED_INT8,
+    },
+    AsClCvoidPtr, CommandKind,
+};
+use crate::{Buffer, CDatatype, Dim2, Dim3, OpenCL};
+
+/// The datatypes, which can be stored in the channels of an image.
+/// In a kernel, images of `float` are accessed with `read_imagef`/`write_imagef`,
+/// signed integers with `read_imagei`/`write_imagei` and unsigned integers with `read_imageui`/`write_imageui`.
+pub trait ImageDatatype: CDatatype {
+    const CHANNEL_TYPE: cl_channel_type;
+}
+
+macro_rules! impl_image_datatype {
+    ($($t:ty => $channel_type:ident),*) => {
+        $(
+            impl ImageDatatype for $t {
+                const CHANNEL_TYPE: cl_channel_type = $channel_type;
+            }
+        )*
+    };
+}
+
+impl_image_datatype!(
+    f32 => CL_FLOAT,
+    i8 => CL_SIGNED_INT8,
+    i16 => CL_SIGNED_INT16,
+    i32 => CL_SIGNED_INT32,
+    u8 => CL_UNSIGNED_INT8,
+    u16 => CL_UNSIGNED_INT16,
+    u32 => CL_UNSIGNED_INT32
+);
+
+/// The channels of a pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelOrder {
+    R,
+    RG,
+    RGBA,
+}
+
+impl ChannelOrder {
+    /// The number of values of a pixel.
+    #[inline]
+    pub fn channels(&self) -> usize {
+        match self {
+            ChannelOrder::R => 1,
+            ChannelOrder::RG => 2,
+            ChannelOrder::RGBA => 4,
+        }
+    }
+
+    #[inline]
+    fn as_cl(&self) -> cl_channel_order {
+        match self {
+            ChannelOrder::R => CL_R,
+            ChannelOrder::RG => CL_RG,
+            ChannelOrder::RGBA => CL_RGBA,
+        }
+    }
+}
+
+/// A 2D or 3D OpenCL image object.
+/// Unlike buffers, images are read with a sampler in kernels, which uses the texture caches and filtering hardware of the device.
+///
+/// The host data of an image is tightly packed: every pixel consists of [`ChannelOrder::channels`] values, rows and slices follow each other.
+/// # Example
+/// ```
+/// use custos::{opencl::{enqueue_kernel, ChannelOrder, CLImage}, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///
+///     let input = CLImage::<f32>::with_2d(&device, 2, 2, ChannelOrder::R, &[1., 2., 3., 4.])?;
+///     let output = CLImage::<f32>::new_2d(&device, 2, 2, ChannelOrder::R)?;
+///
+///     let src = "
+///         __constant sampler_t sampler = CLK_NORMALIZED_COORDS_FALSE | CLK_ADDRESS_CLAMP | CLK_FILTER_NEAREST;
+///
+///         __kernel void twice(__read_only image2d_t input, __write_only image2d_t output) {
+///             int2 pos = (int2)(get_global_id(0), get_global_id(1));
+///             write_imagef(output, pos, read_imagef(input, sampler, pos) * 2);
+///         }
+///     ";
+///     enqueue_kernel(&device, src, [2, 2, 0], None, &[&input, &output])?;
+///
+///     assert_eq!(output.read()?, vec![2., 4., 6., 8.]);
+///     Ok(())
+/// }
+/// ```
+pub struct CLImage<'a, T> {
+    pub mem: *mut c_void,
+    pub width: usize,
+    pub height: usize,
+    /// `1` for 2D images.
+    pub depth: usize,
+    pub order: ChannelOrder,
+    pub device: &'a OpenCL,
+    _p: PhantomData<T>,
+}
+
+impl<'a, T: ImageDatatype> CLImage<'a, T> {
+    /// Allocates a 2D image with `width` x `height` pixels.
+    pub fn new_2d(
+        device: &'a OpenCL,
+        width: usize,
+        height: usize,
+        order: ChannelOrder,
+    ) -> crate::Result<CLImage<'a, T>> {
+        CLImage::create(device, [width, height, 1], order, CL_MEM_OBJECT_IMAGE2D)
+    }
+
+    /// Allocates a 3D image with `width` x `height` x `depth` pixels.
+    pub fn new_3d(
+        device: &'a OpenCL,
+        width: usize,
+        height: usize,
+        depth: usize,
+        order: ChannelOrder,
+    ) -> crate::Result<CLImage<'a, T>> {
+        CLImage::create(device, [width, height, depth], order, CL_MEM_OBJECT_IMAGE3D)
+    }
+
+    /// Allocates a 2D image and writes `data` into it.
+    /// # Panics
+    /// If `data` does not contain exactly one value per channel and pixel.
+    pub fn with_2d(
+        device: &'a OpenCL,
+        width: usize,
+        height: usize,
+        order: ChannelOrder,
+        data: &[T],
+    ) -> crate::Result<CLImage<'a, T>> {
+        let mut image = CLImage::new_2d(device, width, height, order)?;
+        image.write(data)?;
+        Ok(image)
+    }
+
+    fn create(
+        device: &'a OpenCL,
+        [width, height, depth]: [usize; 3],
+        order: ChannelOrder,
+        image_type: cl_mem_object_type,
+    ) -> crate::Result<CLImage<'a, T>> {
+        let format = cl_image_format {
+            image_channel_order: order.as_cl(),
+            image_channel_data_type: T::CHANNEL_TYPE,
+        };
+
+        let desc = cl_image_desc {
+            image_type,
+            image_width: width,
+            image_height: height,
+            image_depth: if image_type == CL_MEM_OBJECT_IMAGE3D {
+                depth
+            } else {
+                0
+            },
+            image_array_size: 0,
+            image_row_pitch: 0,
+            image_slice_pitch: 0,
+            num_mip_levels: 0,
+            num_samples: 0,
+            buffer: null_mut(),
+        };
+
+        let mut err = 0;
+        let mem = unsafe {
+            clCreateImage(
+                device.ctx().0,
+                MemFlags::MemReadWrite as u64,
+                &format,
+                &desc,
+                null_mut(),
+                &mut err,
+            )
+        };
+        check(err)?;
+
+        Ok(CLImage {
+            mem,
+            width,
+            height,
+            depth,
+            order,
+            device,
+            _p: PhantomData,
+        })
+    }
+
+    /// The number of values of the image (pixels * channels).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.width * self.height * self.depth * self.order.channels()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn region(&self) -> [usize; 3] {
+        [self.width, self.height, self.depth]
+    }
+
+    /// Reads the pixels of the image.
+    pub fn read(&self) -> crate::Result<Vec<T>> {
+        let mut data = vec![T::default(); self.len()];
+
+        let mut event = null_mut();
+        let status = unsafe {
+            clEnqueueReadImage(
+                self.device.queue().0,
+                self.mem,
+                1,
+                [0; 3].as_ptr(),
+                self.region().as_ptr(),
+                0,
+                0,
+                data.as_mut_ptr().cast(),
+                0,
+                null(),
+                &mut event,
+            )
+        };
+        wait_recorded(self.device, status, event, CommandKind::Read, "read_image")?;
+        Ok(data)
+    }
+
+    /// Overwrites all pixels of the image.
+    /// # Panics
+    /// If `data` does not contain exactly one value per channel and pixel.
+    pub fn write(&mut self, data: &[T]) -> crate::Result<()> {
+        assert_eq!(data.len(), self.len(), "The data does not match the image.");
+
+        let mut event = null_mut();
+        let status = unsafe {
+            clEnqueueWriteImage(
+                self.device.queue().0,
+                self.mem,
+                1,
+                [0; 3].as_ptr(),
+                self.region().as_ptr(),
+                0,
+                0,
+                data.as_ptr().cast(),
+                0,
+                null(),
+                &mut event,
+            )
+        };
+        wait_recorded(
+            self.device,
+            status,
+            event,
+            CommandKind::Write,
+            "write_image",
+        )
+    }
+
+    /// Copies a matrix into a new 2D image on the device.
+    /// The `A` columns of the matrix contain the channels of `A / channels` pixels.
+    /// # Panics
+    /// If `A` is not a multiple of the number of channels.
+    pub fn from_dim2<const B: usize, const A: usize>(
+        buf: &Buffer<'a, T, OpenCL, Dim2<B, A>>,
+        order: ChannelOrder,
+    ) -> crate::Result<CLImage<'a, T>> {
+        assert_eq!(
+            A % order.channels(),
+            0,
+            "The columns of the matrix are not a multiple of the channels."
+        );
+
+        let image = CLImage::new_2d(buf.device(), A / order.channels(), B, order)?;
+        image.copy_from_buf(buf.ptr.ptr)?;
+        Ok(image)
+    }
+
+    /// Copies an array of `C` matrices into a new 3D image on the device, see [`CLImage::from_dim2`].
+    pub fn from_dim3<const C: usize, const B: usize, const A: usize>(
+        buf: &Buffer<'a, T, OpenCL, Dim3<C, B, A>>,
+        order: ChannelOrder,
+    ) -> crate::Result<CLImage<'a, T>> {
+        assert_eq!(
+            A % order.channels(),
+            0,
+            "The columns of the matrices are not a multiple of the channels."
+        );
+
+        let image = CLImage::new_3d(buf.device(), A / order.channels(), B, C, order)?;
+        image.copy_from_buf(buf.ptr.ptr)?;
+        Ok(image)
+    }
+
+    /// Copies the 2D image into a new matrix on the device.
+    /// # Panics
+    /// If the shape does not match the image.
+    pub fn to_dim2<const B: usize, const A: usize>(
+        &self,
+    ) -> crate::Result<Buffer<'a, T, OpenCL, Dim2<B, A>>> {
+        assert_eq!(
+            [1, B, A],
+            [self.depth, self.height, self.width * self.order.channels()],
+            "The shape does not match the image."
+        );
+
+        let buf = Buffer::new(self.device, B * A);
+        self.copy_to_buf(buf.ptr.ptr)?;
+        Ok(buf)
+    }
+
+    /// Copies the 3D image into a new array of matrices on the device.
+    /// # Panics
+    /// If the shape does not match the image.
+    pub fn to_dim3<const C: usize, const B: usize, const A: usize>(
+        &self,
+    ) -> crate::Result<Buffer<'a, T, OpenCL, Dim3<C, B, A>>> {
+        assert_eq!(
+            [C, B, A],
+            [self.depth, self.height, self.width * self.order.channels()],
+            "The shape does not match the image."
+        );
+
+        let buf = Buffer::new(self.device, C * B * A);
+        self.copy_to_buf(buf.ptr.ptr)?;
+        Ok(buf)
+    }
+
+    fn copy_from_buf(&self, buf: *mut c_void) -> crate::Result<()> {
+        let mut event = null_mut();
+        let status = unsafe {
+            clEnqueueCopyBufferToImage(
+                self.device.queue().0,
+                buf,
+                self.mem,
+                0,
+                [0; 3].as_ptr(),
+                self.region().as_ptr(),
+                0,
+                null(),
+                &mut event,
+            )
+        };
+        wait_recorded(
+            self.device,
+            status,
+            event,
+            CommandKind::Copy,
+            "buffer_to_image",
+        )
+    }
+
+    fn copy_to_buf(&self, buf: *mut c_void) -> crate::Result<()> {
+        let mut event = null_mut();
+        let status = unsafe {
+            clEnqueueCopyImageToBuffer(
+                self.device.queue().0,
+                self.mem,
+                buf,
+                [0; 3].as_ptr(),
+                self.region().as_ptr(),
+                0,
+                0,
+                null(),
+                &mut event,
+            )
+        };
+        wait_recorded(
+            self.device,
+            status,
+            event,
+            CommandKind::Copy,
+            "image_to_buffer",
+        )
+    }
+}
+
+impl<T> Drop for CLImage<'_, T> {
+    fn drop(&mut self) {
+        if self.mem.is_null() {
+            return;
+        }
+        unsafe { release_mem_object(self.mem).unwrap() };
+    }
+}
+
+impl<T> Debug for CLImage<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CLImage")
+            .field("mem", &self.mem)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("depth", &self.depth)
+            .field("order", &self.order)
+            .field("datatype", &core::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T> AsClCvoidPtr for CLImage<'_, T> {
+    #[inline]
+    fn as_cvoid_ptr(&self) -> *const c_void {
+        self.mem
+    }
+}
+
+impl<T> AsClCvoidPtr for &CLImage<'_, T> {
+    #[inline]
+    fn as_cvoid_ptr(&self) -> *const c_void {
+        self.mem
+    }
+}
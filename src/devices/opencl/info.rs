@@ -0,0 +1,203 @@
+use core::{ffi::c_void, ptr::null_mut};
+
+use min_cl::api::{
+    clGetDeviceIDs, clGetDeviceInfo, clGetPlatformIDs, clGetPlatformInfo, cl_device_id,
+    cl_platform_id, CLIntDevice, OCLErrorKind,
+};
+
+use super::{ffi::check, OpenCL};
+use crate::{Backend, DeviceInfo, DeviceProperties};
+
+const CL_PLATFORM_NAME: u32 = 0x0902;
+const CL_PLATFORM_VENDOR: u32 = 0x0903;
+
+const CL_DEVICE_TYPE_ALL: u64 = 0xFFFF_FFFF;
+
+const CL_DEVICE_MAX_MEM_ALLOC_SIZE: u32 = 0x1010;
+const CL_DEVICE_GLOBAL_MEM_SIZE: u32 = 0x101F;
+const CL_DEVICE_EXTENSIONS: u32 = 0x1030;
+const CL_DEVICE_DOUBLE_FP_CONFIG: u32 = 0x1032;
+const CL_DEVICE_HOST_UNIFIED_MEMORY: u32 = 0x1035;
+pub(super) const CL_DEVICE_NAME: u32 = 0x102B;
+const CL_DEVICE_VENDOR: u32 = 0x102C;
+pub(super) const CL_DRIVER_VERSION: u32 = 0x102D;
+
+/// An OpenCL device found by [`cl_devices`].
+#[derive(Debug, Clone)]
+pub struct CLDeviceEntry {
+    /// The index of the platform in the list of all platforms.
+    pub platform_idx: usize,
+    pub platform_name: String,
+    pub platform_vendor: String,
+    /// Can be used to create an [`OpenCL`] device via [`OpenCL::from_cl_device`].
+    pub device: CLIntDevice,
+    pub props: DeviceProperties,
+}
+
+fn platform_string(platform: cl_platform_id, param: u32) -> crate::Result<String> {
+    let mut size = 0;
+    check(unsafe { clGetPlatformInfo(platform, param, 0, null_mut(), &mut size) })?;
+
+    let mut value = vec![0u8; size];
+    check(unsafe {
+        clGetPlatformInfo(
+            platform,
+            param,
+            size,
+            value.as_mut_ptr() as *mut c_void,
+            null_mut(),
+        )
+    })?;
+    Ok(c_string(value))
+}
+
+fn device_bytes(device: cl_device_id, param: u32) -> crate::Result<Vec<u8>> {
+    let mut size = 0;
+    check(unsafe { clGetDeviceInfo(device, param, 0, null_mut(), &mut size) })?;
+
+    let mut value = vec![0u8; size];
+    check(unsafe {
+        clGetDeviceInfo(
+            device,
+            param,
+            size,
+            value.as_mut_ptr() as *mut c_void,
+            null_mut(),
+        )
+    })?;
+    Ok(value)
+}
+
+#[inline]
+pub(super) fn device_string(device: cl_device_id, param: u32) -> crate::Result<String> {
+    Ok(c_string(device_bytes(device, param)?))
+}
+
+/// Reads a `cl_ulong`, `cl_bool` (`cl_uint`) or bitfield value.
+pub(super) fn device_u64(device: cl_device_id, param: u32) -> crate::Result<u64> {
+    let bytes = device_bytes(device, param)?;
+    match bytes.len() {
+        4 => Ok(u32::from_ne_bytes(bytes.try_into().unwrap()) as u64),
+        8 => Ok(u64::from_ne_bytes(bytes.try_into().unwrap())),
+        _ => Err(OCLErrorKind::InvalidValue.into()),
+    }
+}
+
+/// Converts a null terminated C string.
+fn c_string(mut bytes: Vec<u8>) -> String {
+    if let Some(end) = bytes.iter().position(|&b| b == 0) {
+        bytes.truncate(end);
+    }
+    String::from_utf8_lossy(&bytes).trim().to_string()
+}
+
+/// Returns the OpenCL platforms.
+pub fn cl_platforms() -> crate::Result<Vec<cl_platform_id>> {
+    let mut count = 0;
+    check(unsafe { clGetPlatformIDs(0, null_mut(), &mut count) })?;
+
+    let mut platforms = vec![null_mut(); count as usize];
+    check(unsafe { clGetPlatformIDs(count, platforms.as_mut_ptr(), null_mut()) })?;
+    Ok(platforms)
+}
+
+/// Queries the properties of an OpenCL device.
+pub fn cl_device_properties(device: CLIntDevice) -> crate::Result<DeviceProperties> {
+    let id = device.0;
+
+    // CL_DEVICE_DOUBLE_FP_CONFIG is only supported from OpenCL 1.2 on
+    let f64_support = device_u64(id, CL_DEVICE_DOUBLE_FP_CONFIG).unwrap_or(0) != 0
+        || device_string(id, CL_DEVICE_EXTENSIONS)?
+            .split_whitespace()
+            .any(|extension| extension == "cl_khr_fp64");
+
+    Ok(DeviceProperties {
+        backend: Backend::OpenCL,
+        name: device_string(id, CL_DEVICE_NAME)?,
+        vendor: device_string(id, CL_DEVICE_VENDOR)?,
+        global_mem_size: Some(device_u64(id, CL_DEVICE_GLOBAL_MEM_SIZE)?),
+        max_alloc_size: Some(device_u64(id, CL_DEVICE_MAX_MEM_ALLOC_SIZE)?),
+        unified_mem: device_u64(id, CL_DEVICE_HOST_UNIFIED_MEMORY)? != 0,
+        f64_support,
+    })
+}
+
+/// Lists all devices (of any device type, e.g. CPU devices of POCL as well) of all OpenCL platforms.
+/// # Example
+/// ```
+/// use custos::{opencl::cl_devices, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     for entry in cl_devices()? {
+///         println!("{}: {:?}", entry.platform_name, entry.props);
+///     }
+///
+///     // there is no OpenCL device on this machine
+///     let Some(entry) = cl_devices()?.into_iter().next() else {
+///         return Ok(());
+///     };
+///     let device = OpenCL::from_cl_device(entry.device)?;
+///     Ok(())
+/// }
+/// ```
+pub fn cl_devices() -> crate::Result<Vec<CLDeviceEntry>> {
+    let mut entries = Vec::new();
+
+    for (platform_idx, platform) in cl_platforms()?.into_iter().enumerate() {
+        let mut count = 0;
+        let value =
+            unsafe { clGetDeviceIDs(platform, CL_DEVICE_TYPE_ALL, 0, null_mut(), &mut count) };
+
+        // CL_DEVICE_NOT_FOUND: this platform has no devices
+        if value == -1 {
+            continue;
+        }
+        check(value)?;
+
+        let mut devices = vec![null_mut(); count as usize];
+        check(unsafe {
+            clGetDeviceIDs(
+                platform,
+                CL_DEVICE_TYPE_ALL,
+                count,
+                devices.as_mut_ptr(),
+                null_mut(),
+            )
+        })?;
+
+        let platform_name = platform_string(platform, CL_PLATFORM_NAME)?;
+        let platform_vendor = platform_string(platform, CL_PLATFORM_VENDOR)?;
+
+        for device in devices {
+            let device = CLIntDevice(device);
+            entries.push(CLDeviceEntry {
+                platform_idx,
+                platform_name: platform_name.clone(),
+                platform_vendor: platform_vendor.clone(),
+                device,
+                props: cl_device_properties(device)?,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+impl DeviceInfo for OpenCL {
+    #[inline]
+    fn info(&self) -> crate::Result<DeviceProperties> {
+        cl_device_properties(self.device())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::c_string;
+
+    #[test]
+    fn test_c_string() {
+        assert_eq!(c_string(b"pthread-cpu\0".to_vec()), "pthread-cpu");
+        assert_eq!(c_string(b"Intel \0\0".to_vec()), "Intel");
+        assert_eq!(c_string(Vec::new()), "");
+    }
+}
@@ -1,9 +1,7 @@
+use super::{build_program_with_log, BinaryCache, BuildOptions, CLKernel, CLProgram};
 use crate::{Error, Node, OpenCL};
-use min_cl::api::{
-    build_program, create_kernels_in_program, create_program_with_source, release_mem_object,
-    Kernel,
-};
-use std::{collections::HashMap, ffi::c_void, rc::Rc};
+use min_cl::api::{create_program_with_source, release_mem_object};
+use std::{collections::HashMap, ffi::c_void};
 
 #[derive(Debug)]
 pub struct RawCL {
@@ -19,14 +17,29 @@ impl Drop for RawCL {
     }
 }
 
-#[derive(Debug, Default)]
-/// This stores the previously compiled OpenCL kernels.
+#[derive(Debug)]
+/// This stores the previously compiled OpenCL programs.
 pub struct KernelCacheCL {
-    pub kernel_cache: HashMap<String, Rc<Kernel>>,
+    /// The programs are identified by their source code and build options.
+    pub programs: HashMap<(String, String), CLProgram>,
+    /// If set, the binaries of the programs are cached on disk.
+    pub binary_cache: Option<BinaryCache>,
+}
+
+impl Default for KernelCacheCL {
+    /// The binary cache is read from the environment variable `CUSTOS_CL_CACHE_DIR`.
+    #[inline]
+    fn default() -> Self {
+        KernelCacheCL {
+            programs: HashMap::new(),
+            binary_cache: BinaryCache::from_env(),
+        }
+    }
 }
 
 impl KernelCacheCL {
-    /// Returns a cached kernel. If the kernel source code does not exist, a new kernel is created and cached.
+    /// Returns a cached kernel and its signature. If the kernel source code does not exist, a new kernel is created and cached.
+    /// The source code must contain a single kernel. Otherwise, use [`KernelCacheCL::named_kernel`].
     ///
     /// # Example
     /// ```
@@ -37,7 +50,8 @@ impl KernelCacheCL {
     ///     let device = OpenCL::new(0)?;
     ///     
     ///     let mut kernel_cache = KernelCacheCL {
-    ///         kernel_cache: HashMap::new(),
+    ///         programs: HashMap::new(),
+    ///         binary_cache: None,
     ///     };
     ///     
     ///     let mut kernel_fn = || kernel_cache.kernel_cache(&device, "
@@ -51,17 +65,82 @@ impl KernelCacheCL {
     ///     Ok(())
     /// }
     /// ```
-    pub fn kernel_cache(&mut self, device: &OpenCL, src: &str) -> Result<Rc<Kernel>, Error> {
-        if let Some(kernel) = self.kernel_cache.get(src) {
-            return Ok(kernel.clone());
-        }
+    #[inline]
+    pub fn kernel_cache(&mut self, device: &OpenCL, src: &str) -> Result<CLKernel, Error> {
+        self.kernel_cache_with_options(device, src, &BuildOptions::default())
+    }
+
+    /// Returns a cached kernel, which was built with the given options.
+    /// # Errors
+    /// - A [`CLBuildError`](super::CLBuildError) containing the build log, if the kernel could not be built.
+    /// - [`DeviceError::AmbiguousKernel`](crate::DeviceError::AmbiguousKernel), if the source code contains several kernels.
+    #[inline]
+    pub fn kernel_cache_with_options(
+        &mut self,
+        device: &OpenCL,
+        src: &str,
+        options: &BuildOptions,
+    ) -> Result<CLKernel, Error> {
+        self.program(device, src, options)?.only_kernel()
+    }
+
+    /// Returns the kernel with the given function name out of a cached program.
+    /// The program is built only once for all of its kernels.
+    /// # Example
+    /// ```
+    /// use custos::{OpenCL, opencl::{BuildOptions, KernelCacheCL}};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = OpenCL::new(0)?;
+    ///     let mut kernel_cache = KernelCacheCL::default();
+    ///
+    ///     let src = "
+    ///         __kernel void add(__global float* x) {}
+    ///         __kernel void mul(__global float* x) {}
+    ///     ";
+    ///
+    ///     let options = BuildOptions::default();
+    ///     let add = kernel_cache.named_kernel(&device, src, "add", &options)?;
+    ///     let mul = kernel_cache.named_kernel(&device, src, "mul", &options)?;
+    ///
+    ///     assert_ne!(add.0, mul.0);
+    ///     assert_eq!(kernel_cache.programs.len(), 1);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn named_kernel(
+        &mut self,
+        device: &OpenCL,
+        src: &str,
+        name: &str,
+        options: &BuildOptions,
+    ) -> Result<CLKernel, Error> {
+        self.program(device, src, options)?.kernel(name)
+    }
+
+    /// Returns a cached program. If the program does not exist, it is built and cached.
+    pub fn program(
+        &mut self,
+        device: &OpenCL,
+        src: &str,
+        options: &BuildOptions,
+    ) -> Result<&CLProgram, Error> {
+        let key = (src.to_string(), options.to_string());
 
-        let program = create_program_with_source(&device.ctx(), src)?;
-        build_program(&program, &[device.device()], Some("-cl-std=CL1.2"))?; //-cl-single-precision-constant
-        let kernel = create_kernels_in_program(&program)?[0].clone();
+        if !self.programs.contains_key(&key) {
+            let program = match &self.binary_cache {
+                Some(binary_cache) => binary_cache.build(device, src, options)?,
+                None => {
+                    let program = create_program_with_source(&device.ctx(), src)?;
+                    build_program_with_log(&program, device.device(), src, options)?;
+                    program
+                }
+            };
+            self.programs.insert(key.clone(), CLProgram::new(program)?);
+        }
 
-        self.kernel_cache.insert(src.to_string(), kernel.clone());
-        Ok(kernel)
+        Ok(&self.programs[&key])
     }
 }
 
@@ -76,7 +155,8 @@ mod tests {
         let device = OpenCL::new(0)?;
 
         let mut kernel_cache = KernelCacheCL {
-            kernel_cache: HashMap::new(),
+            programs: HashMap::new(),
+            binary_cache: None,
         };
 
         let mut kernel_fn = || {
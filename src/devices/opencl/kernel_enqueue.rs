@@ -1,6 +1,11 @@
-use crate::{number::Number, Buffer, OpenCL, Shape};
-use min_cl::api::{enqueue_nd_range_kernel, set_kernel_arg, OCLErrorKind};
-use std::{ffi::c_void, mem::size_of};
+use super::{ffi::check, BuildOptions, CLEvent, CLKernel, CLQueue, CommandKind, KernelArgError};
+use crate::{number::Number, Buffer, ErrorKind, OpenCL, Shape};
+use min_cl::api::{clEnqueueNDRangeKernel, cl_command_queue, set_kernel_arg, Kernel, OCLErrorKind};
+use std::{
+    ffi::c_void,
+    mem::size_of,
+    ptr::{null, null_mut},
+};
 
 pub trait AsClCvoidPtr {
     fn as_cvoid_ptr(&self) -> *const c_void;
@@ -10,6 +15,69 @@ pub trait AsClCvoidPtr {
     fn ptr_size(&self) -> usize {
         std::mem::size_of::<*const c_void>()
     }
+    /// Shared virtual memory is passed with `clSetKernelArgSVMPointer`.
+    fn is_svm(&self) -> bool {
+        false
+    }
+    /// Local memory is passed with its size in bytes (`ptr_size`) and without a pointer.
+    fn is_local(&self) -> bool {
+        false
+    }
+}
+
+/// Local memory for a `__local` parameter of a kernel, which is shared by the work-items of a work-group.
+/// # Example
+/// ```
+/// use custos::{opencl::{enqueue_kernel, LocalMem}, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let buf = Buffer::<i32, _>::from((&device, [1, 2, 3, 4]));
+///
+///     let src = "
+///         __kernel void reverse(__global int* x, __local int* tmp) {
+///             size_t idx = get_local_id(0);
+///             tmp[idx] = x[idx];
+///             barrier(CLK_LOCAL_MEM_FENCE);
+///             x[idx] = tmp[get_local_size(0) - 1 - idx];
+///         }
+///     ";
+///
+///     enqueue_kernel(&device, src, [4, 0, 0], Some([4, 1, 1]), &[&buf, &LocalMem::new::<i32>(4)])?;
+///     assert_eq!(buf.read(), vec![4, 3, 2, 1]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalMem {
+    bytes: usize,
+}
+
+impl LocalMem {
+    /// Local memory for `len` elements of type `T`.
+    #[inline]
+    pub fn new<T>(len: usize) -> LocalMem {
+        LocalMem {
+            bytes: len * size_of::<T>(),
+        }
+    }
+}
+
+impl AsClCvoidPtr for LocalMem {
+    #[inline]
+    fn as_cvoid_ptr(&self) -> *const c_void {
+        null()
+    }
+
+    #[inline]
+    fn ptr_size(&self) -> usize {
+        self.bytes
+    }
+
+    #[inline]
+    fn is_local(&self) -> bool {
+        true
+    }
 }
 
 impl<'a, T, S: Shape> AsClCvoidPtr for &Buffer<'a, T, OpenCL, S> {
@@ -46,7 +114,233 @@ pub fn enqueue_kernel(
     args: &[&dyn AsClCvoidPtr],
 ) -> crate::Result<()> {
     let kernel = device.kernel_cache.borrow_mut().kernel_cache(device, src)?;
+    enqueue(device, &kernel, gws, lws, args, &[])?.wait()
+}
+
+/// Enqueues a kernel, which starts after all events in `wait_for` have completed, e.g. non-blocking transfers.
+/// Unlike [`enqueue_kernel`], this does not wait for the kernel to finish.
+/// # Safety
+/// The returned event does not borrow the arguments. Until the kernel has finished (e.g. [`CLEvent::wait`]),
+/// the buffers in `args` must stay alive and must not be accessed by the host,
+/// e.g. with [`Buffer::try_as_slice`] on unified memory or `as_slice` on a fine-grained [`SVM`](super::SVM) buffer.
+/// # Example
+/// ```
+/// use custos::{opencl::enqueue_kernel_after, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let lhs = Buffer::<f32, _>::from((&device, [1., 2., 3.]));
+///     let mut out = Buffer::<f32, _>::new(&device, 3);
+///
+///     let src = "
+///         __kernel void add(__global const float* lhs, __global float* out) {
+///             size_t id = get_global_id(0);
+///             out[id] += lhs[id];
+///         }
+///     ";
+///
+///     // Safety: the buffers are only accessed after the kernel has finished
+///     unsafe {
+///         let copy = device.copy_async(&lhs, .., &mut out, ..)?;
+///         enqueue_kernel_after(&device, src, [3, 0, 0], None, &[&lhs, &out], &[&copy])?.wait()?;
+///     }
+///
+///     assert_eq!(out.read(), vec![2., 4., 6.]);
+///     Ok(())
+/// }
+/// ```
+pub unsafe fn enqueue_kernel_after(
+    device: &OpenCL,
+    src: &str,
+    gws: [usize; 3],
+    lws: Option<[usize; 3]>,
+    args: &[&dyn AsClCvoidPtr],
+    wait_for: &[&CLEvent],
+) -> crate::Result<CLEvent> {
+    let kernel = device.kernel_cache.borrow_mut().kernel_cache(device, src)?;
+    enqueue(device, &kernel, gws, lws, args, wait_for)
+}
+
+/// Enqueues a kernel like [`enqueue_kernel`], which is built with custom build options.
+/// The kernel is cached per source code and build options.
+/// # Example
+/// ```
+/// use custos::{opencl::{enqueue_kernel_with_options, BuildOptions}, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let out = Buffer::<f32, _>::new(&device, 4);
+///
+///     let src = "
+///         __kernel void fill(__global float* out) {
+///             out[get_global_id(0)] = VALUE;
+///         }
+///     ";
+///
+///     let options = BuildOptions::new().define("VALUE", "2.5f").fast_relaxed_math();
+///     enqueue_kernel_with_options(&device, src, &options, [4, 0, 0], None, &[&out])?;
+///
+///     assert_eq!(out.read(), vec![2.5; 4]);
+///     Ok(())
+/// }
+/// ```
+pub fn enqueue_kernel_with_options(
+    device: &OpenCL,
+    src: &str,
+    options: &BuildOptions,
+    gws: [usize; 3],
+    lws: Option<[usize; 3]>,
+    args: &[&dyn AsClCvoidPtr],
+) -> crate::Result<()> {
+    let kernel = device
+        .kernel_cache
+        .borrow_mut()
+        .kernel_cache_with_options(device, src, options)?;
+    enqueue(device, &kernel, gws, lws, args, &[])?.wait()
+}
+
+/// Enqueues the kernel with the function name `name` out of a program, which may contain several kernels.
+/// The program is built only once for all of its kernels.
+/// # Example
+/// ```
+/// use custos::{opencl::{enqueue_named_kernel, BuildOptions}, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let buf = Buffer::<f32, _>::from((&device, [1., 2., 3.]));
+///
+///     let src = "
+///         __kernel void add_one(__global float* x) {
+///             x[get_global_id(0)] += 1;
+///         }
+///         __kernel void double_it(__global float* x) {
+///             x[get_global_id(0)] *= 2;
+///         }
+///     ";
+///
+///     let options = BuildOptions::default();
+///     enqueue_named_kernel(&device, src, "add_one", &options, [3, 0, 0], None, &[&buf])?;
+///     enqueue_named_kernel(&device, src, "double_it", &options, [3, 0, 0], None, &[&buf])?;
+///
+///     assert_eq!(buf.read(), vec![4., 6., 8.]);
+///     Ok(())
+/// }
+/// ```
+pub fn enqueue_named_kernel(
+    device: &OpenCL,
+    src: &str,
+    name: &str,
+    options: &BuildOptions,
+    gws: [usize; 3],
+    lws: Option<[usize; 3]>,
+    args: &[&dyn AsClCvoidPtr],
+) -> crate::Result<()> {
+    let kernel = device
+        .kernel_cache
+        .borrow_mut()
+        .named_kernel(device, src, name, options)?;
+    enqueue(device, &kernel, gws, lws, args, &[])?.wait()
+}
+
+/// Enqueues a kernel on a queue, which was created by [`OpenCL::create_queue`].
+/// The kernel starts after all events in `wait_for` have completed, which may belong to any queue of the device.
+/// # Safety
+/// The returned event does not borrow the arguments. Until the kernel has finished (e.g. [`CLEvent::wait`]),
+/// the buffers in `args` must stay alive and must not be accessed by the host,
+/// e.g. with [`Buffer::try_as_slice`] on unified memory or `as_slice` on a fine-grained [`SVM`](super::SVM) buffer.
+/// # Example
+/// ```
+/// use custos::{opencl::enqueue_kernel_on, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let first = device.create_queue(false)?;
+///     let second = device.create_queue(true)?;
+///
+///     let buf = Buffer::<f32, _>::from((&device, [1., 2., 3.]));
+///
+///     let src = "
+///         __kernel void add_one(__global float* x) {
+///             x[get_global_id(0)] += 1;
+///         }
+///     ";
+///
+///     // Safety: `buf` is only read after both kernels have finished
+///     unsafe {
+///         let add = enqueue_kernel_on(&first, src, [3, 0, 0], None, &[&buf], &[])?;
+///         // starts after the kernel on the first queue has finished
+///         enqueue_kernel_on(&second, src, [3, 0, 0], None, &[&buf], &[&add])?.wait()?;
+///     }
+///
+///     assert_eq!(buf.read(), vec![3., 4., 5.]);
+///     Ok(())
+/// }
+/// ```
+pub unsafe fn enqueue_kernel_on(
+    queue: &CLQueue,
+    src: &str,
+    gws: [usize; 3],
+    lws: Option<[usize; 3]>,
+    args: &[&dyn AsClCvoidPtr],
+    wait_for: &[&CLEvent],
+) -> crate::Result<CLEvent> {
+    let device = queue.device();
+    let kernel = device.kernel_cache.borrow_mut().kernel_cache(device, src)?;
+
+    queue.submit_dependencies(wait_for)?;
+    let event = enqueue_raw(queue.as_raw(), &kernel, gws, None, lws, args, wait_for)?;
+    queue.record(CommandKind::Kernel, &event, || {
+        kernel.signature.name.clone()
+    });
+    queue.flush()?;
+    Ok(event)
+}
+
+pub(super) fn enqueue(
+    device: &OpenCL,
+    kernel: &CLKernel,
+    gws: [usize; 3],
+    lws: Option<[usize; 3]>,
+    args: &[&dyn AsClCvoidPtr],
+    wait_for: &[&CLEvent],
+) -> crate::Result<CLEvent> {
+    let event = enqueue_raw(device.queue().0, kernel, gws, None, lws, args, wait_for)?;
+    device.record_event(CommandKind::Kernel, &event, || {
+        kernel.signature.name.clone()
+    });
+    Ok(event)
+}
+
+fn set_arg(kernel: &Kernel, idx: usize, arg: &dyn AsClCvoidPtr) -> crate::Result<()> {
+    #[cfg(not(target_os = "macos"))]
+    if arg.is_svm() {
+        return super::set_svm_kernel_arg(kernel, idx, arg.as_cvoid_ptr());
+    }
+
+    if arg.is_local() {
+        // the null pointer is passed as is, like a number
+        return set_kernel_arg(kernel, idx, null(), arg.ptr_size(), true);
+    }
+    set_kernel_arg(
+        kernel,
+        idx,
+        arg.as_cvoid_ptr(),
+        arg.ptr_size(),
+        arg.is_num(),
+    )
+}
 
+/// Validates the arguments with the signature of the kernel and enqueues the kernel.
+/// The global ids start at `offset`, if it is set.
+pub(super) fn enqueue_raw(
+    queue: cl_command_queue,
+    kernel: &CLKernel,
+    gws: [usize; 3],
+    offset: Option<[usize; 3]>,
+    lws: Option<[usize; 3]>,
+    args: &[&dyn AsClCvoidPtr],
+    wait_for: &[&CLEvent],
+) -> crate::Result<CLEvent> {
     let wd;
     if gws[0] == 0 {
         return Err(OCLErrorKind::InvalidGlobalWorkSize.into());
@@ -58,16 +352,43 @@ pub fn enqueue_kernel(
         wd = 3;
     }
 
+    kernel.signature.check_args(args)?;
+
     for (idx, arg) in args.iter().enumerate() {
-        set_kernel_arg(
-            &kernel,
-            idx,
-            arg.as_cvoid_ptr(),
-            arg.ptr_size(),
-            arg.is_num(),
-        )
-        .unwrap();
+        set_arg(kernel, idx, *arg).map_err(|err| match err.kind::<OCLErrorKind>() {
+            Some(&kind) => KernelArgError::Rejected {
+                kernel: kernel.signature.name.clone(),
+                idx,
+                kind,
+            }
+            .into(),
+            None => err,
+        })?;
     }
-    enqueue_nd_range_kernel(&device.queue(), &kernel, wd, &gws, lws.as_ref(), None)?;
-    Ok(())
+
+    let wait_for = wait_for
+        .iter()
+        .map(|event| event.as_raw())
+        .collect::<Vec<_>>();
+
+    let mut event = null_mut();
+    check(unsafe {
+        clEnqueueNDRangeKernel(
+            queue,
+            kernel.0,
+            wd,
+            offset.as_ref().map_or(null(), |offset| offset.as_ptr()),
+            gws.as_ptr(),
+            lws.as_ref().map_or(null(), |lws| lws.as_ptr()),
+            wait_for.len() as u32,
+            if wait_for.is_empty() {
+                null()
+            } else {
+                wait_for.as_ptr()
+            },
+            &mut event,
+        )
+    })?;
+
+    Ok(unsafe { CLEvent::from_raw(event) })
 }
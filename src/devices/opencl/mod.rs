@@ -1,13 +1,43 @@
 use std::{ffi::c_void, ptr::null_mut};
 
+pub use autotune::*;
+pub use binary_cache::*;
 pub use cl_device::{cl_cached, OpenCL, CL};
+pub use event::*;
+pub use image::*;
+pub use info::*;
 pub use kernel_cache::*;
 pub use kernel_enqueue::*;
+pub use multi::*;
+pub use profiling::*;
+pub use program::*;
+pub use queue::*;
+pub use read::*;
+pub use signature::*;
+#[cfg(not(target_os = "macos"))]
+pub use svm::*;
+pub use work_size::*;
 
 //pub mod api;
+mod autotune;
+mod binary_cache;
 pub mod cl_device;
+mod event;
+mod ffi;
+mod image;
+mod info;
 mod kernel_cache;
 mod kernel_enqueue;
+mod multi;
+mod profiling;
+mod program;
+mod queue;
+mod read;
+mod rect;
+mod signature;
+#[cfg(not(target_os = "macos"))]
+mod svm;
+mod work_size;
 
 #[cfg(not(feature = "realloc"))]
 //#[cfg(unified_cl)]
@@ -21,7 +51,7 @@ use min_cl::api::release_mem_object;
 pub use unified::*;
 
 //use self::api::release_mem_object;
-use crate::{flag::AllocFlag, Buffer, CDatatype, CommonPtrs, PtrType, ShallowCopy};
+use crate::{flag::AllocFlag, Buffer, CDatatype, CommonPtrs, DeviceError, PtrType, ShallowCopy};
 
 pub type CLBuffer<'a, T> = Buffer<'a, T, OpenCL>;
 
@@ -34,6 +64,29 @@ pub fn chosen_cl_idx() -> usize {
         )
 }
 
+/// Decides whether a device uses unified memory.
+/// The environment variable `CUSTOS_USE_UNIFIED` overrides the default:
+/// - `true`: uses host-mapped allocations, which requires a device with unified memory
+/// - `false`: always uses explicit transfers
+/// - `default` or unset: uses unified memory if the device supports it
+/// # Errors
+/// - The value of `CUSTOS_USE_UNIFIED` is invalid
+/// - `CUSTOS_USE_UNIFIED` is `true`, but the device does not support unified memory
+pub fn use_unified_mem(supported: bool) -> crate::Result<bool> {
+    let unified_mem = match std::env::var("CUSTOS_USE_UNIFIED") {
+        Ok(value) if !value.eq_ignore_ascii_case("default") => value
+            .to_ascii_lowercase()
+            .parse()
+            .map_err(|_| DeviceError::InvalidUnifiedEnv)?,
+        _ => supported,
+    };
+
+    if unified_mem && !supported {
+        return Err(DeviceError::UnifiedMemUnsupported.into());
+    }
+    Ok(unified_mem)
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct CLPtr<T> {
     pub ptr: *mut c_void,
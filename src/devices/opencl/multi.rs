@@ -0,0 +1,381 @@
+use core::{ffi::c_void, mem::size_of, ops::Range};
+
+use min_cl::api::{cl_mem, create_context, release_mem_object, CLIntDevice, Context, OCLErrorKind};
+
+use super::{
+    enqueue_raw,
+    ffi::{
+        check, clCreateSubBuffer, clFlush, clRetainContext, cl_buffer_region,
+        CL_BUFFER_CREATE_TYPE_REGION, CL_DEVICE_MEM_BASE_ADDR_ALIGN,
+    },
+    info::device_u64,
+    AsClCvoidPtr, CLEvent, CommandKind, OpenCL,
+};
+use crate::{Buffer, DeviceError, Read, Shape, WriteBuf};
+
+impl OpenCL {
+    /// Returns an [`OpenCL`] device for every device in `devices`, which all share one context.
+    ///
+    /// A buffer allocated by one of these devices can be passed to kernels of every other device, without a transfer through the host.
+    /// The devices must belong to the same platform, e.g. listed by [`cl_devices`](crate::opencl::cl_devices) with the same `platform_idx`.
+    /// # Example
+    /// ```
+    /// use custos::{opencl::{cl_devices, enqueue_kernel}, Buffer, OpenCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let entries = cl_devices()?;
+    ///     let platform_idx = entries[0].platform_idx;
+    ///     let ids = entries
+    ///         .iter()
+    ///         .filter(|entry| entry.platform_idx == platform_idx)
+    ///         .map(|entry| entry.device)
+    ///         .collect::<Vec<_>>();
+    ///
+    ///     let devices = OpenCL::with_shared_context(&ids)?;
+    ///     let first = &devices[0];
+    ///     let last = &devices[devices.len() - 1];
+    ///
+    ///     let buf = Buffer::<f32, _>::from((first, [1., 2., 3.]));
+    ///
+    ///     let src = "
+    ///         __kernel void add_one(__global float* x) {
+    ///             x[get_global_id(0)] += 1;
+    ///         }
+    ///     ";
+    ///     // the kernel runs on another device than the one, which allocated the buffer
+    ///     enqueue_kernel(last, src, [3, 0, 0], None, &[&buf])?;
+    ///
+    ///     assert_eq!(buf.read(), vec![2., 3., 4.]);
+    ///     Ok(())
+    /// }
+    /// ```
+    /// # Errors
+    /// - The devices belong to different platforms
+    /// - The context or a command queue could not be created
+    pub fn with_shared_context(devices: &[CLIntDevice]) -> crate::Result<Vec<OpenCL>> {
+        let ctx = create_context(devices)?;
+
+        // every device releases its own reference to the context, the reference of the creation is released when `ctx` is dropped
+        devices
+            .iter()
+            .map(|&device| {
+                check(unsafe { clRetainContext(ctx.0) })?;
+                OpenCL::with_context(device, Context(ctx.0))
+            })
+            .collect()
+    }
+
+    /// Returns whether the buffers of `self` and `other` are usable by both devices.
+    #[inline]
+    pub fn shares_context(&self, other: &OpenCL) -> bool {
+        self.ctx().0 == other.ctx().0
+    }
+
+    /// Copies `source`, which may belong to another device, to `dest` and waits for the copy to finish.
+    ///
+    /// If the devices of both buffers share a context with `self`, the data is copied on the device by the queue of `self`.
+    /// Otherwise, the data is transferred through the host.
+    /// # Example
+    /// ```
+    /// use custos::{opencl::cl_devices, Buffer, OpenCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let id = cl_devices()?[0].device;
+    ///     let devices = OpenCL::with_shared_context(&[id, id])?;
+    ///
+    ///     let source = Buffer::from((&devices[0], [1, 2, 3]));
+    ///     let mut dest = Buffer::<i32, _>::new(&devices[1], 3);
+    ///
+    ///     devices[1].copy_peer(&source, &mut dest)?;
+    ///     assert_eq!(dest.read(), vec![1, 2, 3]);
+    ///     Ok(())
+    /// }
+    /// ```
+    /// # Panics
+    /// If the buffers have different lengths.
+    pub fn copy_peer<T: Clone + Default>(
+        &self,
+        source: &Buffer<T, OpenCL>,
+        dest: &mut Buffer<T, OpenCL>,
+    ) -> crate::Result<()> {
+        assert_eq!(
+            source.len(),
+            dest.len(),
+            "the buffers must have the same length"
+        );
+
+        if self.shares_context(source.device()) && self.shares_context(dest.device()) {
+            // Safety: the copy is waited for, before the buffers can be accessed again
+            return unsafe { self.copy_async(source, .., dest, ..) }?.wait();
+        }
+
+        let data = source.device().read_to_vec(source);
+        dest.device().write(dest, &data);
+        Ok(())
+    }
+
+    /// Allocates a buffer on `self` with the contents of `source`, which may belong to another device (see [`OpenCL::copy_peer`]).
+    pub fn clone_from_peer<T: Clone + Default>(
+        &self,
+        source: &Buffer<T, OpenCL>,
+    ) -> crate::Result<Buffer<'_, T, OpenCL>> {
+        let mut dest = Buffer::new(self, source.len());
+        self.copy_peer(source, &mut dest)?;
+        Ok(dest)
+    }
+}
+
+/// Splits `len` work-items into at most `parts` contiguous ranges of about the same size.
+/// Every range, except for the last one, is a multiple of `multiple`, e.g. the local work size.
+fn split_work(len: usize, parts: usize, multiple: usize) -> Vec<Range<usize>> {
+    let multiple = multiple.max(1);
+    let groups = (len + multiple - 1) / multiple;
+    let parts = parts.max(1);
+
+    (0..parts)
+        .map(|part| {
+            let start = (groups * part / parts * multiple).min(len);
+            let end = (groups * (part + 1) / parts * multiple).min(len);
+            start..end
+        })
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[inline]
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+/// An argument of [`enqueue_kernel_split`].
+pub struct SplitArg<'a> {
+    arg: &'a dyn AsClCvoidPtr,
+    /// The size of an element and the length of a split buffer.
+    split: Option<(usize, usize)>,
+}
+
+impl<'a> SplitArg<'a> {
+    /// The argument is passed to every part unchanged, e.g. a number or a buffer, which is only read.
+    /// A shared buffer must not be written by the kernel, as the parts run at the same time.
+    #[inline]
+    pub fn shared(arg: &'a dyn AsClCvoidPtr) -> Self {
+        SplitArg { arg, split: None }
+    }
+
+    /// The buffer holds one element per work-item of the first dimension.
+    /// Every part receives its own region of the buffer as a sub-buffer, which starts at the first work-item of the part.
+    #[inline]
+    pub fn split<T, S: Shape>(buf: &'a Buffer<T, OpenCL, S>) -> Self {
+        SplitArg {
+            arg: buf,
+            split: Some((size_of::<T>(), buf.len())),
+        }
+    }
+}
+
+/// A region of a buffer, which is released when it is dropped.
+struct SubBuffer(cl_mem);
+
+impl SubBuffer {
+    fn new(buf: cl_mem, origin: usize, size: usize) -> crate::Result<SubBuffer> {
+        let region = cl_buffer_region { origin, size };
+        let mut err = 0;
+        let mem = unsafe {
+            // the flags of `buf` are inherited
+            clCreateSubBuffer(
+                buf,
+                0,
+                CL_BUFFER_CREATE_TYPE_REGION,
+                &region as *const cl_buffer_region as *const c_void,
+                &mut err,
+            )
+        };
+        check(err)?;
+        Ok(SubBuffer(mem))
+    }
+}
+
+impl Drop for SubBuffer {
+    fn drop(&mut self) {
+        // enqueued commands keep the sub-buffer alive until they have finished
+        unsafe { release_mem_object(self.0).unwrap() };
+    }
+}
+
+impl AsClCvoidPtr for SubBuffer {
+    #[inline]
+    fn as_cvoid_ptr(&self) -> *const c_void {
+        self.0
+    }
+}
+
+/// Launches a kernel, whose work is split across several devices, which share a context (see [`OpenCL::with_shared_context`]).
+///
+/// The first dimension of the global work size is split into contiguous parts of about the same size, one per device.
+/// Every part runs as its own launch, hence the global ids of a part start at 0 and `get_global_size(0)` returns the length of the part.
+/// Buffers passed with [`SplitArg::split`] are split along the same ranges into sub-buffers,
+/// therefore a kernel, which indexes them with `get_global_id(0)`, is written like for a single device.
+///
+/// The parts are enqueued to all devices at once and run at the same time. The function returns when all parts have finished.
+/// The parts start at multiples of the local work size and of the base address alignment of the devices (`CL_DEVICE_MEM_BASE_ADDR_ALIGN`), which sub-buffers require.
+/// # Example
+/// ```
+/// use custos::{opencl::{cl_devices, enqueue_kernel_split, SplitArg}, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let id = cl_devices()?[0].device;
+///     let devices = OpenCL::with_shared_context(&[id, id])?;
+///
+///     let x = Buffer::<f32, _>::from((&devices[0], vec![2.; 1000]));
+///     let out = Buffer::<f32, _>::new(&devices[0], 1000);
+///
+///     let src = "
+///         __kernel void scale(__global const float* x, __global float* out, float factor) {
+///             size_t id = get_global_id(0);
+///             out[id] = x[id] * factor;
+///         }
+///     ";
+///     enqueue_kernel_split(
+///         &devices,
+///         src,
+///         [1000, 0, 0],
+///         None,
+///         &[SplitArg::split(&x), SplitArg::split(&out), SplitArg::shared(&3f32)],
+///     )?;
+///
+///     assert_eq!(out.read(), vec![6.; 1000]);
+///     Ok(())
+/// }
+/// ```
+/// # Errors
+/// - [`DeviceError::NoSharedContext`]: the devices do not share a context
+/// - [`OCLErrorKind::InvalidBufferSize`]: a split buffer is shorter than the first dimension of the global work size
+/// - The kernel could not be built or launched on a device
+/// # Panics
+/// If `devices` is empty.
+pub fn enqueue_kernel_split(
+    devices: &[OpenCL],
+    src: &str,
+    gws: [usize; 3],
+    lws: Option<[usize; 3]>,
+    args: &[SplitArg],
+) -> crate::Result<()> {
+    assert!(!devices.is_empty(), "no devices to split the kernel across");
+
+    if devices[1..]
+        .iter()
+        .any(|device| !device.shares_context(&devices[0]))
+    {
+        return Err(DeviceError::NoSharedContext.into());
+    }
+
+    if gws[0] == 0 {
+        return Err(OCLErrorKind::InvalidGlobalWorkSize.into());
+    }
+
+    if args
+        .iter()
+        .any(|arg| arg.split.map_or(false, |(_, len)| len < gws[0]))
+    {
+        return Err(OCLErrorKind::InvalidBufferSize.into());
+    }
+
+    // every part must start at an aligned address of every split buffer
+    let mut multiple = lws.map_or(1, |lws| lws[0]);
+    for device in devices {
+        let align = device_u64(device.device().0, CL_DEVICE_MEM_BASE_ADDR_ALIGN)? as usize / 8;
+        for (size, _) in args.iter().filter_map(|arg| arg.split) {
+            multiple = lcm(multiple, align.max(1) / gcd(align.max(1), size));
+        }
+    }
+
+    let parts = split_work(gws[0], devices.len(), multiple);
+
+    let mut events = Vec::<CLEvent>::with_capacity(parts.len());
+    for (device, range) in devices.iter().zip(parts) {
+        let kernel = device.kernel_cache.borrow_mut().kernel_cache(device, src)?;
+
+        let sub_buffers = args
+            .iter()
+            .filter_map(|arg| arg.split.map(|split| (arg.arg, split)))
+            .map(|(arg, (size, _))| {
+                SubBuffer::new(
+                    arg.as_cvoid_ptr() as cl_mem,
+                    range.start * size,
+                    range.len() * size,
+                )
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let mut sub_buffers = sub_buffers.iter();
+        let part_args = args
+            .iter()
+            .map(|arg| match arg.split {
+                Some(_) => sub_buffers.next().unwrap() as &dyn AsClCvoidPtr,
+                None => arg.arg,
+            })
+            .collect::<Vec<_>>();
+
+        let event = enqueue_raw(
+            device.queue().0,
+            &kernel,
+            [range.len(), gws[1], gws[2]],
+            None,
+            lws,
+            &part_args,
+            &[],
+        )?;
+        device.record_event(CommandKind::Kernel, &event, || {
+            kernel.signature.name.clone()
+        });
+
+        // submits the part, before the next device is served
+        check(unsafe { clFlush(device.queue().0) })?;
+        events.push(event);
+    }
+
+    events.into_iter().try_for_each(|event| event.wait())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lcm, split_work};
+
+    #[test]
+    fn test_split_work() {
+        assert_eq!(split_work(1000, 2, 1), vec![0..500, 500..1000]);
+        assert_eq!(split_work(10, 3, 1), vec![0..3, 3..6, 6..10]);
+
+        // every part is a multiple of the local work size
+        assert_eq!(
+            split_work(640, 4, 64),
+            vec![0..128, 128..320, 320..448, 448..640]
+        );
+
+        // less work than parts
+        assert_eq!(split_work(2, 4, 1), vec![0..1, 1..2]);
+        assert_eq!(split_work(64, 2, 64), vec![0..64]);
+        assert_eq!(split_work(0, 2, 1), vec![]);
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(lcm(1, 32), 32);
+        assert_eq!(lcm(64, 32), 64);
+        assert_eq!(lcm(6, 4), 12);
+    }
+
+    #[test]
+    fn test_split_work_uneven() {
+        // the last part ends at the global work size
+        assert_eq!(split_work(100, 2, 32), vec![0..64, 64..100]);
+    }
+}
@@ -0,0 +1,379 @@
+use core::{fmt::Display, ptr::null_mut, time::Duration};
+use std::collections::HashMap;
+
+use min_cl::api::{clCreateCommandQueue, clFinish, clWaitForEvents, CommandQueue};
+
+use super::{
+    ffi::{
+        check, clGetEventProfilingInfo, clRetainEvent, cl_profiling_info, CL_PROFILING_COMMAND_END,
+        CL_PROFILING_COMMAND_QUEUED, CL_PROFILING_COMMAND_START, CL_PROFILING_COMMAND_SUBMIT,
+        CL_QUEUE_PROFILING_ENABLE,
+    },
+    CLEvent, OpenCL,
+};
+
+/// The kind of a profiled OpenCL command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CommandKind {
+    Kernel,
+    Read,
+    Write,
+    Copy,
+}
+
+impl CommandKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommandKind::Kernel => "kernel",
+            CommandKind::Read => "read",
+            CommandKind::Write => "write",
+            CommandKind::Copy => "copy",
+        }
+    }
+}
+
+/// The device timestamps (in nanoseconds) of a finished OpenCL command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileRecord {
+    pub kind: CommandKind,
+    /// The name of the kernel or the kind of the transfer.
+    pub name: String,
+    pub queued: u64,
+    pub submit: u64,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ProfileRecord {
+    /// The time the command ran on the device.
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        Duration::from_nanos(self.end.saturating_sub(self.start))
+    }
+}
+
+/// Collects the events of the commands, which are enqueued while profiling is enabled.
+/// The timestamps are queried by [`OpenCL::profile_report`].
+#[derive(Debug, Default)]
+pub struct Profiler {
+    events: Vec<(CommandKind, String, CLEvent)>,
+}
+
+impl Profiler {
+    /// Keeps the event alive until the report is created.
+    pub fn record(&mut self, kind: CommandKind, name: String, event: &CLEvent) {
+        if unsafe { clRetainEvent(event.as_raw()) } == 0 {
+            self.events
+                .push((kind, name, unsafe { CLEvent::from_raw(event.as_raw()) }));
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+fn profiling_info(event: &CLEvent, param: cl_profiling_info) -> crate::Result<u64> {
+    let mut value = 0u64;
+    check(unsafe {
+        clGetEventProfilingInfo(
+            event.as_raw(),
+            param,
+            core::mem::size_of::<u64>(),
+            (&mut value as *mut u64).cast(),
+            null_mut(),
+        )
+    })?;
+    Ok(value)
+}
+
+/// The aggregated timings of all commands with the same kind and name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileSummary {
+    pub kind: CommandKind,
+    pub name: String,
+    pub count: usize,
+    pub total: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl ProfileSummary {
+    #[inline]
+    pub fn mean(&self) -> Duration {
+        self.total / self.count as u32
+    }
+}
+
+/// The timings of the profiled OpenCL commands in the order they were enqueued.
+/// # Example
+/// ```
+/// use custos::{opencl::enqueue_kernel, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     device.enable_profiling()?;
+///
+///     let buf = Buffer::<f32, _>::from((&device, [1., 2., 3.]));
+///     let src = "__kernel void twice(__global float* x) { x[get_global_id(0)] *= 2; }";
+///     enqueue_kernel(&device, src, [3, 0, 0], None, &[&buf])?;
+///     assert_eq!(buf.read(), vec![2., 4., 6.]);
+///
+///     let report = device.profile_report()?;
+///     println!("{report}");
+///
+///     assert_eq!(report.records[0].name, "twice");
+///     std::fs::write(std::env::temp_dir().join("custos-trace.json"), report.to_chrome_trace())?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileReport {
+    pub records: Vec<ProfileRecord>,
+}
+
+impl ProfileReport {
+    /// The total time of all commands on the device.
+    pub fn total(&self) -> Duration {
+        self.records.iter().map(ProfileRecord::duration).sum()
+    }
+
+    /// Aggregates the records by kind and name. The most time consuming commands come first.
+    pub fn summary(&self) -> Vec<ProfileSummary> {
+        let mut summaries = HashMap::<(CommandKind, &str), ProfileSummary>::new();
+
+        for record in &self.records {
+            let duration = record.duration();
+            summaries
+                .entry((record.kind, &record.name))
+                .and_modify(|summary| {
+                    summary.count += 1;
+                    summary.total += duration;
+                    summary.min = summary.min.min(duration);
+                    summary.max = summary.max.max(duration);
+                })
+                .or_insert_with(|| ProfileSummary {
+                    kind: record.kind,
+                    name: record.name.clone(),
+                    count: 1,
+                    total: duration,
+                    min: duration,
+                    max: duration,
+                });
+        }
+
+        let mut summaries = summaries.into_values().collect::<Vec<_>>();
+        summaries.sort_by(|lhs, rhs| {
+            rhs.total
+                .cmp(&lhs.total)
+                .then_with(|| (lhs.kind, &lhs.name).cmp(&(rhs.kind, &rhs.name)))
+        });
+        summaries
+    }
+
+    /// Exports the records in the Chrome trace event format, which can be opened with `chrome://tracing` or Perfetto.
+    /// The timestamps are relative to the first queued command.
+    pub fn to_chrome_trace(&self) -> String {
+        let begin = self.records.iter().map(|record| record.queued).min();
+        let begin = begin.unwrap_or_default();
+
+        let events = self
+            .records
+            .iter()
+            .map(|record| {
+                format!(
+                    "{{\"name\":\"{}\",\"cat\":\"{}\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":0,\"tid\":0,\"args\":{{\"queued\":{},\"submit\":{}}}}}",
+                    escape_json(&record.name),
+                    record.kind.as_str(),
+                    record.start.saturating_sub(begin) as f64 / 1000.,
+                    record.end.saturating_sub(record.start) as f64 / 1000.,
+                    record.queued,
+                    record.submit,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!("{{\"traceEvents\":[{}]}}", events.join(","))
+    }
+}
+
+impl Display for ProfileReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "{:<8} {:<32} {:>6} {:>12} {:>12} {:>12} {:>12}",
+            "kind", "name", "count", "total", "mean", "min", "max"
+        )?;
+        for summary in self.summary() {
+            writeln!(
+                f,
+                "{:<8} {:<32} {:>6} {:>12?} {:>12?} {:>12?} {:>12?}",
+                summary.kind.as_str(),
+                summary.name,
+                summary.count,
+                summary.total,
+                summary.mean(),
+                summary.min,
+                summary.max
+            )?;
+        }
+        write!(f, "total: {:?}", self.total())
+    }
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl OpenCL {
+    /// Enables profiling: the command queue is recreated with profiling enabled and
+    /// the timestamps of every kernel launch and transfer are recorded until [`OpenCL::profile_report`] is called.
+    pub fn enable_profiling(&self) -> crate::Result<()> {
+        if self.profiling() {
+            return Ok(());
+        }
+
+        let mut err = 0;
+        let queue = unsafe {
+            clCreateCommandQueue(
+                self.ctx().0,
+                self.device().0,
+                CL_QUEUE_PROFILING_ENABLE,
+                &mut err,
+            )
+        };
+        check(err)?;
+        // released on drop, if finishing the old queue fails
+        let queue = CommandQueue(queue);
+
+        // the commands of the old queue must not overtake the commands of the new queue
+        check(unsafe { clFinish(self.queue().0) })?;
+
+        self.inner.borrow_mut().queue = queue;
+        *self.profiler.borrow_mut() = Some(Profiler::default());
+        Ok(())
+    }
+
+    #[inline]
+    pub fn profiling(&self) -> bool {
+        self.profiler.borrow().is_some()
+    }
+
+    /// Records the command of the event, if profiling is enabled.
+    /// The name is only created if it is needed.
+    pub fn record_event(&self, kind: CommandKind, event: &CLEvent, name: impl FnOnce() -> String) {
+        if let Some(profiler) = self.profiler.borrow_mut().as_mut() {
+            profiler.record(kind, name(), event);
+        }
+    }
+
+    /// Waits for all recorded commands and returns their timings.
+    /// The recorded commands are cleared, but profiling stays enabled.
+    /// If profiling is not enabled, the report is empty.
+    pub fn profile_report(&self) -> crate::Result<ProfileReport> {
+        let events = match self.profiler.borrow_mut().as_mut() {
+            Some(profiler) => core::mem::take(&mut profiler.events),
+            None => return Ok(ProfileReport::default()),
+        };
+
+        // the commands may have been enqueued on several queues (see `OpenCL::create_queue`)
+        let raw_events = events
+            .iter()
+            .map(|(_, _, event)| event.as_raw())
+            .collect::<Vec<_>>();
+        if !raw_events.is_empty() {
+            check(unsafe { clWaitForEvents(raw_events.len() as u32, raw_events.as_ptr()) })?;
+        }
+
+        let records = events
+            .into_iter()
+            .map(|(kind, name, event)| {
+                Ok(ProfileRecord {
+                    kind,
+                    name,
+                    queued: profiling_info(&event, CL_PROFILING_COMMAND_QUEUED)?,
+                    submit: profiling_info(&event, CL_PROFILING_COMMAND_SUBMIT)?,
+                    start: profiling_info(&event, CL_PROFILING_COMMAND_START)?,
+                    end: profiling_info(&event, CL_PROFILING_COMMAND_END)?,
+                })
+            })
+            .collect::<crate::Result<_>>()?;
+
+        Ok(ProfileReport { records })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::{CommandKind, ProfileRecord, ProfileReport};
+
+    fn record(kind: CommandKind, name: &str, start: u64, end: u64) -> ProfileRecord {
+        ProfileRecord {
+            kind,
+            name: name.into(),
+            queued: start - 10,
+            submit: start - 5,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let report = ProfileReport {
+            records: vec![
+                record(CommandKind::Write, "write", 100, 200),
+                record(CommandKind::Kernel, "add", 300, 1300),
+                record(CommandKind::Kernel, "add", 1400, 1600),
+                record(CommandKind::Read, "read", 1700, 1750),
+            ],
+        };
+
+        assert_eq!(report.total(), Duration::from_nanos(1350));
+
+        let summary = report.summary();
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0].name, "add");
+        assert_eq!(summary[0].count, 2);
+        assert_eq!(summary[0].mean(), Duration::from_nanos(600));
+        assert_eq!(summary[0].min, Duration::from_nanos(200));
+        assert_eq!(summary[0].max, Duration::from_nanos(1000));
+        assert_eq!(summary[2].kind, CommandKind::Read);
+
+        assert!(report.to_string().contains("add"));
+    }
+
+    #[test]
+    fn test_chrome_trace() {
+        let report = ProfileReport {
+            records: vec![
+                record(CommandKind::Kernel, "a\"b", 1010, 3010),
+                record(CommandKind::Copy, "copy", 4010, 4510),
+            ],
+        };
+
+        assert_eq!(
+            report.to_chrome_trace(),
+            "{\"traceEvents\":[\
+            {\"name\":\"a\\\"b\",\"cat\":\"kernel\",\"ph\":\"X\",\"ts\":0.010,\"dur\":2.000,\"pid\":0,\"tid\":0,\"args\":{\"queued\":1000,\"submit\":1005}},\
+            {\"name\":\"copy\",\"cat\":\"copy\",\"ph\":\"X\",\"ts\":3.010,\"dur\":0.500,\"pid\":0,\"tid\":0,\"args\":{\"queued\":4000,\"submit\":4005}}]}"
+        );
+    }
+}
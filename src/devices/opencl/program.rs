@@ -0,0 +1,418 @@
+use core::{
+    fmt::{Debug, Display, Write},
+    ops::Deref,
+    ptr::null_mut,
+};
+use std::{collections::HashMap, ffi::CString, rc::Rc};
+
+use min_cl::api::{
+    clBuildProgram, clGetProgramBuildInfo, create_kernels_in_program, CLIntDevice, Kernel,
+    OCLErrorKind, Program,
+};
+
+use super::{
+    ffi::{check, clGetKernelInfo, CL_KERNEL_FUNCTION_NAME},
+    KernelSignature,
+};
+use crate::DeviceError;
+
+const CL_PROGRAM_BUILD_LOG: u32 = 0x1183;
+
+/// The options, which are passed to the OpenCL compiler.
+/// The default options only select the OpenCL C version 1.2 (`-cl-std=CL1.2`).
+/// # Example
+/// ```
+/// use custos::opencl::BuildOptions;
+///
+/// let options = BuildOptions::new()
+///     .std("CL2.0")
+///     .define("TILE_SIZE", 16)
+///     .fast_relaxed_math();
+///
+/// assert_eq!(
+///     options.to_string(),
+///     "-cl-std=CL2.0 -D TILE_SIZE=16 -cl-fast-relaxed-math"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BuildOptions {
+    std: Option<String>,
+    options: Vec<String>,
+}
+
+impl Default for BuildOptions {
+    #[inline]
+    fn default() -> Self {
+        BuildOptions {
+            std: Some("CL1.2".into()),
+            options: Vec::new(),
+        }
+    }
+}
+
+impl BuildOptions {
+    #[inline]
+    pub fn new() -> BuildOptions {
+        BuildOptions::default()
+    }
+
+    /// Sets the OpenCL C version, e.g. "CL2.0" (`-cl-std=CL2.0`).
+    #[inline]
+    pub fn std(mut self, version: &str) -> BuildOptions {
+        self.std = Some(version.into());
+        self
+    }
+
+    /// Does not pass a `-cl-std` option, hence the compiler uses OpenCL C 1.x.
+    #[inline]
+    pub fn no_std(mut self) -> BuildOptions {
+        self.std = None;
+        self
+    }
+
+    /// Defines a preprocessor macro (`-D name=value`).
+    #[inline]
+    pub fn define(mut self, name: &str, value: impl Display) -> BuildOptions {
+        self.options.push(format!("-D {name}={value}"));
+        self
+    }
+
+    /// Adds `-cl-fast-relaxed-math`.
+    #[inline]
+    pub fn fast_relaxed_math(self) -> BuildOptions {
+        self.option("-cl-fast-relaxed-math")
+    }
+
+    /// Adds any other compiler option, e.g. `-cl-mad-enable`.
+    #[inline]
+    pub fn option(mut self, option: &str) -> BuildOptions {
+        self.options.push(option.into());
+        self
+    }
+}
+
+impl Display for BuildOptions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let std = self.std.as_ref().map(|std| format!("-cl-std={std}"));
+        let options = std.iter().chain(self.options.iter());
+
+        for (idx, option) in options.enumerate() {
+            if idx > 0 {
+                f.write_char(' ')?;
+            }
+            f.write_str(option)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returned if an OpenCL program could not be built, e.g. because of a syntax error in the kernel source code.
+/// # Example
+/// ```
+/// use custos::{opencl::{enqueue_kernel, CLBuildError}, ErrorKind, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///
+///     let src = "__kernel void broken(__global float* x) { x[0] = }";
+///     let err = enqueue_kernel(&device, src, [1, 0, 0], None, &[]).unwrap_err();
+///
+///     let build_err = err.kind::<CLBuildError>().unwrap();
+///     assert_eq!(build_err.options, "-cl-std=CL1.2 -cl-kernel-arg-info");
+///     println!("{}", build_err.log);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CLBuildError {
+    pub kind: OCLErrorKind,
+    /// The build log of the OpenCL compiler.
+    pub log: String,
+    /// The options, which were passed to the compiler (see [`compiler_options`]).
+    pub options: String,
+    pub src: String,
+}
+
+impl CLBuildError {
+    /// The kernel source code with line numbers.
+    pub fn numbered_src(&self) -> String {
+        let lines = self.src.lines().count();
+        let width = lines.to_string().len();
+
+        self.src
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| format!("{:>width$} | {line}\n", idx + 1))
+            .collect()
+    }
+}
+
+impl Display for CLBuildError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "Could not build OpenCL program ({:?}) with options \"{}\":",
+            self.kind, self.options
+        )?;
+        writeln!(f, "{}", self.log.trim_end())?;
+        writeln!(f, "source:")?;
+        write!(f, "{}", self.numbered_src())
+    }
+}
+
+impl std::error::Error for CLBuildError {}
+
+/// The options, which are actually passed to the compiler.
+/// Besides the build options, the argument info is requested, which is used to validate the arguments of a kernel launch (see [`KernelSignature`]).
+pub fn compiler_options(options: &BuildOptions) -> String {
+    let options = options.to_string();
+    if options.is_empty() {
+        return "-cl-kernel-arg-info".into();
+    }
+    format!("{options} -cl-kernel-arg-info")
+}
+
+/// The [`compiler_options`] as a C string.
+/// Fails with [`OCLErrorKind::InvalidBuildOptions`], if the options contain a nul byte.
+fn c_compiler_options(options: &str) -> crate::Result<CString> {
+    CString::new(options).map_err(|_| OCLErrorKind::InvalidBuildOptions.into())
+}
+
+/// Builds the `program` for the `device`.
+/// If this fails, a [`CLBuildError`] containing the build log is returned.
+/// If the build options contain a nul byte, [`OCLErrorKind::InvalidBuildOptions`] is returned.
+pub fn build_program_with_log(
+    program: &Program,
+    device: CLIntDevice,
+    src: &str,
+    options: &BuildOptions,
+) -> crate::Result<()> {
+    let options = compiler_options(options);
+    let c_options = c_compiler_options(&options)?;
+
+    let value = unsafe {
+        clBuildProgram(
+            program.0,
+            1,
+            &device.0,
+            c_options.as_ptr(),
+            null_mut(),
+            null_mut(),
+        )
+    };
+
+    if value == 0 {
+        return Ok(());
+    }
+
+    Err(CLBuildError {
+        kind: OCLErrorKind::from_value(value),
+        // the build failed anyway, so an unavailable log should not hide the build error
+        log: build_log(program, device).unwrap_or_default(),
+        options,
+        src: src.to_string(),
+    }
+    .into())
+}
+
+/// Returns the build log of the `program` for the `device`.
+pub fn build_log(program: &Program, device: CLIntDevice) -> crate::Result<String> {
+    let mut size = 0;
+    check(unsafe {
+        clGetProgramBuildInfo(
+            program.0,
+            device.0,
+            CL_PROGRAM_BUILD_LOG,
+            0,
+            null_mut(),
+            &mut size,
+        )
+    })?;
+
+    let mut log = vec![0u8; size];
+    check(unsafe {
+        clGetProgramBuildInfo(
+            program.0,
+            device.0,
+            CL_PROGRAM_BUILD_LOG,
+            size,
+            log.as_mut_ptr().cast(),
+            null_mut(),
+        )
+    })?;
+
+    Ok(c_string(log))
+}
+
+/// A cached kernel and its signature, which is used to validate the arguments of a launch.
+/// Dereferences to the [`Kernel`].
+#[derive(Debug, Clone)]
+pub struct CLKernel {
+    pub kernel: Rc<Kernel>,
+    pub signature: Rc<KernelSignature>,
+}
+
+impl CLKernel {
+    /// Queries the signature of the `kernel`.
+    pub fn new(kernel: Rc<Kernel>) -> crate::Result<CLKernel> {
+        Ok(CLKernel {
+            signature: Rc::new(KernelSignature::new(&kernel)?),
+            kernel,
+        })
+    }
+}
+
+impl Deref for CLKernel {
+    type Target = Kernel;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.kernel
+    }
+}
+
+/// A built OpenCL program and all of its kernels.
+pub struct CLProgram {
+    pub program: Program,
+    /// The kernels and their signatures, identified by their function names.
+    pub kernels: HashMap<String, CLKernel>,
+}
+
+impl CLProgram {
+    /// Creates every kernel of an already built `program` and queries their signatures.
+    pub fn new(program: Program) -> crate::Result<CLProgram> {
+        let kernels = create_kernels_in_program(&program)?
+            .into_iter()
+            .map(|kernel| {
+                let kernel = CLKernel::new(kernel)?;
+                Ok((kernel.signature.name.clone(), kernel))
+            })
+            .collect::<crate::Result<HashMap<_, _>>>()?;
+
+        Ok(CLProgram { program, kernels })
+    }
+
+    /// Returns the kernel with the given function name.
+    /// # Errors
+    /// [`DeviceError::MissingKernel`], if the program does not contain such a kernel.
+    pub fn kernel(&self, name: &str) -> crate::Result<CLKernel> {
+        self.kernels
+            .get(name)
+            .cloned()
+            .ok_or_else(|| DeviceError::MissingKernel.into())
+    }
+
+    /// Returns the kernel of a program, which contains a single kernel.
+    /// # Errors
+    /// [`DeviceError::AmbiguousKernel`], if the program contains several kernels.
+    pub fn only_kernel(&self) -> crate::Result<CLKernel> {
+        if self.kernels.len() > 1 {
+            return Err(DeviceError::AmbiguousKernel.into());
+        }
+
+        self.kernels
+            .values()
+            .next()
+            .cloned()
+            .ok_or_else(|| DeviceError::MissingKernel.into())
+    }
+}
+
+impl Debug for CLProgram {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CLProgram")
+            .field("program", &self.program.0)
+            .field("kernels", &self.kernels)
+            .finish()
+    }
+}
+
+/// Returns the function name of a kernel.
+pub fn kernel_name(kernel: &Kernel) -> crate::Result<String> {
+    let mut size = 0;
+    check(unsafe { clGetKernelInfo(kernel.0, CL_KERNEL_FUNCTION_NAME, 0, null_mut(), &mut size) })?;
+
+    let mut name = vec![0u8; size];
+    check(unsafe {
+        clGetKernelInfo(
+            kernel.0,
+            CL_KERNEL_FUNCTION_NAME,
+            size,
+            name.as_mut_ptr().cast(),
+            null_mut(),
+        )
+    })?;
+
+    Ok(c_string(name))
+}
+
+/// Converts a null terminated C string.
+pub(super) fn c_string(mut bytes: Vec<u8>) -> String {
+    if let Some(end) = bytes.iter().position(|&byte| byte == 0) {
+        bytes.truncate(end);
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use min_cl::api::OCLErrorKind;
+
+    use super::{c_compiler_options, compiler_options, BuildOptions, CLBuildError};
+
+    #[test]
+    fn test_build_options() {
+        assert_eq!(BuildOptions::default().to_string(), "-cl-std=CL1.2");
+        assert_eq!(BuildOptions::new().no_std().to_string(), "");
+        assert_eq!(
+            BuildOptions::new()
+                .no_std()
+                .define("N", 4)
+                .option("-cl-mad-enable")
+                .to_string(),
+            "-D N=4 -cl-mad-enable"
+        );
+    }
+
+    #[test]
+    fn test_compiler_options() {
+        assert_eq!(
+            compiler_options(&BuildOptions::default()),
+            "-cl-std=CL1.2 -cl-kernel-arg-info"
+        );
+        assert_eq!(
+            compiler_options(&BuildOptions::new().no_std()),
+            "-cl-kernel-arg-info"
+        );
+    }
+
+    #[test]
+    fn test_c_compiler_options_nul() {
+        let options = compiler_options(&BuildOptions::new().define("N", "4\0"));
+        let err = c_compiler_options(&options).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OCLErrorKind>(),
+            Some(&OCLErrorKind::InvalidBuildOptions)
+        );
+
+        assert!(c_compiler_options(&compiler_options(&BuildOptions::default())).is_ok());
+    }
+
+    #[test]
+    fn test_numbered_src() {
+        let err = CLBuildError {
+            kind: OCLErrorKind::BuildProgramFailures,
+            log: "<source>:2:5: error: expected expression".into(),
+            options: compiler_options(&BuildOptions::default()),
+            src: (1..=10).map(|line| format!("line{line}\n")).collect(),
+        };
+
+        let numbered = err.numbered_src();
+        assert!(numbered.starts_with(" 1 | line1\n"));
+        assert!(numbered.ends_with("10 | line10\n"));
+
+        let msg = err.to_string();
+        assert!(msg.contains("-cl-std=CL1.2"));
+        assert!(msg.contains("expected expression"));
+    }
+}
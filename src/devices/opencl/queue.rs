@@ -0,0 +1,321 @@
+use core::{
+    fmt::Debug,
+    mem::size_of_val,
+    ops::RangeBounds,
+    ptr::{null, null_mut},
+};
+
+use min_cl::api::{
+    clCreateCommandQueue, clEnqueueCopyBuffer, clEnqueueReadBuffer, clEnqueueWriteBuffer, clFinish,
+    cl_command_queue, cl_event, CommandQueue,
+};
+
+use super::{
+    ffi::{
+        check, clEnqueueBarrierWithWaitList, clEnqueueMarkerWithWaitList, clFlush,
+        CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE, CL_QUEUE_PROFILING_ENABLE,
+    },
+    CLEvent, CommandKind,
+};
+use crate::{op_traits::bounds_to_range, Buffer, OpenCL};
+
+/// An additional command queue on the context of an [`OpenCL`] device, created by [`OpenCL::create_queue`].
+///
+/// Commands of different queues may run concurrently. Hence, independent work, e.g. transfers and kernels, can overlap.
+/// The buffers of the device can be used with every queue.
+/// Dependencies between commands of different queues are expressed with the events in `wait_for`.
+///
+/// The commands of an out-of-order queue may run in any order, unless they depend on each other via events or a [`CLQueue::barrier`].
+pub struct CLQueue<'a> {
+    queue: CommandQueue,
+    out_of_order: bool,
+    profiled: bool,
+    device: &'a OpenCL,
+}
+
+impl OpenCL {
+    /// Creates an additional command queue on the context of the device.
+    /// If profiling is enabled (see [`OpenCL::enable_profiling`]), the commands of the queue are recorded as well.
+    /// # Example
+    /// ```
+    /// use custos::{Buffer, OpenCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = OpenCL::new(0)?;
+    ///     let upload = device.create_queue(false)?;
+    ///     let download = device.create_queue(false)?;
+    ///
+    ///     let mut buf = Buffer::<i32, _>::new(&device, 3);
+    ///     let mut out = Buffer::<i32, _>::new(&device, 3);
+    ///
+    ///     upload.write(&mut buf, &[1, 2, 3], &[])?;
+    ///     // Safety: `out` is only read after the copy has finished
+    ///     let copy = unsafe { upload.copy(&buf, .., &mut out, .., &[]) }?;
+    ///
+    ///     assert_eq!(download.read(&out, &[&copy])?, vec![1, 2, 3]);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn create_queue(&self, out_of_order: bool) -> crate::Result<CLQueue<'_>> {
+        let profiled = self.profiling();
+
+        let mut properties = 0;
+        if out_of_order {
+            properties |= CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE;
+        }
+        if profiled {
+            properties |= CL_QUEUE_PROFILING_ENABLE;
+        }
+
+        let mut err = 0;
+        let queue =
+            unsafe { clCreateCommandQueue(self.ctx().0, self.device().0, properties, &mut err) };
+        check(err)?;
+
+        self.queues.borrow_mut().push(queue);
+        Ok(CLQueue {
+            queue: CommandQueue(queue),
+            out_of_order,
+            profiled,
+            device: self,
+        })
+    }
+}
+
+impl<'a> CLQueue<'a> {
+    #[inline]
+    pub fn device(&self) -> &'a OpenCL {
+        self.device
+    }
+
+    #[inline]
+    pub fn as_raw(&self) -> cl_command_queue {
+        self.queue.0
+    }
+
+    #[inline]
+    pub fn is_out_of_order(&self) -> bool {
+        self.out_of_order
+    }
+
+    /// Records the command of the event, if the queue was created while profiling was enabled.
+    pub(super) fn record(&self, kind: CommandKind, event: &CLEvent, name: impl FnOnce() -> String) {
+        if self.profiled {
+            self.device.record_event(kind, event, name);
+        }
+    }
+
+    /// Submits the enqueued commands to the device.
+    /// Commands of other queues can only wait for submitted commands.
+    #[inline]
+    pub fn flush(&self) -> crate::Result<()> {
+        check(unsafe { clFlush(self.queue.0) })
+    }
+
+    /// Blocks until all enqueued commands have finished.
+    #[inline]
+    pub fn finish(&self) -> crate::Result<()> {
+        check(unsafe { clFinish(self.queue.0) })
+    }
+
+    /// Writes `data` into `buf` after all events in `wait_for` have completed.
+    /// Blocks until the write has finished.
+    /// # Panics
+    /// If `data` is longer than `buf`.
+    pub fn write<T>(
+        &self,
+        buf: &mut Buffer<T, OpenCL>,
+        data: &[T],
+        wait_for: &[&CLEvent],
+    ) -> crate::Result<()> {
+        assert!(
+            data.len() <= buf.len(),
+            "The host slice is longer than the buffer."
+        );
+
+        let wait_list = self.wait_list(wait_for)?;
+        let mut event = null_mut();
+        let status = unsafe {
+            clEnqueueWriteBuffer(
+                self.queue.0,
+                buf.cl_ptr(),
+                0,
+                0,
+                size_of_val(data),
+                data.as_ptr().cast(),
+                wait_list.len() as u32,
+                wait_list_ptr(&wait_list),
+                &mut event,
+            )
+        };
+        self.wait(status, event, CommandKind::Write, "write")
+    }
+
+    /// Reads `buf` after all events in `wait_for` have completed.
+    /// Blocks until the read has finished.
+    pub fn read<T: Default + Clone>(
+        &self,
+        buf: &Buffer<T, OpenCL>,
+        wait_for: &[&CLEvent],
+    ) -> crate::Result<Vec<T>> {
+        let mut data = vec![T::default(); buf.len()];
+
+        let wait_list = self.wait_list(wait_for)?;
+        let mut event = null_mut();
+        let status = unsafe {
+            clEnqueueReadBuffer(
+                self.queue.0,
+                buf.cl_ptr(),
+                0,
+                0,
+                size_of_val(data.as_slice()),
+                data.as_mut_ptr().cast(),
+                wait_list.len() as u32,
+                wait_list_ptr(&wait_list),
+                &mut event,
+            )
+        };
+        self.wait(status, event, CommandKind::Read, "read")?;
+        Ok(data)
+    }
+
+    /// Enqueues a non-blocking copy from `source` to `dest`, which starts after all events in `wait_for` have completed.
+    /// # Safety
+    /// The returned event does not borrow the buffers. Until the copy has finished (e.g. [`CLEvent::wait`]):
+    /// - `dest` must not be accessed by the host, e.g. with [`Buffer::try_as_slice`] on unified memory
+    /// - `source` must not be written by the host
+    /// # Panics
+    /// If the ranges have different lengths.
+    pub unsafe fn copy<T, SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, OpenCL>,
+        source_range: SR,
+        dest: &mut Buffer<T, OpenCL>,
+        dest_range: DR,
+        wait_for: &[&CLEvent],
+    ) -> crate::Result<CLEvent> {
+        let source_range = bounds_to_range(source_range, source.len());
+        let dest_range = bounds_to_range(dest_range, dest.len());
+
+        let len = source_range.end - source_range.start;
+        assert_eq!(len, dest_range.end - dest_range.start);
+
+        let size = core::mem::size_of::<T>();
+        let wait_list = self.wait_list(wait_for)?;
+        let mut event = null_mut();
+        check(unsafe {
+            clEnqueueCopyBuffer(
+                self.queue.0,
+                source.cl_ptr(),
+                dest.cl_ptr(),
+                source_range.start * size,
+                dest_range.start * size,
+                len * size,
+                wait_list.len() as u32,
+                wait_list_ptr(&wait_list),
+                &mut event,
+            )
+        })?;
+
+        self.submitted(event, CommandKind::Copy, "copy")
+    }
+
+    /// Enqueues a marker, whose event completes after all events in `wait_for` have completed.
+    /// If `wait_for` is empty, the marker waits for all previously enqueued commands of this queue.
+    ///
+    /// The marker does not block later commands of the queue. Its event can be used by other queues.
+    pub fn marker(&self, wait_for: &[&CLEvent]) -> crate::Result<CLEvent> {
+        let wait_list = self.wait_list(wait_for)?;
+        let mut event = null_mut();
+        check(unsafe {
+            clEnqueueMarkerWithWaitList(
+                self.queue.0,
+                wait_list.len() as u32,
+                wait_list_ptr(&wait_list),
+                &mut event,
+            )
+        })?;
+
+        let event = unsafe { CLEvent::from_raw(event) };
+        self.flush()?;
+        Ok(event)
+    }
+
+    /// Enqueues a barrier: commands, which are enqueued afterwards, start after all events in `wait_for` have completed.
+    /// If `wait_for` is empty, they start after all previously enqueued commands of this queue have finished, even on an out-of-order queue.
+    pub fn barrier(&self, wait_for: &[&CLEvent]) -> crate::Result<()> {
+        let wait_list = self.wait_list(wait_for)?;
+        check(unsafe {
+            clEnqueueBarrierWithWaitList(
+                self.queue.0,
+                wait_list.len() as u32,
+                wait_list_ptr(&wait_list),
+                null_mut(),
+            )
+        })
+    }
+
+    /// Submits the commands of the device queue, if there are dependencies, because their events may belong to it.
+    /// Commands of this queue can only wait for submitted commands.
+    pub(super) fn submit_dependencies(&self, wait_for: &[&CLEvent]) -> crate::Result<()> {
+        if wait_for.is_empty() {
+            return Ok(());
+        }
+        check(unsafe { clFlush(self.device.queue().0) })
+    }
+
+    fn wait_list(&self, wait_for: &[&CLEvent]) -> crate::Result<Vec<cl_event>> {
+        self.submit_dependencies(wait_for)?;
+        Ok(wait_for.iter().map(|event| event.as_raw()).collect())
+    }
+
+    fn wait(
+        &self,
+        status: i32,
+        event: cl_event,
+        kind: CommandKind,
+        name: &str,
+    ) -> crate::Result<()> {
+        check(status)?;
+
+        let event = unsafe { CLEvent::from_raw(event) };
+        self.record(kind, &event, || name.into());
+        event.wait()
+    }
+
+    fn submitted(&self, event: cl_event, kind: CommandKind, name: &str) -> crate::Result<CLEvent> {
+        let event = unsafe { CLEvent::from_raw(event) };
+        self.record(kind, &event, || name.into());
+        self.flush()?;
+        Ok(event)
+    }
+}
+
+#[inline]
+fn wait_list_ptr(wait_list: &[cl_event]) -> *const cl_event {
+    if wait_list.is_empty() {
+        null()
+    } else {
+        wait_list.as_ptr()
+    }
+}
+
+impl Drop for CLQueue<'_> {
+    fn drop(&mut self) {
+        // the queue itself is released afterwards by `CommandQueue`
+        self.device
+            .queues
+            .borrow_mut()
+            .retain(|&queue| queue != self.queue.0);
+    }
+}
+
+impl Debug for CLQueue<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CLQueue")
+            .field("queue", &self.queue.0)
+            .field("out_of_order", &self.out_of_order)
+            .field("profiled", &self.profiled)
+            .finish()
+    }
+}
@@ -0,0 +1,118 @@
+use core::{fmt::Debug, ops::Deref};
+
+/// The data of an OpenCL buffer, returned by [`Read::read`](crate::Read::read).
+///
+/// The data of buffers in unified memory is borrowed.
+/// Otherwise, it is transferred into a `Vec`.
+/// # Example
+/// ```
+/// use custos::{opencl::CLRead, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let buf = Buffer::from((&device, [1, 2, 3]));
+///
+///     let read = buf.read();
+///     assert_eq!(read, [1, 2, 3]);
+///     assert_eq!(read.is_borrowed(), device.unified_mem());
+///
+///     let data: Vec<i32> = read.into_vec();
+///     assert_eq!(data, vec![1, 2, 3]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub enum CLRead<'a, T> {
+    Borrowed(&'a [T]),
+    Owned(Vec<T>),
+}
+
+impl<T> CLRead<'_, T> {
+    #[inline]
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, CLRead::Borrowed(_))
+    }
+
+    /// Returns the data as a `Vec`. Borrowed data is cloned.
+    #[inline]
+    pub fn into_vec(self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        match self {
+            CLRead::Borrowed(slice) => slice.to_vec(),
+            CLRead::Owned(vec) => vec,
+        }
+    }
+}
+
+impl<T> Deref for CLRead<'_, T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            CLRead::Borrowed(slice) => slice,
+            CLRead::Owned(vec) => vec,
+        }
+    }
+}
+
+impl<T> AsRef<[T]> for CLRead<'_, T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: Debug> Debug for CLRead<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: Clone> From<CLRead<'_, T>> for Vec<T> {
+    #[inline]
+    fn from(read: CLRead<'_, T>) -> Self {
+        read.into_vec()
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<CLRead<'_, U>> for CLRead<'_, T> {
+    #[inline]
+    fn eq(&self, other: &CLRead<'_, U>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for CLRead<'_, T> {}
+
+macro_rules! impl_partial_eq {
+    ($($rhs:ty $(, $n:ident)?);*) => {
+        $(
+            impl<T: PartialEq<U>, U $(, const $n: usize)?> PartialEq<$rhs> for CLRead<'_, T> {
+                #[inline]
+                fn eq(&self, other: &$rhs) -> bool {
+                    **self == other[..]
+                }
+            }
+
+            impl<T, U: PartialEq<T> $(, const $n: usize)?> PartialEq<CLRead<'_, T>> for $rhs {
+                #[inline]
+                fn eq(&self, other: &CLRead<'_, T>) -> bool {
+                    self[..] == **other
+                }
+            }
+        )*
+    };
+}
+
+impl_partial_eq!(Vec<U>; &[U]; &mut [U]; [U; N], N; &[U; N], N);
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for CLRead<'_, T> {
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        **self == *other
+    }
+}
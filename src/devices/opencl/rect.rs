@@ -0,0 +1,143 @@
+use core::{
+    mem::size_of,
+    ptr::{null, null_mut},
+};
+
+use super::{
+    event::wait_recorded,
+    ffi::{clEnqueueCopyBufferRect, clEnqueueReadBufferRect, clEnqueueWriteBufferRect},
+    CommandKind,
+};
+use crate::{Buffer, CopyRect, OpenCL, Rect, Shape};
+
+/// The origin and pitches of a [`Rect`] in the units of OpenCL, which counts the first dimension in bytes.
+struct ByteRect {
+    origin: [usize; 3],
+    row_pitch: usize,
+    slice_pitch: usize,
+}
+
+impl ByteRect {
+    fn new<T>(rect: Rect, region: [usize; 3]) -> ByteRect {
+        let (row_pitch, slice_pitch) = rect.pitches(region);
+        ByteRect {
+            origin: byte_region::<T>(rect.origin),
+            row_pitch: row_pitch * size_of::<T>(),
+            slice_pitch: slice_pitch * size_of::<T>(),
+        }
+    }
+}
+
+#[inline]
+fn byte_region<T>([x, y, z]: [usize; 3]) -> [usize; 3] {
+    [x * size_of::<T>(), y, z]
+}
+
+impl<T, S: Shape> CopyRect<T, OpenCL, S> for OpenCL {
+    fn copy_rect<DS: Shape>(
+        &self,
+        source: &Buffer<T, OpenCL, S>,
+        source_rect: Rect,
+        dest: &mut Buffer<T, OpenCL, DS>,
+        dest_rect: Rect,
+        region: [usize; 3],
+    ) {
+        source_rect.assert_region(region, source.len());
+        dest_rect.assert_region(region, dest.len());
+
+        let src = ByteRect::new::<T>(source_rect, region);
+        let dst = ByteRect::new::<T>(dest_rect, region);
+
+        let mut event = null_mut();
+        let status = unsafe {
+            clEnqueueCopyBufferRect(
+                self.queue().0,
+                source.ptr.ptr,
+                dest.ptr.ptr,
+                src.origin.as_ptr(),
+                dst.origin.as_ptr(),
+                byte_region::<T>(region).as_ptr(),
+                src.row_pitch,
+                src.slice_pitch,
+                dst.row_pitch,
+                dst.slice_pitch,
+                0,
+                null(),
+                &mut event,
+            )
+        };
+        wait_recorded(self, status, event, CommandKind::Copy, "copy_rect").unwrap();
+    }
+
+    fn read_rect(
+        &self,
+        buf: &Buffer<T, OpenCL, S>,
+        buf_rect: Rect,
+        host: &mut [T],
+        host_rect: Rect,
+        region: [usize; 3],
+    ) {
+        buf_rect.assert_region(region, buf.len());
+        host_rect.assert_region(region, host.len());
+
+        let buf_bytes = ByteRect::new::<T>(buf_rect, region);
+        let host_bytes = ByteRect::new::<T>(host_rect, region);
+
+        let mut event = null_mut();
+        let status = unsafe {
+            clEnqueueReadBufferRect(
+                self.queue().0,
+                buf.ptr.ptr,
+                1,
+                buf_bytes.origin.as_ptr(),
+                host_bytes.origin.as_ptr(),
+                byte_region::<T>(region).as_ptr(),
+                buf_bytes.row_pitch,
+                buf_bytes.slice_pitch,
+                host_bytes.row_pitch,
+                host_bytes.slice_pitch,
+                host.as_mut_ptr().cast(),
+                0,
+                null(),
+                &mut event,
+            )
+        };
+        wait_recorded(self, status, event, CommandKind::Read, "read_rect").unwrap();
+    }
+
+    fn write_rect(
+        &self,
+        buf: &mut Buffer<T, OpenCL, S>,
+        buf_rect: Rect,
+        host: &[T],
+        host_rect: Rect,
+        region: [usize; 3],
+    ) {
+        buf_rect.assert_region(region, buf.len());
+        host_rect.assert_region(region, host.len());
+
+        let buf_bytes = ByteRect::new::<T>(buf_rect, region);
+        let host_bytes = ByteRect::new::<T>(host_rect, region);
+
+        let mut event = null_mut();
+        let status = unsafe {
+            clEnqueueWriteBufferRect(
+                self.queue().0,
+                buf.ptr.ptr,
+                1,
+                buf_bytes.origin.as_ptr(),
+                host_bytes.origin.as_ptr(),
+                byte_region::<T>(region).as_ptr(),
+                buf_bytes.row_pitch,
+                buf_bytes.slice_pitch,
+                host_bytes.row_pitch,
+                host_bytes.slice_pitch,
+                host.as_ptr().cast(),
+                0,
+                null(),
+                &mut event,
+            )
+        };
+        wait_recorded(self, status, event, CommandKind::Write, "write_rect").unwrap();
+    }
+}
@@ -0,0 +1,456 @@
+use core::{fmt::Display, ptr::null_mut};
+
+use min_cl::api::{Kernel, OCLErrorKind};
+
+use super::{
+    ffi::{
+        check, clGetKernelArgInfo, clGetKernelInfo, cl_kernel_arg_address_qualifier,
+        cl_kernel_arg_info, CL_KERNEL_ARG_ADDRESS_CONSTANT, CL_KERNEL_ARG_ADDRESS_GLOBAL,
+        CL_KERNEL_ARG_ADDRESS_LOCAL, CL_KERNEL_ARG_ADDRESS_PRIVATE,
+        CL_KERNEL_ARG_ADDRESS_QUALIFIER, CL_KERNEL_ARG_NAME, CL_KERNEL_ARG_TYPE_NAME,
+        CL_KERNEL_NUM_ARGS,
+    },
+    kernel_name,
+    program::c_string,
+    AsClCvoidPtr,
+};
+
+/// The address space of a kernel parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressSpace {
+    /// `__global`, e.g. buffers and images
+    Global,
+    /// `__constant`
+    Constant,
+    /// `__local`
+    Local,
+    /// scalars and vectors, which are passed by value
+    Private,
+}
+
+impl AddressSpace {
+    fn from_cl(qualifier: cl_kernel_arg_address_qualifier) -> Option<AddressSpace> {
+        match qualifier {
+            CL_KERNEL_ARG_ADDRESS_GLOBAL => Some(AddressSpace::Global),
+            CL_KERNEL_ARG_ADDRESS_CONSTANT => Some(AddressSpace::Constant),
+            CL_KERNEL_ARG_ADDRESS_LOCAL => Some(AddressSpace::Local),
+            CL_KERNEL_ARG_ADDRESS_PRIVATE => Some(AddressSpace::Private),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AddressSpace {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            AddressSpace::Global => "__global",
+            AddressSpace::Constant => "__constant",
+            AddressSpace::Local => "__local",
+            AddressSpace::Private => "__private",
+        })
+    }
+}
+
+/// A parameter of a kernel, e.g. `__global float* x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelParam {
+    pub name: String,
+    /// The type without qualifiers, e.g. `float*` or `int4`.
+    pub type_name: String,
+    pub address_space: AddressSpace,
+}
+
+impl KernelParam {
+    /// The size of a scalar or vector type, which is passed by value.
+    /// `None` for pointers and other types, e.g. `sampler_t` or structs.
+    pub fn scalar_size(&self) -> Option<usize> {
+        if self.address_space != AddressSpace::Private {
+            return None;
+        }
+        scalar_size(&self.type_name)
+    }
+}
+
+impl Display for KernelParam {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.address_space {
+            AddressSpace::Private => write!(f, "{} {}", self.type_name, self.name),
+            address_space => write!(f, "{address_space} {} {}", self.type_name, self.name),
+        }
+    }
+}
+
+/// The size of an OpenCL C scalar or vector type, e.g. `float` or `uint4`.
+fn scalar_size(type_name: &str) -> Option<usize> {
+    let width_start = type_name
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(type_name.len());
+    let (scalar, width) = type_name.split_at(width_start);
+
+    let size = match scalar {
+        "char" | "uchar" | "bool" => 1,
+        "short" | "ushort" | "half" => 2,
+        "int" | "uint" | "float" => 4,
+        "long" | "ulong" | "double" => 8,
+        _ => return None,
+    };
+
+    let width = match width {
+        "" => 1,
+        // 3-component vectors are aligned like 4-component vectors
+        "3" => 4,
+        "2" | "4" | "8" | "16" => width.parse::<usize>().unwrap(),
+        _ => return None,
+    };
+    Some(size * width)
+}
+
+/// The parameters of a kernel, which are used to validate the arguments passed to [`enqueue_kernel`](super::enqueue_kernel).
+/// The signatures are cached in [`KernelCacheCL`](super::KernelCacheCL).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelSignature {
+    /// The function name of the kernel.
+    pub name: String,
+    pub num_args: usize,
+    /// `None` if the OpenCL implementation does not provide information about the arguments,
+    /// e.g. for programs loaded from a binary. Then, only the number of arguments is validated.
+    pub params: Option<Vec<KernelParam>>,
+}
+
+impl KernelSignature {
+    /// Queries the signature of a built kernel.
+    pub fn new(kernel: &Kernel) -> crate::Result<KernelSignature> {
+        let mut num_args = 0u32;
+        check(unsafe {
+            clGetKernelInfo(
+                kernel.0,
+                CL_KERNEL_NUM_ARGS,
+                core::mem::size_of::<u32>(),
+                (&mut num_args as *mut u32).cast(),
+                null_mut(),
+            )
+        })?;
+
+        let params = (0..num_args)
+            .map(|idx| kernel_param(kernel, idx))
+            .collect::<crate::Result<Vec<_>>>()
+            .ok();
+
+        Ok(KernelSignature {
+            name: kernel_name(kernel)?,
+            num_args: num_args as usize,
+            params,
+        })
+    }
+
+    /// Checks the number of arguments, their address spaces and the sizes of scalars.
+    pub fn check_args(&self, args: &[&dyn AsClCvoidPtr]) -> Result<(), KernelArgError> {
+        if args.len() != self.num_args {
+            return Err(KernelArgError::Count {
+                kernel: self.name.clone(),
+                expected: self.num_args,
+                provided: args.len(),
+            });
+        }
+
+        let Some(params) = &self.params else {
+            return Ok(());
+        };
+
+        for (idx, (param, arg)) in params.iter().zip(args).enumerate() {
+            let matches = match param.address_space {
+                AddressSpace::Global | AddressSpace::Constant => !arg.is_num() && !arg.is_local(),
+                AddressSpace::Local => arg.is_local(),
+                AddressSpace::Private => match param.scalar_size() {
+                    Some(size) => arg.is_num() && arg.ptr_size() == size,
+                    // e.g. a sampler or a struct
+                    None => !arg.is_local(),
+                },
+            };
+
+            if !matches {
+                return Err(KernelArgError::Mismatch {
+                    kernel: self.name.clone(),
+                    idx,
+                    param: param.clone(),
+                    provided: describe_arg(*arg),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn describe_arg(arg: &dyn AsClCvoidPtr) -> String {
+    if arg.is_num() {
+        format!("a number of {} bytes", arg.ptr_size())
+    } else if arg.is_local() {
+        format!("local memory of {} bytes", arg.ptr_size())
+    } else {
+        "a buffer or image".into()
+    }
+}
+
+fn kernel_param(kernel: &Kernel, idx: u32) -> crate::Result<KernelParam> {
+    let mut qualifier: cl_kernel_arg_address_qualifier = 0;
+    check(unsafe {
+        clGetKernelArgInfo(
+            kernel.0,
+            idx,
+            CL_KERNEL_ARG_ADDRESS_QUALIFIER,
+            core::mem::size_of::<cl_kernel_arg_address_qualifier>(),
+            (&mut qualifier as *mut cl_kernel_arg_address_qualifier).cast(),
+            null_mut(),
+        )
+    })?;
+
+    Ok(KernelParam {
+        name: arg_string(kernel, idx, CL_KERNEL_ARG_NAME)?,
+        type_name: arg_string(kernel, idx, CL_KERNEL_ARG_TYPE_NAME)?,
+        address_space: AddressSpace::from_cl(qualifier).ok_or(OCLErrorKind::InvalidValue)?,
+    })
+}
+
+fn arg_string(kernel: &Kernel, idx: u32, param: cl_kernel_arg_info) -> crate::Result<String> {
+    let mut size = 0;
+    check(unsafe { clGetKernelArgInfo(kernel.0, idx, param, 0, null_mut(), &mut size) })?;
+
+    let mut value = vec![0u8; size];
+    check(unsafe {
+        clGetKernelArgInfo(
+            kernel.0,
+            idx,
+            param,
+            size,
+            value.as_mut_ptr().cast(),
+            null_mut(),
+        )
+    })?;
+    Ok(c_string(value))
+}
+
+/// Returned by [`enqueue_kernel`](super::enqueue_kernel) and the other launch functions, if the arguments do not match the parameters of the kernel.
+/// # Example
+/// ```
+/// use custos::{opencl::{enqueue_kernel, KernelArgError}, Buffer, ErrorKind, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let buf = Buffer::<f32, _>::new(&device, 4);
+///
+///     let src = "
+///         __kernel void fill(__global float* out, const float value) {
+///             out[get_global_id(0)] = value;
+///         }
+///     ";
+///
+///     let err = enqueue_kernel(&device, src, [4, 0, 0], None, &[&buf]).unwrap_err();
+///     assert_eq!(
+///         err.kind::<KernelArgError>(),
+///         Some(&KernelArgError::Count {
+///             kernel: "fill".into(),
+///             expected: 2,
+///             provided: 1
+///         })
+///     );
+///
+///     // a double instead of a float
+///     let err = enqueue_kernel(&device, src, [4, 0, 0], None, &[&buf, &1f64]).unwrap_err();
+///     println!("{err}");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KernelArgError {
+    /// The number of arguments does not match the number of parameters.
+    Count {
+        kernel: String,
+        expected: usize,
+        provided: usize,
+    },
+    /// The argument at `idx` does not match its parameter, e.g. a number was passed for a `__global float*`.
+    Mismatch {
+        kernel: String,
+        idx: usize,
+        param: KernelParam,
+        /// A description of the provided argument.
+        provided: String,
+    },
+    /// The argument at `idx` was rejected by `clSetKernelArg`.
+    Rejected {
+        kernel: String,
+        idx: usize,
+        kind: OCLErrorKind,
+    },
+}
+
+impl Display for KernelArgError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            KernelArgError::Count {
+                kernel,
+                expected,
+                provided,
+            } => write!(
+                f,
+                "The kernel '{kernel}' expects {expected} arguments, but {provided} were provided."
+            ),
+            KernelArgError::Mismatch {
+                kernel,
+                idx,
+                param,
+                provided,
+            } => {
+                write!(f, "Argument {idx} of the kernel '{kernel}' is declared as '{param}', but {provided} was provided.")?;
+                match param.address_space {
+                    AddressSpace::Local => {
+                        write!(f, " Local memory is passed as a `LocalMem`.")
+                    }
+                    _ => Ok(()),
+                }
+            }
+            KernelArgError::Rejected { kernel, idx, kind } => write!(
+                f,
+                "Argument {idx} of the kernel '{kernel}' was rejected by OpenCL: {kind:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KernelArgError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{scalar_size, AddressSpace, KernelArgError, KernelParam, KernelSignature};
+    use crate::opencl::{AsClCvoidPtr, LocalMem};
+
+    fn param(address_space: AddressSpace, type_name: &str, name: &str) -> KernelParam {
+        KernelParam {
+            name: name.into(),
+            type_name: type_name.into(),
+            address_space,
+        }
+    }
+
+    fn signature() -> KernelSignature {
+        KernelSignature {
+            name: "scale".into(),
+            num_args: 3,
+            params: Some(vec![
+                param(AddressSpace::Global, "float*", "x"),
+                param(AddressSpace::Private, "float", "factor"),
+                param(AddressSpace::Private, "int", "len"),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_scalar_size() {
+        assert_eq!(scalar_size("float"), Some(4));
+        assert_eq!(scalar_size("uchar"), Some(1));
+        assert_eq!(scalar_size("double2"), Some(16));
+        assert_eq!(scalar_size("int3"), Some(16));
+        assert_eq!(scalar_size("half16"), Some(32));
+        assert_eq!(scalar_size("float5"), None);
+        assert_eq!(scalar_size("float*"), None);
+        assert_eq!(scalar_size("sampler_t"), None);
+        assert_eq!(scalar_size("struct Foo"), None);
+    }
+
+    #[test]
+    fn test_check_args_count() {
+        let buf = 0f32;
+        let err = signature().check_args(&[&buf]).unwrap_err();
+        assert_eq!(
+            err,
+            KernelArgError::Count {
+                kernel: "scale".into(),
+                expected: 3,
+                provided: 1
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "The kernel 'scale' expects 3 arguments, but 1 were provided."
+        );
+    }
+
+    #[test]
+    fn test_check_args_scalars() {
+        let signature = KernelSignature {
+            name: "scale".into(),
+            num_args: 2,
+            params: Some(vec![
+                param(AddressSpace::Private, "float", "factor"),
+                param(AddressSpace::Private, "int", "len"),
+            ]),
+        };
+        assert!(signature.check_args(&[&2f32, &3i32]).is_ok());
+
+        let err = signature.check_args(&[&2f64, &3i32]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Argument 0 of the kernel 'scale' is declared as 'float factor', but a number of 8 bytes was provided."
+        );
+    }
+
+    #[test]
+    fn test_check_args_number_for_buffer() {
+        let err = signature().check_args(&[&1i32, &2f32, &3i32]).unwrap_err();
+        assert_eq!(
+            err,
+            KernelArgError::Mismatch {
+                kernel: "scale".into(),
+                idx: 0,
+                param: param(AddressSpace::Global, "float*", "x"),
+                provided: "a number of 4 bytes".into()
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Argument 0 of the kernel 'scale' is declared as '__global float* x', but a number of 4 bytes was provided."
+        );
+    }
+
+    #[test]
+    fn test_check_args_local() {
+        let signature = KernelSignature {
+            name: "sum".into(),
+            num_args: 2,
+            params: Some(vec![
+                param(AddressSpace::Global, "float*", "x"),
+                param(AddressSpace::Local, "float*", "tmp"),
+            ]),
+        };
+
+        // passed like a buffer
+        struct Global;
+        impl AsClCvoidPtr for Global {
+            fn as_cvoid_ptr(&self) -> *const core::ffi::c_void {
+                core::ptr::null()
+            }
+        }
+
+        let local = LocalMem::new::<f32>(64);
+        assert!(signature.check_args(&[&Global, &local]).is_ok());
+        assert!(signature.check_args(&[&local, &local]).is_err());
+
+        let err = signature.check_args(&[&Global, &1f32]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Argument 1 of the kernel 'sum' is declared as '__local float* tmp', but a number of 4 bytes was provided. Local memory is passed as a `LocalMem`."
+        );
+    }
+
+    #[test]
+    fn test_check_args_without_params() {
+        let signature = KernelSignature {
+            name: "scale".into(),
+            num_args: 2,
+            params: None,
+        };
+        // only the count is checked
+        assert!(signature.check_args(&[&1i32, &2f64]).is_ok());
+        assert!(signature.check_args(&[&1i32]).is_err());
+    }
+}
@@ -0,0 +1,542 @@
+use core::{
+    ffi::c_void,
+    marker::PhantomData,
+    mem::{size_of, size_of_val},
+    ops::{Deref, DerefMut},
+    ptr::{null, null_mut},
+};
+
+use min_cl::api::{cl_context, Kernel};
+
+use super::{
+    ffi::{
+        check, clEnqueueSVMMap, clEnqueueSVMMemFill, clEnqueueSVMMemcpy, clEnqueueSVMUnmap,
+        clSVMAlloc, clSVMFree, clSetKernelArgSVMPointer, cl_map_flags, cl_svm_mem_flags,
+        CL_DEVICE_SVM_CAPABILITIES, CL_DEVICE_SVM_COARSE_GRAIN_BUFFER,
+        CL_DEVICE_SVM_FINE_GRAIN_BUFFER, CL_MAP_READ, CL_MAP_WRITE, CL_MEM_READ_WRITE,
+        CL_MEM_SVM_FINE_GRAIN_BUFFER,
+    },
+    info::device_u64,
+    AsClCvoidPtr, CLEvent, CommandKind, OpenCL,
+};
+use crate::{
+    flag::AllocFlag, shape::Shape, Alloc, Buffer, CommonPtrs, Device, DeviceError, IsShapeIndep,
+    MainMemory, PtrType, Read, ShallowCopy, WriteBuf,
+};
+
+/// The granularity of the shared virtual memory of an [`SVM`] device.
+pub trait Granularity {
+    /// The flags passed to `clSVMAlloc`.
+    const FLAGS: cl_svm_mem_flags;
+    /// The bit of `CL_DEVICE_SVM_CAPABILITIES`, which must be set to use this granularity.
+    const CAPABILITY: u64;
+}
+
+/// The host and the device access the memory directly. Hence, buffers implement [`MainMemory`].
+/// The host may only access a buffer while no kernel uses it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FineGrained;
+
+impl Granularity for FineGrained {
+    const FLAGS: cl_svm_mem_flags = CL_MEM_READ_WRITE | CL_MEM_SVM_FINE_GRAIN_BUFFER;
+    const CAPABILITY: u64 = CL_DEVICE_SVM_FINE_GRAIN_BUFFER;
+}
+
+/// The host must map a buffer before accessing it (see [`Buffer::map`] and [`Buffer::map_mut`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoarseGrained;
+
+impl Granularity for CoarseGrained {
+    const FLAGS: cl_svm_mem_flags = CL_MEM_READ_WRITE;
+    const CAPABILITY: u64 = CL_DEVICE_SVM_COARSE_GRAIN_BUFFER;
+}
+
+/// A device, which allocates shared virtual memory (OpenCL 2.0) on an [`OpenCL`] device.
+///
+/// The buffers share their address space with the host and can be passed to [`enqueue_kernel`](super::enqueue_kernel) like any other OpenCL buffer.
+/// Fine-grained buffers are accessed by the host directly, coarse-grained buffers must be mapped.
+/// # Example
+/// ```
+/// use custos::{opencl::{enqueue_kernel, FineGrained, SVM}, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     if !SVM::<FineGrained>::is_supported(&device) {
+///         return Ok(());
+///     }
+///     let svm = SVM::fine(&device)?;
+///
+///     let mut buf = Buffer::from((&svm, [1f32, 2., 3.]));
+///     buf[0] = 5.;
+///
+///     let src = "
+///         __kernel void add_one(__global float* x) {
+///             x[get_global_id(0)] += 1;
+///         }
+///     ";
+///     enqueue_kernel(&device, src, [3, 0, 0], None, &[&buf])?;
+///
+///     assert_eq!(buf.as_slice(), [6., 3., 4.]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SVM<'a, G = FineGrained> {
+    pub device: &'a OpenCL,
+    _granularity: PhantomData<G>,
+}
+
+impl<'a> SVM<'a, FineGrained> {
+    /// Creates a device, which allocates fine-grained buffers.
+    ///
+    /// # Errors
+    /// - [`DeviceError::SvmNotSupported`]: the device does not support fine-grained buffers
+    #[inline]
+    pub fn fine(device: &'a OpenCL) -> crate::Result<Self> {
+        SVM::with_granularity(device)
+    }
+}
+
+impl<'a> SVM<'a, CoarseGrained> {
+    /// Creates a device, which allocates coarse-grained buffers.
+    ///
+    /// # Errors
+    /// - [`DeviceError::SvmNotSupported`]: the device does not support coarse-grained buffers
+    #[inline]
+    pub fn coarse(device: &'a OpenCL) -> crate::Result<Self> {
+        SVM::with_granularity(device)
+    }
+}
+
+impl<'a, G: Granularity> SVM<'a, G> {
+    fn with_granularity(device: &'a OpenCL) -> crate::Result<Self> {
+        if !Self::is_supported(device) {
+            return Err(DeviceError::SvmNotSupported.into());
+        }
+
+        Ok(SVM {
+            device,
+            _granularity: PhantomData,
+        })
+    }
+
+    /// Returns whether the device supports shared virtual memory with the granularity `G`.
+    /// Devices below OpenCL 2.0 do not support shared virtual memory at all.
+    pub fn is_supported(device: &OpenCL) -> bool {
+        // a query error means that the device predates OpenCL 2.0
+        device_u64(device.device().0, CL_DEVICE_SVM_CAPABILITIES)
+            .map_or(false, |capabilities| capabilities & G::CAPABILITY != 0)
+    }
+
+    /// Copies `size` bytes with `clEnqueueSVMMemcpy` and waits for the copy to finish.
+    fn memcpy(
+        &self,
+        dst: *mut c_void,
+        src: *const c_void,
+        size: usize,
+        kind: CommandKind,
+    ) -> crate::Result<()> {
+        let mut event = null_mut();
+        check(unsafe {
+            clEnqueueSVMMemcpy(
+                self.device.queue().0,
+                0,
+                dst,
+                src,
+                size,
+                0,
+                null(),
+                &mut event,
+            )
+        })?;
+
+        let event = unsafe { CLEvent::from_raw(event) };
+        self.device.record_event(kind, &event, || kind.as_str().into());
+        event.wait()
+    }
+
+    /// Sets `size` bytes to zero and waits for the fill to finish.
+    fn zero(&self, ptr: *mut c_void, size: usize) -> crate::Result<()> {
+        let pattern = 0u8;
+
+        let mut event = null_mut();
+        check(unsafe {
+            clEnqueueSVMMemFill(
+                self.device.queue().0,
+                ptr,
+                (&pattern as *const u8).cast(),
+                1,
+                size,
+                0,
+                null(),
+                &mut event,
+            )
+        })?;
+
+        let event = unsafe { CLEvent::from_raw(event) };
+        self.device
+            .record_event(CommandKind::Write, &event, || "fill".into());
+        event.wait()
+    }
+}
+
+/// The pointer type of the [`SVM`] device. The memory is freed with `clSVMFree`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SvmPtr<T> {
+    pub ptr: *mut T,
+    pub len: usize,
+    pub flag: AllocFlag,
+    /// The context, which allocated the memory.
+    pub ctx: cl_context,
+}
+
+impl<T> Default for SvmPtr<T> {
+    fn default() -> Self {
+        Self {
+            ptr: null_mut(),
+            len: 0,
+            flag: AllocFlag::default(),
+            ctx: null_mut(),
+        }
+    }
+}
+
+impl<T> PtrType for SvmPtr<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn flag(&self) -> AllocFlag {
+        self.flag
+    }
+}
+
+impl<T> CommonPtrs<T> for SvmPtr<T> {
+    #[inline]
+    fn ptrs(&self) -> (*const T, *mut c_void, u64) {
+        (self.ptr, self.ptr.cast(), 0)
+    }
+
+    #[inline]
+    fn ptrs_mut(&mut self) -> (*mut T, *mut c_void, u64) {
+        (self.ptr, self.ptr.cast(), 0)
+    }
+}
+
+impl<T> ShallowCopy for SvmPtr<T> {
+    #[inline]
+    unsafe fn shallow(&self) -> Self {
+        SvmPtr {
+            ptr: self.ptr,
+            len: self.len,
+            flag: AllocFlag::Wrapper,
+            ctx: self.ctx,
+        }
+    }
+}
+
+impl<T> Drop for SvmPtr<T> {
+    fn drop(&mut self) {
+        if self.flag != AllocFlag::None {
+            return;
+        }
+
+        if self.ptr.is_null() {
+            return;
+        }
+        unsafe { clSVMFree(self.ctx, self.ptr.cast()) }
+    }
+}
+
+impl<G: Granularity> Device for SVM<'_, G> {
+    type Ptr<U, S: Shape> = SvmPtr<U>;
+    type Cache = ();
+
+    fn new() -> crate::Result<Self> {
+        Err(DeviceError::MissingSvmDevice.into())
+    }
+}
+
+impl<G: Granularity> IsShapeIndep for SVM<'_, G> {}
+
+impl<T, S: Shape, G: Granularity> Alloc<'_, T, S> for SVM<'_, G> {
+    /// The memory is zeroed.
+    /// # Panics
+    /// If the allocation fails.
+    fn alloc(&self, mut len: usize, flag: AllocFlag) -> SvmPtr<T> {
+        assert!(len > 0, "invalid buffer len: 0");
+
+        if S::LEN > len {
+            len = S::LEN
+        }
+
+        let ctx = self.device.ctx().0;
+        let ptr = unsafe { clSVMAlloc(ctx, G::FLAGS, len * size_of::<T>(), 0) };
+        assert!(!ptr.is_null(), "Could not allocate shared virtual memory.");
+
+        let ptr = SvmPtr::<T> {
+            ptr: ptr.cast(),
+            len,
+            flag,
+            ctx,
+        };
+        self.zero(ptr.ptr.cast(), len * size_of::<T>()).unwrap();
+        ptr
+    }
+
+    fn with_slice(&self, data: &[T]) -> SvmPtr<T>
+    where
+        T: Clone,
+    {
+        let ptr = Alloc::<T, S>::alloc(self, data.len(), AllocFlag::None);
+        self.memcpy(
+            ptr.ptr.cast(),
+            data.as_ptr().cast(),
+            size_of_val(data),
+            CommandKind::Write,
+        )
+        .unwrap();
+        ptr
+    }
+}
+
+impl MainMemory for SVM<'_, FineGrained> {
+    #[inline]
+    fn as_ptr<T, S: Shape>(ptr: &Self::Ptr<T, S>) -> *const T {
+        ptr.ptr
+    }
+
+    #[inline]
+    fn as_ptr_mut<T, S: Shape>(ptr: &mut Self::Ptr<T, S>) -> *mut T {
+        ptr.ptr
+    }
+}
+
+impl<'d, T, S: Shape> Read<T, SVM<'d, FineGrained>, S> for SVM<'d, FineGrained> {
+    type Read<'a> = &'a [T] where T: 'a, 'd: 'a, S: 'a;
+
+    #[inline]
+    fn read<'a>(&self, buf: &'a Buffer<T, SVM<'d, FineGrained>, S>) -> Self::Read<'a> {
+        buf.as_slice()
+    }
+
+    #[inline]
+    fn read_to_vec(&self, buf: &Buffer<T, SVM<'d, FineGrained>, S>) -> Vec<T>
+    where
+        T: Default + Clone,
+    {
+        buf.to_vec()
+    }
+}
+
+impl<'d, T: Copy + Default, S: Shape> Read<T, SVM<'d, CoarseGrained>, S>
+    for SVM<'d, CoarseGrained>
+{
+    type Read<'a> = Vec<T> where T: 'a, 'd: 'a, S: 'a;
+
+    #[inline]
+    fn read<'a>(&self, buf: &'a Buffer<T, SVM<'d, CoarseGrained>, S>) -> Self::Read<'a> {
+        self.read_to_vec(buf)
+    }
+
+    fn read_to_vec(&self, buf: &Buffer<T, SVM<'d, CoarseGrained>, S>) -> Vec<T> {
+        let mut data = vec![T::default(); buf.len()];
+        self.memcpy(
+            data.as_mut_ptr().cast(),
+            buf.ptr.ptr.cast(),
+            buf.len() * size_of::<T>(),
+            CommandKind::Read,
+        )
+        .unwrap();
+        data
+    }
+}
+
+impl<T: Copy, S: Shape, G: Granularity> WriteBuf<T, Self, S> for SVM<'_, G> {
+    fn write(&self, buf: &mut Buffer<T, Self, S>, data: &[T]) {
+        assert!(data.len() <= buf.len(), "data is longer than the buffer");
+        self.memcpy(
+            buf.ptr.ptr.cast(),
+            data.as_ptr().cast(),
+            size_of_val(data),
+            CommandKind::Write,
+        )
+        .unwrap();
+    }
+
+    fn write_buf(&self, dst: &mut Buffer<T, Self, S>, src: &Buffer<T, Self, S>) {
+        assert_eq!(
+            dst.len(),
+            src.len(),
+            "the buffers must have the same length"
+        );
+        self.memcpy(
+            dst.ptr.ptr.cast(),
+            src.ptr.ptr.cast(),
+            src.len() * size_of::<T>(),
+            CommandKind::Copy,
+        )
+        .unwrap();
+    }
+}
+
+impl<'a, T, S: Shape, G: Granularity> AsClCvoidPtr for &Buffer<'a, T, SVM<'_, G>, S> {
+    #[inline]
+    fn as_cvoid_ptr(&self) -> *const c_void {
+        self.ptr.ptr.cast()
+    }
+
+    #[inline]
+    fn is_svm(&self) -> bool {
+        true
+    }
+}
+
+impl<'a, T, S: Shape, G: Granularity> AsClCvoidPtr for Buffer<'a, T, SVM<'_, G>, S> {
+    #[inline]
+    fn as_cvoid_ptr(&self) -> *const c_void {
+        self.ptr.ptr.cast()
+    }
+
+    #[inline]
+    fn is_svm(&self) -> bool {
+        true
+    }
+}
+
+/// Sets a kernel argument to the shared virtual memory at `ptr`.
+pub(super) fn set_svm_kernel_arg(
+    kernel: &Kernel,
+    idx: usize,
+    ptr: *const c_void,
+) -> crate::Result<()> {
+    check(unsafe { clSetKernelArgSVMPointer(kernel.0, idx as u32, ptr) })
+}
+
+impl<'a, T, S: Shape> Buffer<'a, T, SVM<'_, CoarseGrained>, S> {
+    /// Maps the buffer for reading on the host. The buffer is unmapped when the guard is dropped.
+    /// # Example
+    /// ```
+    /// use custos::{opencl::{CoarseGrained, SVM}, Buffer, OpenCL};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = OpenCL::new(0)?;
+    ///     if !SVM::<CoarseGrained>::is_supported(&device) {
+    ///         return Ok(());
+    ///     }
+    ///     let svm = SVM::coarse(&device)?;
+    ///
+    ///     let mut buf = Buffer::<i32, _>::new(&svm, 3);
+    ///     buf.map_mut()?.copy_from_slice(&[1, 2, 3]);
+    ///
+    ///     assert_eq!(*buf.map()?, [1, 2, 3]);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn map(&self) -> crate::Result<SvmMap<'_, T>> {
+        Ok(SvmMap {
+            mapped: Mapped::new(self.device().device, &self.ptr, CL_MAP_READ)?,
+        })
+    }
+
+    /// Maps the buffer for reading and writing on the host. The buffer is unmapped when the guard is dropped.
+    #[inline]
+    pub fn map_mut(&mut self) -> crate::Result<SvmMapMut<'_, T>> {
+        Ok(SvmMapMut {
+            mapped: Mapped::new(
+                self.device().device,
+                &self.ptr,
+                CL_MAP_READ | CL_MAP_WRITE,
+            )?,
+        })
+    }
+}
+
+/// A mapped region of a coarse-grained buffer. Unmaps the region when dropped.
+#[derive(Debug)]
+struct Mapped<'a, T> {
+    device: &'a OpenCL,
+    ptr: *mut T,
+    len: usize,
+}
+
+impl<'a, T> Mapped<'a, T> {
+    fn new(device: &'a OpenCL, ptr: &SvmPtr<T>, flags: cl_map_flags) -> crate::Result<Self> {
+        check(unsafe {
+            clEnqueueSVMMap(
+                device.queue().0,
+                1,
+                flags,
+                ptr.ptr.cast(),
+                ptr.len * size_of::<T>(),
+                0,
+                null(),
+                null_mut(),
+            )
+        })?;
+
+        Ok(Mapped {
+            device,
+            ptr: ptr.ptr,
+            len: ptr.len,
+        })
+    }
+}
+
+impl<T> Drop for Mapped<'_, T> {
+    fn drop(&mut self) {
+        let mut event = null_mut();
+        check(unsafe {
+            clEnqueueSVMUnmap(
+                self.device.queue().0,
+                self.ptr.cast(),
+                0,
+                null(),
+                &mut event,
+            )
+        })
+        .unwrap();
+
+        unsafe { CLEvent::from_raw(event) }.wait().unwrap();
+    }
+}
+
+/// A coarse-grained buffer, which is mapped for reading. Returned by [`Buffer::map`].
+#[derive(Debug)]
+pub struct SvmMap<'a, T> {
+    mapped: Mapped<'a, T>,
+}
+
+impl<T> Deref for SvmMap<'_, T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.mapped.ptr, self.mapped.len) }
+    }
+}
+
+/// A coarse-grained buffer, which is mapped for reading and writing. Returned by [`Buffer::map_mut`].
+#[derive(Debug)]
+pub struct SvmMapMut<'a, T> {
+    mapped: Mapped<'a, T>,
+}
+
+impl<T> Deref for SvmMapMut<'_, T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.mapped.ptr, self.mapped.len) }
+    }
+}
+
+impl<T> DerefMut for SvmMapMut<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { core::slice::from_raw_parts_mut(self.mapped.ptr, self.mapped.len) }
+    }
+}
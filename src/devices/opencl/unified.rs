@@ -48,10 +48,11 @@ pub unsafe fn to_unified<T>(
 /// Converts an 'only' CPU buffer into an OpenCL + CPU (unified memory) buffer.
 /// # Safety
 /// The host pointer of the no_drop `Buffer` must be valid for the entire lifetime of the returned Buffer.
+/// # Errors
+/// [`DeviceError::NoUnifiedMemory`], if the device does not use unified memory.
 ///
 /// # Example
-#[cfg_attr(unified_cl, doc = "```")]
-#[cfg_attr(not(unified_cl), doc = "```ignore")]
+/// ```
 /// use custos::prelude::*;
 ///
 /// fn main() -> custos::Result<()> {
@@ -60,12 +61,16 @@ pub unsafe fn to_unified<T>(
 ///     no_drop.write(&[1., 3.1, 2.34, 0.76]);
 ///     
 ///     let device = OpenCL::new(0)?;
+///     if !device.unified_mem() {
+///         return Ok(());
+///     }
+///
 ///     let buf = unsafe {
 ///         construct_buffer(&device, no_drop, ())?
 ///     };
 ///     
 ///     assert_eq!(buf.read(), vec![1., 3.1, 2.34, 0.76]);
-///     assert_eq!(buf.as_slice(), &[1., 3.1, 2.34, 0.76]);
+///     assert_eq!(buf.try_as_slice()?, &[1., 3.1, 2.34, 0.76]);
 ///     Ok(())
 /// }
 /// ```
@@ -80,6 +85,10 @@ pub unsafe fn construct_buffer<'a, T: Debug>(
         return Err(DeviceError::ConstructError.into());
     }
 
+    if !device.unified_mem() {
+        return Err(DeviceError::NoUnifiedMemory.into());
+    }
+
     // if buffer was already converted, return the cache entry.
     if let Some(rawcl) = device.cache.borrow().nodes.get(&Ident::new(no_drop.len())) {
         return Ok(Buffer {
@@ -113,7 +122,6 @@ pub unsafe fn construct_buffer<'a, T: Debug>(
     })
 }
 
-#[cfg(unified_cl)]
 #[cfg(test)]
 mod tests {
     use crate::{opencl::CLPtr, AllocFlag, Buffer, CacheBuf, Node, OpenCL, CPU};
@@ -127,6 +135,9 @@ mod tests {
         no_drop.write(&[1., 2.3, 0.76]);
 
         let device = OpenCL::new(0)?;
+        if !device.unified_mem() {
+            return Ok(());
+        }
 
         let (host_ptr, len) = (no_drop.host_ptr_mut(), no_drop.len());
         let cl_host_ptr = unsafe { to_unified(&device, no_drop, Node::default())? };
@@ -143,7 +154,7 @@ mod tests {
         };
 
         assert_eq!(buf.read(), vec![1., 2.3, 0.76]);
-        assert_eq!(buf.as_slice(), &[1., 2.3, 0.76]);
+        assert_eq!(buf.try_as_slice()?, &[1., 2.3, 0.76]);
         Ok(())
     }
 
@@ -154,10 +165,14 @@ mod tests {
         no_drop.write(&[1., 2.3, 0.76]);
 
         let device = OpenCL::new(0)?;
+        if !device.unified_mem() {
+            return Ok(());
+        }
+
         let buf = unsafe { construct_buffer(&device, no_drop, ())? };
 
         assert_eq!(buf.read(), vec![1., 2.3, 0.76]);
-        assert_eq!(buf.as_slice(), &[1., 2.3, 0.76]);
+        assert_eq!(buf.try_as_slice()?, &[1., 2.3, 0.76]);
 
         Ok(())
     }
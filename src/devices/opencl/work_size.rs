@@ -0,0 +1,160 @@
+use core::ptr::null_mut;
+
+use min_cl::api::Kernel;
+
+use super::{
+    enqueue,
+    ffi::{
+        check, clGetKernelWorkGroupInfo, cl_kernel_work_group_info,
+        CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE, CL_KERNEL_WORK_GROUP_SIZE,
+    },
+    AsClCvoidPtr, OpenCL,
+};
+
+/// The work-group limits of a kernel on a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkGroupInfo {
+    /// The maximum number of work-items in a work-group (`CL_KERNEL_WORK_GROUP_SIZE`).
+    pub max_size: usize,
+    /// Work-groups should be a multiple of this size, e.g. the warp or wavefront size (`CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE`).
+    pub preferred_multiple: usize,
+}
+
+impl WorkGroupInfo {
+    /// Queries the work-group limits of the kernel on the device.
+    pub fn new(device: &OpenCL, kernel: &Kernel) -> crate::Result<WorkGroupInfo> {
+        Ok(WorkGroupInfo {
+            max_size: work_group_info(device, kernel, CL_KERNEL_WORK_GROUP_SIZE)?,
+            preferred_multiple: work_group_info(
+                device,
+                kernel,
+                CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE,
+            )?,
+        })
+    }
+
+    /// The largest multiple of the preferred multiple, which fits into a work-group.
+    /// Small problems use a smaller work-group, hence less work-items are wasted by padding.
+    pub fn local_size_1d(&self, len: usize) -> usize {
+        let max_size = self.max_size.max(1);
+        let multiple = self.preferred_multiple.clamp(1, max_size);
+
+        let local_size = max_size / multiple * multiple;
+        local_size.min(round_up(len.max(1), multiple))
+    }
+
+    /// Returns the global and local work size to launch `len` work-items.
+    /// The global work size is padded to a multiple of the local work size, hence the kernel must ignore the work-items with an id `>= len`.
+    pub fn launch_config_1d(&self, len: usize) -> ([usize; 3], [usize; 3]) {
+        let local_size = self.local_size_1d(len);
+        ([round_up(len.max(1), local_size), 0, 0], [local_size, 1, 1])
+    }
+}
+
+fn work_group_info(
+    device: &OpenCL,
+    kernel: &Kernel,
+    param: cl_kernel_work_group_info,
+) -> crate::Result<usize> {
+    let mut value = 0usize;
+    check(unsafe {
+        clGetKernelWorkGroupInfo(
+            kernel.0,
+            device.device().0,
+            param,
+            core::mem::size_of::<usize>(),
+            (&mut value as *mut usize).cast(),
+            null_mut(),
+        )
+    })?;
+    Ok(value)
+}
+
+#[inline]
+fn round_up(value: usize, multiple: usize) -> usize {
+    (value + multiple - 1) / multiple * multiple
+}
+
+/// Launches a kernel with `len` work-items, whose work-group size is chosen by the work-group limits of the kernel (see [`WorkGroupInfo::launch_config_1d`]).
+/// The global work size is padded. Therefore, the kernel must check the bounds of the global id.
+/// # Example
+/// ```
+/// use custos::{opencl::enqueue_kernel1d, Buffer, OpenCL};
+///
+/// fn main() -> custos::Result<()> {
+///     let device = OpenCL::new(0)?;
+///     let buf = Buffer::<f32, _>::from((&device, [1., 2., 3., 4., 5.]));
+///
+///     let src = "
+///         __kernel void scale(__global float* x, const int len) {
+///             size_t id = get_global_id(0);
+///             if (id >= len) {
+///                 return;
+///             }
+///             x[id] *= 2;
+///         }
+///     ";
+///     enqueue_kernel1d(&device, src, buf.len(), &[&buf, &(buf.len() as i32)])?;
+///
+///     assert_eq!(buf.read(), vec![2., 4., 6., 8., 10.]);
+///     Ok(())
+/// }
+/// ```
+pub fn enqueue_kernel1d(
+    device: &OpenCL,
+    src: &str,
+    len: usize,
+    args: &[&dyn AsClCvoidPtr],
+) -> crate::Result<()> {
+    let kernel = device.kernel_cache.borrow_mut().kernel_cache(device, src)?;
+
+    let (gws, lws) = WorkGroupInfo::new(device, &kernel)?.launch_config_1d(len);
+    enqueue(device, &kernel, gws, Some(lws), args, &[])?.wait()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WorkGroupInfo;
+
+    #[test]
+    fn test_launch_config_1d() {
+        let info = WorkGroupInfo {
+            max_size: 1024,
+            preferred_multiple: 32,
+        };
+
+        assert_eq!(
+            info.launch_config_1d(100_000),
+            ([100_352, 0, 0], [1024, 1, 1])
+        );
+        assert_eq!(info.launch_config_1d(2048), ([2048, 0, 0], [1024, 1, 1]));
+        // small problems use a small work-group
+        assert_eq!(info.launch_config_1d(5), ([32, 0, 0], [32, 1, 1]));
+        assert_eq!(info.launch_config_1d(40), ([64, 0, 0], [64, 1, 1]));
+        assert_eq!(info.launch_config_1d(0), ([32, 0, 0], [32, 1, 1]));
+    }
+
+    #[test]
+    fn test_launch_config_1d_odd_limits() {
+        // the maximum is not a multiple of the preferred multiple
+        let info = WorkGroupInfo {
+            max_size: 100,
+            preferred_multiple: 32,
+        };
+        assert_eq!(info.launch_config_1d(1000), ([1056, 0, 0], [96, 1, 1]));
+
+        // the preferred multiple exceeds the maximum
+        let info = WorkGroupInfo {
+            max_size: 16,
+            preferred_multiple: 64,
+        };
+        assert_eq!(info.launch_config_1d(1000), ([1008, 0, 0], [16, 1, 1]));
+
+        // unknown limits
+        let info = WorkGroupInfo {
+            max_size: 0,
+            preferred_multiple: 0,
+        };
+        assert_eq!(info.launch_config_1d(3), ([3, 0, 0], [1, 1, 1]));
+    }
+}
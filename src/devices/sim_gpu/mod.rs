@@ -0,0 +1,196 @@
+use core::{
+    cell::RefCell,
+    marker::PhantomData,
+    mem::{size_of, size_of_val},
+};
+use std::rc::Rc;
+
+pub use sim_gpu_device::*;
+
+use crate::{flag::AllocFlag, CommonPtrs, PtrType, ShallowCopy};
+
+mod sim_gpu_device;
+
+/// The "device memory" of a [`SimGPU`].
+/// Allocations are stored as plain bytes and are only accessible via their id.
+#[derive(Debug, Default)]
+pub struct SimArena {
+    slots: Vec<Option<Vec<u8>>>,
+    free: Vec<usize>,
+}
+
+impl SimArena {
+    /// Allocates `bytes` zeroed bytes and returns the id of the allocation.
+    pub fn alloc(&mut self, bytes: usize) -> usize {
+        let slot = Some(vec![0; bytes]);
+
+        match self.free.pop() {
+            Some(id) => {
+                self.slots[id] = slot;
+                id
+            }
+            None => {
+                self.slots.push(slot);
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    pub fn free(&mut self, id: usize) {
+        self.slots[id] = None;
+        self.free.push(id);
+    }
+
+    /// The number of live allocations.
+    pub fn allocations(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    #[inline]
+    pub fn bytes(&self, id: usize) -> &[u8] {
+        self.slots[id]
+            .as_ref()
+            .expect("Access to a freed allocation.")
+    }
+
+    #[inline]
+    pub fn bytes_mut(&mut self, id: usize) -> &mut [u8] {
+        self.slots[id]
+            .as_mut()
+            .expect("Access to a freed allocation.")
+    }
+
+    /// Copies `len` elements, starting at element `offset`, out of the allocation `id`.
+    pub fn load<T: Copy>(&self, id: usize, offset: usize, len: usize) -> Vec<T> {
+        let bytes = &self.bytes(id)[offset * size_of::<T>()..(offset + len) * size_of::<T>()];
+
+        let mut data = Vec::<T>::with_capacity(len);
+        unsafe {
+            core::ptr::copy_nonoverlapping(bytes.as_ptr(), data.as_mut_ptr().cast(), bytes.len());
+            data.set_len(len);
+        }
+        data
+    }
+
+    /// Copies `data` into the allocation `id`, starting at element `offset`.
+    pub fn store<T: Copy>(&mut self, id: usize, offset: usize, data: &[T]) {
+        let start = offset * size_of::<T>();
+        let bytes = &mut self.bytes_mut(id)[start..start + size_of_val(data)];
+
+        unsafe {
+            core::ptr::copy_nonoverlapping(data.as_ptr().cast(), bytes.as_mut_ptr(), bytes.len());
+        }
+    }
+}
+
+/// The pointer type of the [`SimGPU`] device.
+/// It does not point to memory, but identifies an allocation in the [`SimArena`].
+#[derive(Debug)]
+pub struct SimPtr<T> {
+    pub id: usize,
+    pub len: usize,
+    pub flag: AllocFlag,
+    arena: Option<Rc<RefCell<SimArena>>>,
+    _pd: PhantomData<T>,
+}
+
+impl<T> SimPtr<T> {
+    pub fn new(arena: &Rc<RefCell<SimArena>>, len: usize, flag: AllocFlag) -> SimPtr<T> {
+        SimPtr {
+            id: arena.borrow_mut().alloc(len * size_of::<T>()),
+            len,
+            flag,
+            arena: Some(arena.clone()),
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for SimPtr<T> {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            len: 0,
+            flag: AllocFlag::default(),
+            arena: None,
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for SimPtr<T> {
+    fn drop(&mut self) {
+        if self.flag != AllocFlag::None {
+            return;
+        }
+
+        if let Some(arena) = &self.arena {
+            arena.borrow_mut().free(self.id);
+        }
+    }
+}
+
+impl<T> PtrType for SimPtr<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn flag(&self) -> AllocFlag {
+        self.flag
+    }
+}
+
+impl<T> CommonPtrs<T> for SimPtr<T> {
+    #[inline]
+    fn ptrs(&self) -> (*const T, *mut core::ffi::c_void, u64) {
+        (core::ptr::null(), core::ptr::null_mut(), self.id as u64)
+    }
+
+    #[inline]
+    fn ptrs_mut(&mut self) -> (*mut T, *mut core::ffi::c_void, u64) {
+        (core::ptr::null_mut(), core::ptr::null_mut(), self.id as u64)
+    }
+}
+
+impl<T> ShallowCopy for SimPtr<T> {
+    #[inline]
+    unsafe fn shallow(&self) -> Self {
+        SimPtr {
+            id: self.id,
+            len: self.len,
+            flag: AllocFlag::Wrapper,
+            arena: self.arena.clone(),
+            _pd: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SimArena;
+
+    #[test]
+    fn test_arena_reuses_slots() {
+        let mut arena = SimArena::default();
+        let a = arena.alloc(8);
+        let b = arena.alloc(4);
+        assert_eq!(arena.allocations(), 2);
+
+        arena.free(a);
+        assert_eq!(arena.allocations(), 1);
+        assert_eq!(arena.alloc(16), a);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_arena_load_store() {
+        let mut arena = SimArena::default();
+        let id = arena.alloc(4 * 4);
+
+        arena.store(id, 1, &[1f32, 2.]);
+        assert_eq!(arena.load::<f32>(id, 0, 4), [0., 1., 2., 0.]);
+        assert_eq!(arena.load::<f32>(id, 2, 1), [2.]);
+    }
+}
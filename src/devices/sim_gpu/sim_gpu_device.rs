@@ -0,0 +1,277 @@
+use core::{
+    cell::{Cell, RefCell},
+    mem::size_of,
+    ops::{Range, RangeBounds},
+    time::Duration,
+};
+use std::rc::Rc;
+
+use super::{SimArena, SimPtr};
+use crate::{
+    bounds_to_range, flag::AllocFlag, shape::Shape, Alloc, Buffer, ClearBuf, CloneBuf, CopySlice,
+    Device, IsShapeIndep, Read, WriteBuf,
+};
+
+/// Counts the transfers between host and a [`SimGPU`] and the launched kernels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SimStats {
+    /// Number of device to host transfers.
+    pub reads: usize,
+    /// Number of host to device transfers.
+    pub writes: usize,
+    pub bytes_read: usize,
+    pub bytes_written: usize,
+    /// Number of kernels launched via [`SimGPU::launch`].
+    pub launches: usize,
+}
+
+/// A host-side device, which behaves like a discrete GPU.
+///
+/// The data of its buffers lives in an opaque [`SimArena`] and [`MainMemory`](crate::MainMemory) is deliberately not implemented.
+/// Therefore, data can only be accessed via [`Read`] and [`WriteBuf`], like on a real GPU.
+/// These transfers are counted (see [`SimGPU::stats`]).
+///
+/// # Example
+/// ```
+/// use custos::{Buffer, SimGPU};
+///
+/// let device = SimGPU::new();
+///
+/// let lhs = Buffer::from((&device, [1, 2, 3, 4]));
+/// let rhs = Buffer::from((&device, [4, 3, 2, 1]));
+///
+/// let mut out = Buffer::new(&device, 4);
+/// device.launch(&mut out, &[&lhs, &rhs], |x| x[0] + x[1]);
+///
+/// assert_eq!(out.read(), vec![5; 4]);
+///
+/// // two uploads, one download
+/// assert_eq!(device.stats().writes, 2);
+/// assert_eq!(device.stats().reads, 1);
+/// ```
+#[derive(Debug)]
+pub struct SimGPU {
+    pub arena: Rc<RefCell<SimArena>>,
+    /// Simulated latency, which is added to every transfer and every kernel launch.
+    pub latency: Option<Duration>,
+    stats: Cell<SimStats>,
+}
+
+impl Default for SimGPU {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimGPU {
+    /// Creates a [`SimGPU`] without simulated latency.
+    #[must_use]
+    pub fn new() -> SimGPU {
+        SimGPU {
+            arena: Default::default(),
+            latency: None,
+            stats: Default::default(),
+        }
+    }
+
+    /// Adds a simulated latency to every transfer and every kernel launch.
+    #[must_use]
+    pub fn with_latency(mut self, latency: Duration) -> SimGPU {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Returns the transfers and kernel launches counted so far.
+    #[inline]
+    pub fn stats(&self) -> SimStats {
+        self.stats.get()
+    }
+
+    /// Sets all counters back to zero.
+    #[inline]
+    pub fn reset_stats(&self) {
+        self.stats.set(SimStats::default());
+    }
+
+    /// Launches an element-wise kernel.
+    /// For every index `i` of `out`, `kernel` is called with the `i`-th elements of all `inputs`.
+    ///
+    /// This runs "on the device": no transfers are counted.
+    ///
+    /// # Panics
+    /// If an input buffer is shorter than `out`.
+    pub fn launch<T: Copy>(
+        &self,
+        out: &mut Buffer<T, SimGPU>,
+        inputs: &[&Buffer<T, SimGPU>],
+        kernel: impl Fn(&[T]) -> T,
+    ) {
+        self.simulate_latency();
+
+        let len = out.len();
+        let mut arena = self.arena.borrow_mut();
+
+        let inputs = inputs
+            .iter()
+            .map(|input| {
+                assert!(
+                    input.len() >= len,
+                    "An input buffer is shorter than the output buffer."
+                );
+                arena.load::<T>(input.ptr.id, 0, len)
+            })
+            .collect::<Vec<_>>();
+
+        let mut args = Vec::with_capacity(inputs.len());
+        let data = (0..len)
+            .map(|idx| {
+                args.clear();
+                args.extend(inputs.iter().map(|input| input[idx]));
+                kernel(&args)
+            })
+            .collect::<Vec<_>>();
+
+        arena.store(out.ptr.id, 0, &data);
+        self.count(|stats| stats.launches += 1);
+    }
+
+    fn simulate_latency(&self) {
+        if let Some(latency) = self.latency {
+            std::thread::sleep(latency);
+        }
+    }
+
+    fn count(&self, f: impl FnOnce(&mut SimStats)) {
+        let mut stats = self.stats.get();
+        f(&mut stats);
+        self.stats.set(stats);
+    }
+
+    fn upload<T: Copy>(&self, id: usize, data: &[T]) {
+        self.simulate_latency();
+        self.arena.borrow_mut().store(id, 0, data);
+        self.count(|stats| {
+            stats.writes += 1;
+            stats.bytes_written += core::mem::size_of_val(data);
+        });
+    }
+}
+
+impl Device for SimGPU {
+    type Ptr<U, S: Shape> = SimPtr<U>;
+    type Cache = ();
+
+    fn new() -> crate::Result<Self> {
+        Ok(SimGPU::new())
+    }
+}
+
+impl IsShapeIndep for SimGPU {}
+
+impl<T: Copy, S: Shape> Alloc<'_, T, S> for SimGPU {
+    fn alloc(&self, mut len: usize, flag: AllocFlag) -> SimPtr<T> {
+        assert!(len > 0, "invalid buffer len: 0");
+
+        if S::LEN > len {
+            len = S::LEN
+        }
+
+        SimPtr::new(&self.arena, len, flag)
+    }
+
+    fn with_slice(&self, data: &[T]) -> SimPtr<T>
+    where
+        T: Clone,
+    {
+        let ptr = Alloc::<T, S>::alloc(self, data.len(), AllocFlag::None);
+        self.upload(ptr.id, data);
+        ptr
+    }
+}
+
+impl<T: Copy + Default, S: Shape> Read<T, SimGPU, S> for SimGPU {
+    type Read<'a> = Vec<T> where T: 'a, S: 'a;
+
+    #[inline]
+    fn read(&self, buf: &Buffer<T, SimGPU, S>) -> Vec<T> {
+        self.read_to_vec(buf)
+    }
+
+    fn read_to_vec(&self, buf: &Buffer<T, SimGPU, S>) -> Vec<T> {
+        self.simulate_latency();
+        let data = self.arena.borrow().load(buf.ptr.id, 0, buf.len());
+        self.count(|stats| {
+            stats.reads += 1;
+            stats.bytes_read += buf.len() * size_of::<T>();
+        });
+        data
+    }
+}
+
+impl<T: Copy, S: Shape> WriteBuf<T, SimGPU, S> for SimGPU {
+    #[inline]
+    fn write(&self, buf: &mut Buffer<T, SimGPU, S>, data: &[T]) {
+        self.upload(buf.ptr.id, data)
+    }
+
+    /// A device to device copy. It is not counted as a transfer.
+    fn write_buf(&self, dst: &mut Buffer<T, Self, S>, src: &Buffer<T, Self, S>) {
+        let mut arena = self.arena.borrow_mut();
+        let data = arena.load::<T>(src.ptr.id, 0, src.len());
+        arena.store(dst.ptr.id, 0, &data);
+    }
+}
+
+impl<T, S: Shape> ClearBuf<T, SimGPU, S> for SimGPU {
+    /// Sets all bytes to zero, like `clEnqueueFillBuffer` or `cuMemsetD8`.
+    fn clear(&self, buf: &mut Buffer<T, SimGPU, S>) {
+        self.arena.borrow_mut().bytes_mut(buf.ptr.id).fill(0);
+    }
+}
+
+impl<T: Copy> CopySlice<T> for SimGPU {
+    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, SimGPU>,
+        source_range: SR,
+        dest: &mut Buffer<T, SimGPU>,
+        dest_range: DR,
+    ) {
+        let source_range = bounds_to_range(source_range, source.len());
+        let dest_range = bounds_to_range(dest_range, dest.len());
+
+        assert_eq!(
+            source_range.end - source_range.start,
+            dest_range.end - dest_range.start,
+        );
+
+        self.copy_slice_all(source, dest, [(source_range, dest_range)]);
+    }
+
+    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
+        &self,
+        source: &Buffer<T, SimGPU>,
+        dest: &mut Buffer<T, SimGPU>,
+        ranges: I,
+    ) {
+        let mut arena = self.arena.borrow_mut();
+
+        for (source_range, dest_range) in ranges {
+            assert!(source_range.end <= source.len() && dest_range.end <= dest.len());
+            assert_eq!(source_range.len(), dest_range.len());
+
+            let data = arena.load::<T>(source.ptr.id, source_range.start, source_range.len());
+            arena.store(dest.ptr.id, dest_range.start, &data);
+        }
+    }
+}
+
+impl<'a, T: Copy, S: Shape> CloneBuf<'a, T, S> for SimGPU {
+    /// A device to device copy. It is not counted as a transfer.
+    fn clone_buf(&'a self, buf: &Buffer<'a, T, SimGPU, S>) -> Buffer<'a, T, SimGPU, S> {
+        let mut cloned = Buffer::new(self, buf.len());
+        self.write_buf(&mut cloned, buf);
+        cloned
+    }
+}
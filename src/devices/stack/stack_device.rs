@@ -1,8 +1,16 @@
+use alloc::vec::Vec;
+use core::ops::{Range, RangeBounds};
+
 use crate::{
-    flag::AllocFlag, shape::Shape, Alloc, Buffer, CloneBuf, Device, DevicelessAble, MainMemory,
-    Read, StackArray,
+    bounds_to_range, flag::AllocFlag, shape::Shape, Alloc, Buffer, CacheBuf, ClearBuf, CloneBuf,
+    CopySlice, Device, DevicelessAble, MainMemory, Read, StackArray, WriteBuf,
 };
 
+use crate::Graph;
+
+#[cfg(not(feature = "no-std"))]
+use core::cell::RefCell;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Stack;
 
@@ -17,6 +25,22 @@ impl Device for Stack {
     }
 }
 
+#[cfg(not(feature = "no-std"))]
+impl crate::DeviceInfo for Stack {
+    /// The size of a stack allocated buffer is limited by the stack size of the current thread, which is not known.
+    fn info(&self) -> crate::Result<crate::DeviceProperties> {
+        Ok(crate::DeviceProperties {
+            backend: crate::Backend::Stack,
+            name: "Stack".into(),
+            vendor: String::new(),
+            global_mem_size: None,
+            max_alloc_size: None,
+            unified_mem: true,
+            f64_support: true,
+        })
+    }
+}
+
 impl MainMemory for Stack {
     #[inline]
     fn as_ptr<T, S: Shape>(ptr: &Self::Ptr<T, S>) -> *const T {
@@ -61,11 +85,86 @@ impl<'a, S: Shape, T: Copy + Default> Alloc<'a, T, S> for Stack {
     }*/
 }
 
-/*impl GraphReturn for Stack {
-    fn graph(&self) -> core::cell::RefMut<crate::Graph> {
-        unimplemented!()
+#[cfg(not(feature = "no-std"))]
+std::thread_local! {
+    static STACK_GRAPH: RefCell<Graph> = const { RefCell::new(Graph::new()) };
+}
+
+/// Without the standard library, there are no thread locals.
+/// Hence, the graph of the `Stack` is stored in a global, which is guarded by a spin lock.
+#[cfg(feature = "no-std")]
+struct StackGraph {
+    locked: core::sync::atomic::AtomicBool,
+    graph: core::cell::UnsafeCell<Graph>,
+}
+
+// Safety: the graph is only accessed while `locked` is held (see Stack::with_graph).
+#[cfg(feature = "no-std")]
+unsafe impl Sync for StackGraph {}
+
+#[cfg(feature = "no-std")]
+static STACK_GRAPH: StackGraph = StackGraph {
+    locked: core::sync::atomic::AtomicBool::new(false),
+    graph: core::cell::UnsafeCell::new(Graph::new()),
+};
+
+impl Stack {
+    /// Calls `f` with the graph of the `Stack`.
+    ///
+    /// As `Stack` is zero-sized, it can't own a graph and therefore does not implement [`GraphReturn`](crate::GraphReturn).
+    /// With the standard library, every thread uses its own graph.
+    /// Without it, all threads share one graph with a fixed capacity of [`GRAPH_CAPACITY`](crate::GRAPH_CAPACITY) nodes.
+    ///
+    /// # Panics
+    /// If `with_graph` is called inside of `f`.
+    /// # Example
+    /// ```
+    /// use custos::Stack;
+    ///
+    /// let node = Stack.with_graph(|graph| graph.add_node(4, -1, -1));
+    /// assert_eq!(Stack.with_graph(|graph| graph.nodes.last().copied()), Some(node));
+    /// ```
+    #[cfg(not(feature = "no-std"))]
+    #[inline]
+    pub fn with_graph<R>(&self, f: impl FnOnce(&mut Graph) -> R) -> R {
+        STACK_GRAPH.with(|graph| f(&mut graph.borrow_mut()))
     }
-}*/
+
+    /// Calls `f` with the graph of the `Stack`.
+    ///
+    /// As `Stack` is zero-sized, it can't own a graph and therefore does not implement [`GraphReturn`](crate::GraphReturn).
+    /// Without the standard library, all threads share one graph with a fixed capacity of [`GRAPH_CAPACITY`](crate::GRAPH_CAPACITY) nodes.
+    /// Calls from several threads wait for each other.
+    ///
+    /// # Panics
+    /// If `with_graph` is called inside of `f`.
+    #[cfg(feature = "no-std")]
+    pub fn with_graph<R>(&self, f: impl FnOnce(&mut Graph) -> R) -> R {
+        use core::sync::atomic::Ordering;
+
+        // releases the lock, even if `f` panics
+        struct Unlock;
+
+        impl Drop for Unlock {
+            #[inline]
+            fn drop(&mut self) {
+                STACK_GRAPH.locked.store(false, Ordering::Release);
+            }
+        }
+
+        while STACK_GRAPH
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        let _unlock = Unlock;
+
+        // Safety: the lock is held until `_unlock` is dropped.
+        f(unsafe { &mut *STACK_GRAPH.graph.get() })
+    }
+}
 
 impl<T: Copy, S: Shape> Read<T, Stack, S> for Stack
 where
@@ -83,7 +182,6 @@ where
     }
 
     #[inline]
-    #[cfg(not(feature = "no-std"))]
     fn read_to_vec(&self, buf: &Buffer<T, Stack, S>) -> Vec<T>
     where
         T: Default,
@@ -105,10 +203,73 @@ where
     }
 }
 
+impl<T: Default, D: MainMemory, S: Shape> ClearBuf<T, D, S> for Stack {
+    #[inline]
+    fn clear(&self, buf: &mut Buffer<T, D, S>) {
+        for value in buf.iter_mut() {
+            *value = T::default();
+        }
+    }
+}
+
+impl<T: Copy, D: MainMemory, S: Shape> WriteBuf<T, D, S> for Stack {
+    /// Copies `data` to the buffer. `data` must have the length of the shape `S`.
+    #[inline]
+    fn write(&self, buf: &mut Buffer<T, D, S>, data: &[T]) {
+        buf.copy_from_slice(data)
+    }
+
+    #[inline]
+    fn write_buf(&self, dst: &mut Buffer<T, Self, S>, src: &Buffer<T, Self, S>) {
+        dst.copy_from_slice(src)
+    }
+}
+
+impl<T: Copy, D: MainMemory, S: Shape, DS: Shape> CopySlice<T, D, S, DS> for Stack {
+    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, D, S>,
+        source_range: SR,
+        dest: &mut Buffer<T, Self, DS>,
+        dest_range: DR,
+    ) {
+        let source_range = bounds_to_range(source_range, source.len());
+        let dest_range = bounds_to_range(dest_range, dest.len());
+
+        assert_eq!(
+            source_range.end - source_range.start,
+            dest_range.end - dest_range.start,
+        );
+
+        dest[dest_range].copy_from_slice(&source[source_range]);
+    }
+
+    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
+        &self,
+        source: &Buffer<T, D, S>,
+        dest: &mut Buffer<T, Self, DS>,
+        ranges: I,
+    ) {
+        for (source_range, dest_range) in ranges {
+            self.copy_slice_to(source, source_range, dest, dest_range);
+        }
+    }
+}
+
+impl<'a, T: Copy + Default, S: Shape> CacheBuf<'a, T, S> for Stack {
+    /// `Stack` does not cache buffers, this returns a new buffer with the length of the shape `S` (`len` is ignored).
+    #[inline]
+    fn cached(&'a self, _len: usize) -> Buffer<'a, T, Stack, S> {
+        Buffer::new(self, S::LEN)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    #[cfg(not(feature = "no-std"))]
-    use crate::{shape::Dim2, Buffer, Stack};
+    use crate::{
+        shape::{Dim1, Dim2},
+        Buffer, CacheBuf, ClearBuf, CloneBuf, CopySlice, Shape, Stack, WriteBuf,
+    };
 
     #[cfg(not(feature = "no-std"))]
     #[test]
@@ -123,4 +284,91 @@ mod tests {
 
         assert_eq!(buf.read_to_vec(), [3., 2., 1., 4., 7., 1.]);
     }
+
+    /// Resets a buffer of any device that supports the core op traits.
+    fn reset<'a, T, D, S>(device: &'a D, buf: &mut Buffer<'a, T, D, S>, data: &[T])
+    where
+        T: Copy,
+        D: ClearBuf<T, D, S> + WriteBuf<T, D, S> + CloneBuf<'a, T, S> + CopySlice<T, D, S>,
+        S: Shape,
+    {
+        device.clear(buf);
+        device.write(buf, data);
+
+        let cloned = device.clone_buf(buf);
+        device.copy_slice_to(&cloned, 1.., buf, ..data.len() - 1);
+    }
+
+    #[test]
+    fn test_stack_op_traits() {
+        let mut buf = Buffer::<i32, Stack, Dim1<4>>::new(&Stack, 4);
+        reset(&Stack, &mut buf, &[1, 2, 3, 4]);
+        assert_eq!(buf.read(), [2, 3, 4, 4]);
+
+        let mut buf = Buffer::<f32, Stack, Dim2<2, 2>>::new(&Stack, 4);
+        reset(&Stack, &mut buf, &[1., 2., 3., 4.]);
+        assert_eq!(buf.read(), [[2., 3.], [4., 4.]]);
+    }
+
+    #[test]
+    fn test_stack_copy_slice_shapes() {
+        let source = Buffer::<i32, Stack, Dim1<6>>::from((&Stack, [1, 2, 3, 4, 5, 6]));
+
+        let slice: Buffer<i32, Stack, Dim1<2>> = Stack.copy_slice(&source, 2..4);
+        assert_eq!(slice.read(), [3, 4]);
+
+        let mut dest = Buffer::<i32, Stack, Dim1<4>>::new(&Stack, 4);
+        Stack.copy_slice_all(&source, &mut dest, [(0..2, 2..4), (4..6, 0..2)]);
+        assert_eq!(dest.read(), [5, 6, 1, 2]);
+
+        let mut other = Buffer::<i32, Stack, Dim1<4>>::new(&Stack, 4);
+        WriteBuf::<_, Stack, _>::write_buf(&Stack, &mut other, &dest);
+        assert_eq!(other.read(), [5, 6, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stack_copy_slice_wrong_len() {
+        let source = Buffer::<i32, Stack, Dim1<6>>::from((&Stack, [1, 2, 3, 4, 5, 6]));
+        let _slice: Buffer<i32, Stack, Dim1<3>> = Stack.copy_slice(&source, 2..4);
+    }
+
+    #[test]
+    fn test_stack_cached() {
+        let buf: Buffer<f32, Stack, Dim1<8>> = Stack.cached(3);
+        assert_eq!(buf.len(), 8);
+        assert_eq!(buf.read(), [0.; 8]);
+    }
+
+    #[test]
+    fn test_stack_graph() {
+        let node = Stack.with_graph(|graph| graph.add_node(4, -1, -1));
+        assert_eq!(
+            Stack.with_graph(|graph| graph.nodes.last().copied()),
+            Some(node)
+        );
+    }
+
+    #[cfg(feature = "no-std")]
+    #[test]
+    fn test_stack_graph_threads() {
+        let len = Stack.with_graph(|graph| graph.nodes.len());
+
+        let handles = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    for _ in 0..8 {
+                        Stack.with_graph(|graph| graph.add_node(4, -1, -1));
+                    }
+                })
+            })
+            .collect::<std::vec::Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // other tests may add nodes at the same time
+        assert!(Stack.with_graph(|graph| graph.nodes.len()) >= len + 32);
+    }
 }
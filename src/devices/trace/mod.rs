@@ -0,0 +1,242 @@
+use core::{fmt::Write, time::Duration};
+use std::{collections::BTreeMap, time::Instant};
+
+pub use trace_device::*;
+
+mod trace_device;
+
+/// The operations recorded by a [`Traced`] device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TraceOp {
+    Alloc,
+    Read,
+    Write,
+    Clear,
+    CopySlice,
+    CloneBuf,
+    CacheBuf,
+    /// A caller-supplied label spanning several operations, see [`Traced::label`].
+    Label,
+}
+
+impl TraceOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TraceOp::Alloc => "alloc",
+            TraceOp::Read => "read",
+            TraceOp::Write => "write",
+            TraceOp::Clear => "clear",
+            TraceOp::CopySlice => "copy_slice",
+            TraceOp::CloneBuf => "clone_buf",
+            TraceOp::CacheBuf => "cache_buf",
+            TraceOp::Label => "label",
+        }
+    }
+}
+
+/// A single recorded call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent {
+    pub op: TraceOp,
+    /// The innermost label that was active during the call.
+    /// For [`TraceOp::Label`] events, this is the label itself.
+    pub label: Option<String>,
+    /// The start of the call, relative to the creation of the device.
+    pub start: Duration,
+    pub duration: Duration,
+    /// The number of bytes allocated, read, written, ...
+    pub bytes: usize,
+}
+
+impl TraceEvent {
+    /// The name of the event, as shown in a timeline.
+    pub fn name(&self) -> &str {
+        match (self.op, &self.label) {
+            (TraceOp::Label, Some(label)) => label,
+            _ => self.op.as_str(),
+        }
+    }
+}
+
+/// Summarizes all events with the same operation and label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryRow {
+    pub op: TraceOp,
+    pub label: Option<String>,
+    pub calls: usize,
+    pub bytes: usize,
+    pub total: Duration,
+}
+
+impl SummaryRow {
+    #[inline]
+    pub fn mean(&self) -> Duration {
+        self.total / self.calls as u32
+    }
+}
+
+/// Stores the events and the active labels of a [`Traced`] device.
+#[derive(Debug)]
+pub struct TraceLog {
+    pub events: Vec<TraceEvent>,
+    labels: Vec<String>,
+    epoch: Instant,
+}
+
+impl Default for TraceLog {
+    fn default() -> Self {
+        Self {
+            events: Vec::new(),
+            labels: Vec::new(),
+            epoch: Instant::now(),
+        }
+    }
+}
+
+impl TraceLog {
+    #[inline]
+    pub fn current_label(&self) -> Option<&String> {
+        self.labels.last()
+    }
+
+    pub fn push(&mut self, op: TraceOp, start: Instant, bytes: usize) {
+        let event = TraceEvent {
+            op,
+            label: self.current_label().cloned(),
+            start: start - self.epoch,
+            duration: start.elapsed(),
+            bytes,
+        };
+        self.events.push(event);
+    }
+
+    /// Exports the events in the Chrome trace event format.
+    /// The resulting JSON can be loaded into `chrome://tracing` or <https://ui.perfetto.dev>.
+    pub fn to_chrome_trace(&self) -> String {
+        let mut json = String::from("{\"traceEvents\":[");
+
+        for (idx, event) in self.events.iter().enumerate() {
+            if idx > 0 {
+                json.push(',');
+            }
+
+            write!(
+                json,
+                "{{\"name\":\"{}\",\"cat\":\"{}\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":0,\"tid\":0,\"args\":{{\"bytes\":{}",
+                escape_json(event.name()),
+                event.op.as_str(),
+                event.start.as_secs_f64() * 1e6,
+                event.duration.as_secs_f64() * 1e6,
+                event.bytes,
+            )
+            .unwrap();
+
+            if let (Some(label), false) = (&event.label, event.op == TraceOp::Label) {
+                write!(json, ",\"label\":\"{}\"", escape_json(label)).unwrap();
+            }
+            json.push_str("}}");
+        }
+
+        json.push_str("],\"displayTimeUnit\":\"ns\"}");
+        json
+    }
+
+    /// Groups the events by operation and label.
+    pub fn summary(&self) -> Vec<SummaryRow> {
+        let mut rows = BTreeMap::<(TraceOp, Option<&String>), SummaryRow>::new();
+
+        for event in &self.events {
+            let row = rows
+                .entry((event.op, event.label.as_ref()))
+                .or_insert_with(|| SummaryRow {
+                    op: event.op,
+                    label: event.label.clone(),
+                    calls: 0,
+                    bytes: 0,
+                    total: Duration::ZERO,
+                });
+            row.calls += 1;
+            row.bytes += event.bytes;
+            row.total += event.duration;
+        }
+
+        rows.into_values().collect()
+    }
+
+    /// Returns the [`summary`](TraceLog::summary) as a formatted table.
+    pub fn summary_table(&self) -> String {
+        let rows = self.summary();
+        let label_width = rows
+            .iter()
+            .filter_map(|row| row.label.as_ref().map(|label| label.len()))
+            .max()
+            .unwrap_or(0)
+            .max(5);
+
+        let mut table = format!(
+            "{:<10} | {:<label_width$} | {:>6} | {:>12} | {:>12} | {:>12}\n",
+            "op", "label", "calls", "bytes", "total (us)", "mean (us)"
+        );
+        table.push_str(&"-".repeat(table.len() - 1));
+        table.push('\n');
+
+        for row in rows {
+            writeln!(
+                table,
+                "{:<10} | {:<label_width$} | {:>6} | {:>12} | {:>12.3} | {:>12.3}",
+                row.op.as_str(),
+                row.label.as_deref().unwrap_or("-"),
+                row.calls,
+                row.bytes,
+                row.total.as_secs_f64() * 1e6,
+                row.mean().as_secs_f64() * 1e6,
+            )
+            .unwrap();
+        }
+        table
+    }
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_json, TraceLog, TraceOp};
+    use std::time::Instant;
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("matmul"), "matmul");
+        assert_eq!(escape_json("a \"b\"\\\n"), "a \\\"b\\\"\\\\\\n");
+        assert_eq!(escape_json("\u{1}"), "\\u0001");
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut log = TraceLog::default();
+        log.push(TraceOp::Alloc, Instant::now(), 16);
+        log.push(TraceOp::Alloc, Instant::now(), 8);
+        log.push(TraceOp::Read, Instant::now(), 4);
+
+        let summary = log.summary();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].op, TraceOp::Alloc);
+        assert_eq!(summary[0].calls, 2);
+        assert_eq!(summary[0].bytes, 24);
+        assert_eq!(summary[1].op, TraceOp::Read);
+    }
+}
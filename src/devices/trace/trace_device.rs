@@ -0,0 +1,317 @@
+use core::{
+    cell::{Ref, RefCell},
+    mem::{size_of, ManuallyDrop},
+    ops::{Range, RangeBounds},
+};
+use std::time::Instant;
+
+use super::{SummaryRow, TraceEvent, TraceLog, TraceOp};
+use crate::{
+    flag::AllocFlag, shape::Shape, Alloc, Buffer, CacheBuf, ClearBuf, CloneBuf, CopySlice, Device,
+    IsShapeIndep, MainMemory, Read, WriteBuf,
+};
+
+/// A device that wraps another device `D` and records every call of
+/// [`Alloc`], [`Read`], [`WriteBuf`], [`ClearBuf`], [`CopySlice`], [`CloneBuf`] and [`CacheBuf`].
+///
+/// The recorded events can be exported as a Chrome trace ([`Traced::to_chrome_trace`])
+/// or as a summary table ([`Traced::summary_table`]).
+///
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use custos::{Buffer, ClearBuf, trace::TraceOp, Traced, CPU};
+///
+/// let device = Traced::new(CPU::new());
+///
+/// let mut buf = Buffer::from((&device, [1, 2, 3, 4]));
+/// {
+///     let _label = device.label("reset");
+///     device.clear(&mut buf);
+/// }
+/// assert_eq!(buf.read(), [0; 4]);
+///
+/// let ops = device.events().iter().map(|event| event.op).collect::<Vec<_>>();
+/// assert_eq!(ops, [TraceOp::Alloc, TraceOp::Clear, TraceOp::Label, TraceOp::Read]);
+///
+/// println!("{}", device.summary_table());
+/// ```
+#[derive(Debug, Default)]
+pub struct Traced<D> {
+    pub inner: D,
+    log: RefCell<TraceLog>,
+}
+
+impl<D> Traced<D> {
+    /// Wraps `device`. Timestamps of events are relative to this call.
+    #[inline]
+    pub fn new(device: D) -> Traced<D> {
+        Traced {
+            inner: device,
+            log: Default::default(),
+        }
+    }
+
+    /// Attaches `label` to all calls until the returned [`TraceLabel`] is dropped.
+    /// The label itself is recorded as a [`TraceOp::Label`] event spanning these calls.
+    /// Labels can be nested, an event always carries the innermost one.
+    pub fn label(&self, label: impl Into<String>) -> TraceLabel<'_, D> {
+        self.log.borrow_mut().labels.push(label.into());
+        TraceLabel {
+            device: self,
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns all recorded events in order of their completion.
+    #[inline]
+    pub fn events(&self) -> Ref<'_, Vec<TraceEvent>> {
+        Ref::map(self.log.borrow(), |log| &log.events)
+    }
+
+    /// Removes all recorded events.
+    #[inline]
+    pub fn clear_events(&self) {
+        self.log.borrow_mut().events.clear();
+    }
+
+    /// Exports the recorded events in the Chrome trace event format.
+    /// The JSON can be loaded into `chrome://tracing` or <https://ui.perfetto.dev>.
+    #[inline]
+    pub fn to_chrome_trace(&self) -> String {
+        self.log.borrow().to_chrome_trace()
+    }
+
+    /// Writes the [Chrome trace](Traced::to_chrome_trace) to the file at `path`.
+    pub fn write_chrome_trace(&self, path: impl AsRef<std::path::Path>) -> crate::Result<()> {
+        std::fs::write(path, self.to_chrome_trace())?;
+        Ok(())
+    }
+
+    /// Groups the recorded events by operation and label.
+    #[inline]
+    pub fn summary(&self) -> Vec<SummaryRow> {
+        self.log.borrow().summary()
+    }
+
+    /// Returns the [summary](Traced::summary) formatted as a table.
+    #[inline]
+    pub fn summary_table(&self) -> String {
+        self.log.borrow().summary_table()
+    }
+
+    fn record<R>(&self, op: TraceOp, bytes: usize, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let out = f();
+        self.log.borrow_mut().push(op, start, bytes);
+        out
+    }
+}
+
+/// Keeps a label of a [`Traced`] device active. Created by [`Traced::label`].
+#[derive(Debug)]
+#[must_use = "the label is only active until this guard is dropped"]
+pub struct TraceLabel<'a, D> {
+    device: &'a Traced<D>,
+    start: Instant,
+}
+
+impl<D> Drop for TraceLabel<'_, D> {
+    fn drop(&mut self) {
+        let mut log = self.device.log.borrow_mut();
+        log.push(TraceOp::Label, self.start, 0);
+        log.labels.pop();
+    }
+}
+
+impl<D: Device> Traced<D> {
+    /// Returns a view of `buf` as a buffer of the inner device.
+    /// The view does not own the allocation, hence it must not be dropped.
+    #[inline]
+    fn view<'a, T, S: Shape>(
+        &'a self,
+        buf: &Buffer<'_, T, Traced<D>, S>,
+    ) -> ManuallyDrop<Buffer<'a, T, D, S>> {
+        ManuallyDrop::new(Buffer {
+            // safety: the view is never dropped; hence, the pointer is not freed twice
+            ptr: unsafe { core::ptr::read(&buf.ptr) },
+            device: Some(&self.inner),
+            node: buf.node,
+        })
+    }
+
+    /// Moves a buffer of the inner device into a buffer of this device.
+    #[inline]
+    fn wrap<'a, T, S: Shape>(&'a self, buf: Buffer<'a, T, D, S>) -> Buffer<'a, T, Traced<D>, S> {
+        let buf = ManuallyDrop::new(buf);
+        Buffer {
+            // safety: buf is not dropped, therefore the ownership of the pointer is moved
+            ptr: unsafe { core::ptr::read(&buf.ptr) },
+            device: Some(self),
+            node: buf.node,
+        }
+    }
+}
+
+impl<D: Device> Device for Traced<D> {
+    type Ptr<U, S: Shape> = D::Ptr<U, S>;
+    type Cache = ();
+
+    #[inline]
+    fn new() -> crate::Result<Self> {
+        Ok(Traced::new(D::new()?))
+    }
+}
+
+impl<D: IsShapeIndep> IsShapeIndep for Traced<D> {}
+
+impl<'a, T, D: Alloc<'a, T, S>, S: Shape> Alloc<'a, T, S> for Traced<D> {
+    #[inline]
+    fn alloc(&'a self, len: usize, flag: AllocFlag) -> D::Ptr<T, S> {
+        self.record(TraceOp::Alloc, len * size_of::<T>(), || {
+            self.inner.alloc(len, flag)
+        })
+    }
+
+    #[inline]
+    fn with_slice(&'a self, data: &[T]) -> D::Ptr<T, S>
+    where
+        T: Clone,
+    {
+        self.record(TraceOp::Alloc, core::mem::size_of_val(data), || {
+            self.inner.with_slice(data)
+        })
+    }
+
+    #[inline]
+    fn alloc_with_vec(&'a self, vec: Vec<T>) -> D::Ptr<T, S>
+    where
+        T: Clone,
+    {
+        self.record(TraceOp::Alloc, vec.len() * size_of::<T>(), || {
+            self.inner.alloc_with_vec(vec)
+        })
+    }
+
+    #[inline]
+    fn with_array(&'a self, array: S::ARR<T>) -> D::Ptr<T, S>
+    where
+        T: Clone,
+    {
+        self.record(TraceOp::Alloc, S::LEN * size_of::<T>(), || {
+            self.inner.with_array(array)
+        })
+    }
+}
+
+impl<D: MainMemory> MainMemory for Traced<D> {
+    #[inline]
+    fn as_ptr<T, S: Shape>(ptr: &Self::Ptr<T, S>) -> *const T {
+        D::as_ptr(ptr)
+    }
+
+    #[inline]
+    fn as_ptr_mut<T, S: Shape>(ptr: &mut Self::Ptr<T, S>) -> *mut T {
+        D::as_ptr_mut(ptr)
+    }
+}
+
+/// As the read result of the inner device may borrow the buffer of the inner device,
+/// the data is always read into a `Vec`.
+impl<T: Default + Clone, D: Read<T, D, S>, S: Shape> Read<T, Traced<D>, S> for Traced<D> {
+    type Read<'a> = Vec<T> where T: 'a, D: 'a, S: 'a;
+
+    #[inline]
+    fn read<'a>(&self, buf: &'a Buffer<T, Traced<D>, S>) -> Self::Read<'a> {
+        self.read_to_vec(buf)
+    }
+
+    fn read_to_vec(&self, buf: &Buffer<T, Traced<D>, S>) -> Vec<T>
+    where
+        T: Default + Clone,
+    {
+        self.record(TraceOp::Read, buf.len() * size_of::<T>(), || {
+            self.inner.read_to_vec(&self.view(buf))
+        })
+    }
+}
+
+impl<T, D: WriteBuf<T, D, S>, S: Shape> WriteBuf<T, Traced<D>, S> for Traced<D> {
+    fn write(&self, buf: &mut Buffer<T, Traced<D>, S>, data: &[T]) {
+        self.record(TraceOp::Write, core::mem::size_of_val(data), || {
+            self.inner.write(&mut self.view(buf), data)
+        })
+    }
+
+    fn write_buf(&self, dst: &mut Buffer<T, Self, S>, src: &Buffer<T, Self, S>) {
+        self.record(TraceOp::Write, src.len() * size_of::<T>(), || {
+            self.inner.write_buf(&mut self.view(dst), &self.view(src))
+        })
+    }
+}
+
+impl<T, D: ClearBuf<T, D, S>, S: Shape> ClearBuf<T, Traced<D>, S> for Traced<D> {
+    fn clear(&self, buf: &mut Buffer<T, Traced<D>, S>) {
+        self.record(TraceOp::Clear, buf.len() * size_of::<T>(), || {
+            self.inner.clear(&mut self.view(buf))
+        })
+    }
+}
+
+impl<T, D: CopySlice<T, D>> CopySlice<T, Traced<D>> for Traced<D> {
+    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, Traced<D>>,
+        source_range: SR,
+        dest: &mut Buffer<T, Self>,
+        dest_range: DR,
+    ) {
+        let source_range = crate::bounds_to_range(source_range, source.len());
+        let bytes = (source_range.end - source_range.start) * size_of::<T>();
+
+        self.record(TraceOp::CopySlice, bytes, || {
+            self.inner.copy_slice_to(
+                &self.view(source),
+                source_range,
+                &mut self.view(dest),
+                dest_range,
+            )
+        })
+    }
+
+    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
+        &self,
+        source: &Buffer<T, Traced<D>>,
+        dest: &mut Buffer<T, Self>,
+        ranges: I,
+    ) {
+        let ranges = ranges.into_iter().collect::<Vec<_>>();
+        let bytes = ranges
+            .iter()
+            .map(|(source_range, _)| source_range.len() * size_of::<T>())
+            .sum();
+
+        self.record(TraceOp::CopySlice, bytes, || {
+            self.inner
+                .copy_slice_all(&self.view(source), &mut self.view(dest), ranges)
+        })
+    }
+}
+
+impl<'a, T, D: CloneBuf<'a, T, S>, S: Shape> CloneBuf<'a, T, S> for Traced<D> {
+    fn clone_buf(&'a self, buf: &Buffer<'a, T, Traced<D>, S>) -> Buffer<'a, T, Traced<D>, S> {
+        let cloned = self.record(TraceOp::CloneBuf, buf.len() * size_of::<T>(), || {
+            self.inner.clone_buf(&self.view(buf))
+        });
+        self.wrap(cloned)
+    }
+}
+
+impl<'a, T, D: CacheBuf<'a, T, S>, S: Shape> CacheBuf<'a, T, S> for Traced<D> {
+    fn cached(&'a self, len: usize) -> Buffer<'a, T, Traced<D>, S> {
+        let cached = self.record(TraceOp::CacheBuf, len * size_of::<T>(), || {
+            self.inner.cached(len)
+        });
+        self.wrap(cached)
+    }
+}
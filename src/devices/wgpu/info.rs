@@ -0,0 +1,103 @@
+use wgpu::{Adapter, AdapterInfo, Backends, DeviceType};
+
+use super::WGPU;
+use crate::{Backend, DeviceInfo, DeviceProperties};
+
+/// A WGPU adapter found by [`wgpu_adapters`].
+#[derive(Debug)]
+pub struct WGPUAdapterEntry {
+    /// The index of the adapter in the list of all adapters of the requested backends.
+    pub idx: usize,
+    pub info: AdapterInfo,
+    pub props: DeviceProperties,
+    /// Can be used to create a [`WGPU`] device via [`WGPU::from_adapter`].
+    pub adapter: Adapter,
+}
+
+/// Maps the PCI vendor id of an adapter to a name.
+fn vendor_name(vendor: usize) -> String {
+    match vendor {
+        0x1002 => "AMD".into(),
+        0x10DE => "NVIDIA".into(),
+        0x8086 => "Intel".into(),
+        0x13B5 => "ARM".into(),
+        0x5143 => "Qualcomm".into(),
+        0x106B => "Apple".into(),
+        0x10005 => "Mesa".into(),
+        vendor => format!("{vendor:#x}"),
+    }
+}
+
+/// Returns the properties of a WGPU adapter.
+pub fn adapter_properties(adapter: &Adapter) -> DeviceProperties {
+    let info = adapter.get_info();
+
+    DeviceProperties {
+        backend: Backend::WGPU,
+        name: info.name,
+        vendor: vendor_name(info.vendor),
+        // wgpu does not expose the size of the device memory
+        global_mem_size: None,
+        max_alloc_size: Some(adapter.limits().max_buffer_size),
+        unified_mem: matches!(
+            info.device_type,
+            DeviceType::IntegratedGpu | DeviceType::Cpu
+        ),
+        // shaders are written in WGSL, which does not support f64
+        f64_support: false,
+    }
+}
+
+/// Lists all adapters of the given backends.
+/// # Example
+/// ```
+/// use custos::{wgpu::wgpu_adapters, WGPU};
+///
+/// fn main() -> custos::Result<()> {
+///     for entry in wgpu_adapters(wgpu::Backends::all()) {
+///         println!("{:?}: {:?}", entry.info.backend, entry.props);
+///     }
+///
+///     // there is no adapter on this machine
+///     let Some(entry) = wgpu_adapters(wgpu::Backends::all()).into_iter().next() else {
+///         return Ok(());
+///     };
+///     let device = WGPU::from_adapter(entry.adapter)?;
+///     Ok(())
+/// }
+/// ```
+pub fn wgpu_adapters(backends: Backends) -> Vec<WGPUAdapterEntry> {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends,
+        dx12_shader_compiler: Default::default(),
+    });
+
+    instance
+        .enumerate_adapters(backends)
+        .enumerate()
+        .map(|(idx, adapter)| WGPUAdapterEntry {
+            idx,
+            info: adapter.get_info(),
+            props: adapter_properties(&adapter),
+            adapter,
+        })
+        .collect()
+}
+
+impl DeviceInfo for WGPU {
+    #[inline]
+    fn info(&self) -> crate::Result<DeviceProperties> {
+        Ok(adapter_properties(&self.adapter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vendor_name;
+
+    #[test]
+    fn test_vendor_name() {
+        assert_eq!(vendor_name(0x10DE), "NVIDIA");
+        assert_eq!(vendor_name(0x1234), "0x1234");
+    }
+}
@@ -26,6 +26,12 @@ impl<'a, T, S: Shape> AsBindingResource for &mut Buffer<'a, T, WGPU, S> {
     }
 }
 
+impl AsBindingResource for &wgpu::Buffer {
+    fn as_binding_resource(&self) -> BindingResource {
+        self.as_entire_binding()
+    }
+}
+
 /// Launches a `WGPU` compute shader.
 ///
 /// # Example
@@ -1,3 +1,4 @@
+mod info;
 mod launch_shader;
 mod shader_cache;
 mod wgpu_buffer;
@@ -5,10 +6,58 @@ pub mod wgpu_device;
 
 use core::fmt::Debug;
 
+pub use info::*;
 pub use launch_shader::*;
 pub use wgpu_device::*;
 
-use crate::{Buffer, Shape};
+use crate::{Buffer, DeviceError, Shape};
+use wgpu::Backends;
+
+/// The backends, which are searched for an adapter by [`WGPU`]'s `Device::new`.
+/// The environment variable `CUSTOS_WGPU_BACKEND` contains a comma separated list of backends,
+/// e.g. `vulkan,gl`. Valid backends are `vulkan`, `metal`, `dx12`, `dx11`, `gl`, `webgpu`, `primary`, `secondary` and `all` (the default).
+/// # Errors
+/// [`DeviceError::InvalidWGPUBackend`], if the list contains an unknown backend.
+pub fn chosen_wgpu_backends() -> crate::Result<Backends> {
+    match std::env::var("CUSTOS_WGPU_BACKEND") {
+        Ok(value) => Ok(parse_backends(&value).ok_or(DeviceError::InvalidWGPUBackend)?),
+        Err(_) => Ok(Backends::all()),
+    }
+}
+
+/// The index of the adapter in [`wgpu_adapters`], which is used by [`WGPU`]'s `Device::new`.
+/// If the environment variable `CUSTOS_WGPU_ADAPTER_IDX` is not set, wgpu chooses the adapter.
+/// # Errors
+/// [`DeviceError::InvalidWGPUAdapterIdxEnv`], if the value is not an index.
+pub fn chosen_wgpu_adapter_idx() -> crate::Result<Option<usize>> {
+    let Ok(idx) = std::env::var("CUSTOS_WGPU_ADAPTER_IDX") else {
+        return Ok(None);
+    };
+    let idx = idx
+        .parse()
+        .map_err(|_| DeviceError::InvalidWGPUAdapterIdxEnv)?;
+    Ok(Some(idx))
+}
+
+/// Parses a comma separated list of backends. Returns `None`, if the list contains an unknown backend.
+fn parse_backends(value: &str) -> Option<Backends> {
+    let mut backends = Backends::empty();
+    for backend in value.split(',') {
+        backends |= match backend.trim().to_ascii_lowercase().as_str() {
+            "vulkan" | "vk" => Backends::VULKAN,
+            "metal" | "mtl" => Backends::METAL,
+            "dx12" | "d3d12" => Backends::DX12,
+            "dx11" | "d3d11" => Backends::DX11,
+            "gl" | "gles" | "opengl" => Backends::GL,
+            "webgpu" | "browser" => Backends::BROWSER_WEBGPU,
+            "primary" => Backends::PRIMARY,
+            "secondary" => Backends::SECONDARY,
+            "all" => Backends::all(),
+            _ => return None,
+        };
+    }
+    Some(backends)
+}
 
 /// Sets all the elements of a `WGPU` `Buffer` to zero / default.
 ///
@@ -46,7 +95,22 @@ pub fn wgpu_clear<T: Default + Debug, S: Shape>(device: &WGPU, buf: &mut Buffer<
 
 #[cfg(test)]
 mod tests {
-    use crate::{Buffer, WGPU};
+    use wgpu::Backends;
+
+    use super::parse_backends;
+    use crate::{Buffer, CacheBuf, CopySlice, Dim1, WriteBuf, WGPU};
+
+    #[test]
+    fn test_parse_backends() {
+        assert_eq!(parse_backends("vulkan"), Some(Backends::VULKAN));
+        assert_eq!(
+            parse_backends("Vulkan, gl"),
+            Some(Backends::VULKAN | Backends::GL)
+        );
+        assert_eq!(parse_backends("all"), Some(Backends::all()));
+        assert_eq!(parse_backends("vulkan,cuda"), None);
+        assert_eq!(parse_backends(""), None);
+    }
 
     #[test]
     fn test_wgpu_clear() -> crate::Result<()> {
@@ -59,4 +123,125 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_wgpu_write_unaligned() -> crate::Result<()> {
+        let device = WGPU::new(wgpu::Backends::all())?;
+
+        let mut buf = Buffer::<u8, _>::new(&device, 3);
+        buf.write(&[1, 2, 3]);
+        assert_eq!(buf.read(), [1, 2, 3]);
+
+        let buf = Buffer::from((&device, [-1i16, 2, -3]));
+        assert_eq!(buf.read(), [-1, 2, -3]);
+
+        let cloned = buf.clone();
+        assert_eq!(cloned.read(), [-1, 2, -3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wgpu_write() -> crate::Result<()> {
+        let device = WGPU::new(wgpu::Backends::all())?;
+
+        let mut buf = Buffer::<i32, _>::new(&device, 5);
+        buf.write(&[3, 1, -4, 1, 5]);
+        assert_eq!(buf.read(), [3, 1, -4, 1, 5]);
+
+        let mut dst = Buffer::new(&device, 5);
+        device.write_buf(&mut dst, &buf);
+        assert_eq!(dst.read(), [3, 1, -4, 1, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wgpu_copy_slice() -> crate::Result<()> {
+        let device = WGPU::new(wgpu::Backends::all())?;
+
+        let source = Buffer::from((&device, [1., 2., 6., 2., 4.]));
+        let mut dest = Buffer::new(&device, 10);
+        device.copy_slice_all(&source, &mut dest, [(2..5, 7..10), (1..3, 3..5)]);
+        assert_eq!(
+            dest.read(),
+            [0.0, 0.0, 0.0, 2.0, 6.0, 0.0, 0.0, 6.0, 2.0, 4.0]
+        );
+
+        let slice = device.copy_slice(&source, 1..4);
+        assert_eq!(slice.read(), [2., 6., 2.]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wgpu_clone_shaped() -> crate::Result<()> {
+        let device = WGPU::new(wgpu::Backends::all())?;
+
+        let mut buf = Buffer::<f32, _, Dim1<3>>::new(&device, 3);
+        buf.write(&[1., 2., 3.]);
+
+        let cloned = buf.clone();
+        drop(buf);
+
+        assert_eq!(cloned.read(), [1., 2., 3.]);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "realloc"))]
+    #[test]
+    fn test_wgpu_cached() -> crate::Result<()> {
+        let device = WGPU::new(wgpu::Backends::all())?;
+
+        crate::set_count(0);
+        let mut buf = CacheBuf::<f32>::cached(&device, 4);
+        buf.write(&[1., 2., 3., 4.]);
+        let ptr = buf.ptr.ptr;
+
+        // the buffer is returned by the cache again
+        crate::set_count(0);
+        let buf = CacheBuf::<f32>::cached(&device, 4);
+        assert_eq!(buf.ptr.ptr, ptr);
+        assert_eq!(buf.read(), [1., 2., 3., 4.]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wgpu_copy_slice_unaligned() -> crate::Result<()> {
+        let device = WGPU::new(wgpu::Backends::all())?;
+
+        let source = Buffer::from((&device, [1u8, 2, 3, 4, 5, 6, 7, 8, 9]));
+        let mut dest = Buffer::from((&device, [0u8; 9]));
+
+        device.copy_slice_to(&source, 1..5, &mut dest, 0..4);
+        assert_eq!(dest.read(), [2, 3, 4, 5, 0, 0, 0, 0, 0]);
+
+        // the bytes next to the range are kept
+        device.copy_slice_to(&source, 0..3, &mut dest, 5..8);
+        assert_eq!(dest.read(), [2, 3, 4, 5, 0, 1, 2, 3, 0]);
+
+        let slice = device.copy_slice(&source, 6..9);
+        assert_eq!(slice.read(), [7, 8, 9]);
+
+        let source = Buffer::from((&device, [1i16, -2, 3, -4, 5]));
+        let mut dest = Buffer::<i16, _>::new(&device, 5);
+        device.copy_slice_all(&source, &mut dest, [(0..3, 1..4), (4..5, 0..1)]);
+        assert_eq!(dest.read(), [5, 1, -2, 3, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wgpu_copy_slice_unaligned_large() -> crate::Result<()> {
+        let device = WGPU::new(wgpu::Backends::all())?;
+
+        // more words than the workgroups of one dimension can cover
+        let len = 65535 * 64 * 4 + 11;
+        let data = (0..len).map(|idx| idx as u8).collect::<Vec<_>>();
+
+        let source = Buffer::from((&device, data.clone()));
+        let mut dest = Buffer::<u8, _>::new(&device, len);
+        device.copy_slice_to(&source, 0..len - 1, &mut dest, 1..len);
+
+        let read = dest.read();
+        assert_eq!(read[0], 0);
+        assert_eq!(&read[1..], &data[..len - 1]);
+        Ok(())
+    }
 }
@@ -6,15 +6,23 @@ pub struct WGPUBuffer<T> {
     pub _p: PhantomData<T>,
 }
 
+/// Rounds `bytes` up to a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`].
+#[inline]
+pub fn align_copy_size(bytes: u64) -> u64 {
+    let align = wgpu::COPY_BUFFER_ALIGNMENT;
+    (bytes + align - 1) / align * align
+}
+
 impl<T> WGPUBuffer<T> {
+    /// Creates a buffer for `size` elements.
+    /// The size in bytes is rounded up to a multiple of 4 bytes, the size of the unit in which wgpu copies buffers.
     pub fn new(device: &wgpu::Device, size: u64) -> Self {
         let buf = Box::new(device.create_buffer(&wgpu::BufferDescriptor {
             label: None,
-            size: size * size_of::<T>() as u64,
+            size: align_copy_size(size * size_of::<T>() as u64),
             usage: wgpu::BufferUsages::STORAGE
                 | wgpu::BufferUsages::COPY_DST
-                | wgpu::BufferUsages::COPY_SRC
-                | wgpu::BufferUsages::MAP_READ,
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         }));
         Self {
@@ -23,6 +31,7 @@ impl<T> WGPUBuffer<T> {
         }
     }
 
+    /// Creates a buffer with the contents of `slice`, which is padded to a multiple of 4 bytes as well.
     pub fn with_slice(device: &wgpu::Device, slice: &[T]) -> Self {
         let buf = Box::new(
             device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -30,8 +39,7 @@ impl<T> WGPUBuffer<T> {
                 contents: slice_u8_cast(slice),
                 usage: wgpu::BufferUsages::STORAGE
                     | wgpu::BufferUsages::COPY_DST
-                    | wgpu::BufferUsages::COPY_SRC
-                    | wgpu::BufferUsages::MAP_READ,
+                    | wgpu::BufferUsages::COPY_SRC,
             }),
         );
         Self {
@@ -1,12 +1,22 @@
-use core::{cell::RefCell, fmt::Debug};
+use core::{
+    cell::RefCell,
+    fmt::Debug,
+    mem::size_of,
+    ops::{Range, RangeBounds},
+    ptr::null_mut,
+};
 
-use super::{shader_cache::ShaderCache, wgpu_buffer::*, wgpu_clear};
+use super::{
+    chosen_wgpu_adapter_idx, chosen_wgpu_backends, launch_shader, shader_cache::ShaderCache,
+    wgpu_adapters, wgpu_buffer::*, wgpu_clear,
+};
 
 use crate::{
-    flag::AllocFlag, Alloc, Cache, CacheReturn, ClearBuf, Device, DeviceError, Graph, GraphReturn,
-    Node, PtrType, RawConv, Read, Shape,
+    flag::AllocFlag, op_traits::bounds_to_range, Alloc, Buffer, Cache, CacheBuf, CacheReturn,
+    CachedLeaf, ClearBuf, CloneBuf, CopySlice, Device, DeviceError, Graph, GraphReturn, Node,
+    PtrType, RawConv, Read, ShallowCopy, Shape, WriteBuf,
 };
-use wgpu::{Adapter, Backends, Queue};
+use wgpu::{util::DeviceExt, Adapter, Backends, Queue};
 
 pub struct WGPU {
     pub adapter: Adapter,
@@ -18,6 +28,8 @@ pub struct WGPU {
 }
 
 impl WGPU {
+    /// Creates a device with the adapter chosen by wgpu out of the given backends.
+    /// If there is no hardware adapter, a fallback adapter for software rendering is used, e.g. llvmpipe or lavapipe.
     pub fn new(backends: Backends) -> crate::Result<WGPU> {
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
             backends,
@@ -26,12 +38,34 @@ impl WGPU {
 
         let adapter =
             pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+                .or_else(|| {
+                    pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+                        force_fallback_adapter: true,
+                        ..Default::default()
+                    }))
+                })
                 .ok_or(DeviceError::WGPUDeviceReturn)?;
 
+        WGPU::from_adapter(adapter)
+    }
+
+    /// Creates a device with the adapter at index `idx` in the list of [`wgpu_adapters`] of the given backends.
+    /// # Errors
+    /// [`DeviceError::InvalidWGPUAdapterIdx`]: there is no adapter at the index
+    pub fn with_adapter_idx(backends: Backends, idx: usize) -> crate::Result<WGPU> {
+        let entry = wgpu_adapters(backends)
+            .into_iter()
+            .nth(idx)
+            .ok_or(DeviceError::InvalidWGPUAdapterIdx)?;
+        WGPU::from_adapter(entry.adapter)
+    }
+
+    /// Creates a device from an adapter, e.g. one returned by [`wgpu_adapters`](super::wgpu_adapters).
+    pub fn from_adapter(adapter: Adapter) -> crate::Result<WGPU> {
         let (device, queue) = pollster::block_on(adapter.request_device(
             &wgpu::DeviceDescriptor {
                 label: None,
-                features: wgpu::Features::MAPPABLE_PRIMARY_BUFFERS,
+                features: wgpu::Features::empty(),
                 limits: wgpu::Limits::downlevel_defaults(),
             },
             None,
@@ -46,6 +80,126 @@ impl WGPU {
             cache: Default::default(),
         })
     }
+
+    /// Copies `size` bytes from `source` at `source_offset` to `dest` at `dest_offset`.
+    /// The copy is executed in order with all other commands of the queue, hence it is not waited for.
+    ///
+    /// wgpu copies offsets and sizes in multiples of [`wgpu::COPY_BUFFER_ALIGNMENT`] (4 bytes).
+    /// Other copies are executed by a shader, which keeps the bytes of `dest` next to the range.
+    fn copy_buffer(
+        &self,
+        source: &wgpu::Buffer,
+        source_offset: u64,
+        dest: &wgpu::Buffer,
+        dest_offset: u64,
+        size: u64,
+    ) {
+        if size == 0 {
+            return;
+        }
+
+        if [source_offset, dest_offset, size]
+            .iter()
+            .any(|bytes| bytes % wgpu::COPY_BUFFER_ALIGNMENT != 0)
+        {
+            return self.copy_bytes(source, source_offset, dest, dest_offset, size);
+        }
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_buffer_to_buffer(source, source_offset, dest, dest_offset, size);
+        self.queue.submit(Some(encoder.finish()));
+    }
+
+    /// Copies bytes at unaligned offsets with a shader. Every invocation writes one 4 byte word of `dest`.
+    fn copy_bytes(
+        &self,
+        source: &wgpu::Buffer,
+        source_offset: u64,
+        dest: &wgpu::Buffer,
+        dest_offset: u64,
+        size: u64,
+    ) {
+        const WORKGROUP_SIZE: u64 = 64;
+        // the maximum number of workgroups per dimension
+        const MAX_GROUPS: u64 = 65535;
+
+        let first_word = dest_offset / 4;
+        let words = align_copy_size(dest_offset + size) / 4 - first_word;
+
+        let params = [source_offset, dest_offset, size, first_word, words].map(|value| {
+            u32::try_from(value).expect("wgpu copies unaligned bytes only in the first 4 GiB")
+        });
+        let params = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: slice_u8_cast(&params),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+
+        let groups = (words + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+        let gws = [
+            groups.min(MAX_GROUPS) as u32,
+            ((groups + MAX_GROUPS - 1) / MAX_GROUPS) as u32,
+            1,
+        ];
+
+        launch_shader(self, COPY_BYTES, gws, &[source, dest, &params]);
+    }
+}
+
+/// Copies `params[2]` bytes from `source` at byte `params[0]` to `dest` at byte `params[1]`.
+/// `params[3]` is the first and `params[4]` the number of words of `dest`, which contain a copied byte.
+const COPY_BYTES: &str = "
+    @group(0)
+    @binding(0)
+    var<storage, read> source: array<u32>;
+
+    @group(0)
+    @binding(1)
+    var<storage, read_write> dest: array<u32>;
+
+    @group(0)
+    @binding(2)
+    var<storage, read> params: array<u32>;
+
+    @compute
+    @workgroup_size(64)
+    fn main(
+        @builtin(global_invocation_id) global_id: vec3<u32>,
+        @builtin(num_workgroups) groups: vec3<u32>,
+    ) {
+        let idx = global_id.y * groups.x * 64u + global_id.x;
+        if (idx >= params[4]) {
+            return;
+        }
+
+        let word = params[3] + idx;
+        var value = dest[word];
+
+        for (var byte = 0u; byte < 4u; byte = byte + 1u) {
+            let dest_byte = word * 4u + byte;
+            if (dest_byte >= params[1] && dest_byte < params[1] + params[2]) {
+                let source_byte = dest_byte - params[1] + params[0];
+                let copied = (source[source_byte / 4u] >> ((source_byte % 4u) * 8u)) & 0xFFu;
+                value = (value & ~(0xFFu << (byte * 8u))) | (copied << (byte * 8u));
+            }
+        }
+
+        dest[word] = value;
+    }
+";
+
+/// Returns the offset and the size in bytes of a range of elements.
+#[inline]
+fn byte_range<T>(range: &Range<usize>) -> (u64, u64) {
+    let size = size_of::<T>() as u64;
+    (
+        range.start as u64 * size,
+        (range.end - range.start) as u64 * size,
+    )
 }
 
 impl GraphReturn for WGPU {
@@ -58,8 +212,15 @@ impl Device for WGPU {
     type Ptr<U, S: Shape> = WGPUBufPtr<U>;
     type Cache = Cache<WGPU>;
 
+    /// Creates a device with the backends in `CUSTOS_WGPU_BACKEND` and the adapter at the index in `CUSTOS_WGPU_ADAPTER_IDX`
+    /// (see [`chosen_wgpu_backends`] and [`chosen_wgpu_adapter_idx`]).
     fn new() -> crate::Result<Self> {
-        unimplemented!()
+        let backends = chosen_wgpu_backends()?;
+
+        match chosen_wgpu_adapter_idx()? {
+            Some(idx) => WGPU::with_adapter_idx(backends, idx),
+            None => WGPU::new(backends),
+        }
     }
 }
 
@@ -86,18 +247,40 @@ impl<T, S: Shape> Alloc<'_, T, S> for WGPU {
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
 pub struct WGPUBufPtr<T> {
     pub ptr: *mut WGPUBuffer<T>,
     pub len: usize,
     pub flag: AllocFlag,
 }
 
+impl<T> Default for WGPUBufPtr<T> {
+    fn default() -> Self {
+        Self {
+            ptr: null_mut(),
+            len: 0,
+            flag: AllocFlag::default(),
+        }
+    }
+}
+
 impl<T> WGPUBufPtr<T> {
     pub unsafe fn buf(&self) -> &wgpu::Buffer {
         &*(*self.ptr).buf
     }
 }
 
+impl<T> ShallowCopy for WGPUBufPtr<T> {
+    #[inline]
+    unsafe fn shallow(&self) -> Self {
+        WGPUBufPtr {
+            ptr: self.ptr,
+            len: self.len,
+            flag: AllocFlag::Wrapper,
+        }
+    }
+}
+
 impl<T> PtrType for WGPUBufPtr<T> {
     #[inline]
     fn len(&self) -> usize {
@@ -116,6 +299,9 @@ impl<T> Drop for WGPUBufPtr<T> {
             return;
         }
 
+        if self.ptr.is_null() {
+            return;
+        }
         unsafe { drop(Box::from_raw(self.ptr)) }
     }
 }
@@ -195,25 +381,35 @@ impl<T: Default + Debug, S: Shape> ClearBuf<T, Self, S> for WGPU {
     }
 }
 
-impl<T: Default + Clone> Read<T, Self> for WGPU {
+impl<T: Default + Clone, S: Shape> Read<T, Self, S> for WGPU {
     type Read<'a> = Vec<T>
     where
         T: 'a,
-        Self: 'a;
+        Self: 'a,
+        S: 'a;
 
     #[inline]
-    fn read<'a>(&self, buf: &'a crate::Buffer<T, Self>) -> Self::Read<'a> {
+    fn read<'a>(&self, buf: &'a Buffer<T, Self, S>) -> Self::Read<'a> {
         self.read_to_vec(buf)
     }
 
-    fn read_to_vec(&self, buf: &crate::Buffer<T, Self>) -> Vec<T>
+    fn read_to_vec(&self, buf: &Buffer<T, Self, S>) -> Vec<T>
     where
         T: Default + Clone,
     {
-        self.queue.submit(None);
-
-        let buf = unsafe { buf.ptr.buf() };
+        // storage buffers are not mappable on every adapter, hence the data is copied into a staging buffer
+        // the padding of the buffer is copied as well, as wgpu copies in multiples of 4 bytes
+        let bytes = buf.len() * size_of::<T>();
+        let size = align_copy_size(bytes as u64);
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.copy_buffer(unsafe { buf.ptr.buf() }, 0, &staging, 0, size);
 
+        let buf = &staging;
         let buf_slice = buf.slice(..);
 
         let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
@@ -226,9 +422,139 @@ impl<T: Default + Clone> Read<T, Self> for WGPU {
         };
 
         let data = buf_slice.get_mapped_range();
-        let read = slice_gen_cast::<T>(&data).to_vec();
+        let read = slice_gen_cast::<T>(&data[..bytes]).to_vec();
         drop(data);
         buf.unmap();
         read
     }
 }
+
+impl<T, S: Shape> WriteBuf<T, Self, S> for WGPU {
+    /// Writes `data` to the buffer. The write is executed before any command, which is submitted afterwards.
+    /// # Example
+    /// ```
+    /// use custos::{WGPU, Buffer, WriteBuf};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = WGPU::new(wgpu::Backends::all())?;
+    ///     let mut buf = Buffer::<f32, _>::new(&device, 4);
+    ///     device.write(&mut buf, &[9., 3., 2., -4.]);
+    ///
+    ///     assert_eq!(buf.read(), [9., 3., 2., -4.]);
+    ///     Ok(())
+    /// }
+    /// ```
+    /// # Panics
+    /// If the lengths of the buffer and `data` differ.
+    fn write(&self, buf: &mut Buffer<T, Self, S>, data: &[T]) {
+        assert_eq!(
+            buf.len(),
+            data.len(),
+            "the lengths of the buffer and data differ"
+        );
+
+        let data = slice_u8_cast(data);
+        let size = align_copy_size(data.len() as u64) as usize;
+
+        // wgpu writes in multiples of 4 bytes, hence the data is padded up to the padded size of the buffer
+        if size != data.len() {
+            let mut padded = data.to_vec();
+            padded.resize(size, 0);
+            self.queue
+                .write_buffer(unsafe { buf.ptr.buf() }, 0, &padded);
+            return;
+        }
+
+        self.queue.write_buffer(unsafe { buf.ptr.buf() }, 0, data);
+    }
+
+    /// Copies the contents of `src` to `dst` on the device.
+    /// # Panics
+    /// If the buffers have different lengths.
+    fn write_buf(&self, dst: &mut Buffer<T, Self, S>, src: &Buffer<T, Self, S>) {
+        assert_eq!(
+            dst.len(),
+            src.len(),
+            "the buffers must have the same length"
+        );
+
+        // both buffers are padded to the same size, hence the padding is copied as well
+        let size = align_copy_size((src.len() * size_of::<T>()) as u64);
+        unsafe { self.copy_buffer(src.ptr.buf(), 0, dst.ptr.buf(), 0, size) };
+    }
+}
+
+impl<T, S: Shape> CopySlice<T, Self, S> for WGPU {
+    /// Copies a range of `source` to a range of `dest` on the device.
+    /// # Example
+    /// ```
+    /// use custos::{WGPU, Buffer, CopySlice};
+    ///
+    /// fn main() -> custos::Result<()> {
+    ///     let device = WGPU::new(wgpu::Backends::all())?;
+    ///     let source = Buffer::from((&device, [1., 2., 3., 4., 5.]));
+    ///     let mut dest = Buffer::from((&device, [5., 4., 3., 2., 1.]));
+    ///
+    ///     device.copy_slice_to(&source, 1..3, &mut dest, 3..5);
+    ///     assert_eq!(dest.read(), [5., 4., 3., 2., 3.]);
+    ///     Ok(())
+    /// }
+    /// ```
+    /// # Panics
+    /// If the ranges have different lengths.
+    fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
+        &self,
+        source: &Buffer<T, Self, S>,
+        source_range: SR,
+        dest: &mut Buffer<T, Self, S>,
+        dest_range: DR,
+    ) {
+        let source_range = bounds_to_range(source_range, source.len());
+        let dest_range = bounds_to_range(dest_range, dest.len());
+
+        assert_eq!(
+            source_range.end - source_range.start,
+            dest_range.end - dest_range.start,
+        );
+
+        let (source_offset, size) = byte_range::<T>(&source_range);
+        let (dest_offset, _) = byte_range::<T>(&dest_range);
+
+        self.copy_buffer(
+            unsafe { source.ptr.buf() },
+            source_offset,
+            unsafe { dest.ptr.buf() },
+            dest_offset,
+            size,
+        );
+    }
+
+    fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
+        &self,
+        source: &Buffer<T, Self, S>,
+        dest: &mut Buffer<T, Self, S>,
+        ranges: I,
+    ) {
+        for (source_range, dest_range) in ranges {
+            self.copy_slice_to(source, source_range, dest, dest_range);
+        }
+    }
+}
+
+impl<'a, T, S: Shape> CloneBuf<'a, T, S> for WGPU {
+    fn clone_buf(&'a self, buf: &Buffer<'a, T, WGPU, S>) -> Buffer<'a, T, WGPU, S> {
+        let mut cloned = Buffer::new(self, buf.len());
+        self.write_buf(&mut cloned, buf);
+        cloned
+    }
+}
+
+impl<'a, T, S: Shape> CacheBuf<'a, T, S> for WGPU {
+    #[inline]
+    fn cached(&'a self, len: usize) -> Buffer<'a, T, WGPU, S> {
+        Cache::get(self, len, CachedLeaf)
+    }
+}
+
+#[cfg(feature = "opt-cache")]
+impl crate::GraphOpt for WGPU {}
@@ -68,8 +68,49 @@ impl core::fmt::Display for Error {
 pub type Result<T> = core::result::Result<T, self::std_err::Error>;
 
 #[cfg(feature = "no-std")]
-#[derive(Debug)]
-pub struct Error {}
+mod no_std_err {
+    use crate::DeviceError;
+
+    /// The error type of custos without the standard library.
+    /// As there is no `Box<dyn Error>`, it always carries a [`DeviceError`].
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    pub struct Error {
+        pub kind: DeviceError,
+    }
+
+    pub trait ErrorKind {
+        fn kind<E: PartialEq + 'static>(&self) -> Option<&E>;
+    }
+
+    impl ErrorKind for Error {
+        #[inline]
+        fn kind<E: PartialEq + 'static>(&self) -> Option<&E> {
+            (&self.kind as &dyn core::any::Any).downcast_ref::<E>()
+        }
+    }
+
+    impl From<DeviceError> for Error {
+        #[inline]
+        fn from(kind: DeviceError) -> Self {
+            Error { kind }
+        }
+    }
+
+    impl core::fmt::Debug for Error {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "{:?}", self.kind)
+        }
+    }
+
+    impl core::fmt::Display for Error {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "{}", self.kind)
+        }
+    }
+}
+
+#[cfg(feature = "no-std")]
+pub use no_std_err::*;
 
 #[cfg(feature = "no-std")]
 pub type Result<T> = core::result::Result<T, Error>;
@@ -81,6 +122,25 @@ pub enum DeviceError {
     GraphOptimization, // probably a programming error
     MissingAddress,
     WGPUDeviceReturn,
+    InvalidWGPUAdapterIdx,
+    InvalidWGPUBackend,
+    InvalidWGPUAdapterIdxEnv,
+    MemMapHeader,
+    MemMapDatatype,
+    MemMapFlush,
+    NetworkProtocol,
+    UnknownBackend,
+    GraphCapacity,
+    ArenaExhausted,
+    MissingArenaMemory,
+    MissingKernel,
+    AmbiguousKernel,
+    NoUnifiedMemory,
+    MissingSvmDevice,
+    SvmNotSupported,
+    NoSharedContext,
+    InvalidUnifiedEnv,
+    UnifiedMemUnsupported,
 }
 
 impl DeviceError {
@@ -93,6 +153,53 @@ impl DeviceError {
             DeviceError::GraphOptimization => "This graph can't be optimized.",
             DeviceError::MissingAddress => "An address was not supplied for a Network device.",
             DeviceError::WGPUDeviceReturn => "Cannot create WGPU device instance.",
+            DeviceError::InvalidWGPUAdapterIdx => {
+                "There is no WGPU adapter at the index in CUSTOS_WGPU_ADAPTER_IDX."
+            }
+            DeviceError::InvalidWGPUBackend => {
+                "Environment variable 'CUSTOS_WGPU_BACKEND' contains an invalid list of wgpu backends."
+            }
+            DeviceError::InvalidWGPUAdapterIdxEnv => {
+                "Environment variable 'CUSTOS_WGPU_ADAPTER_IDX' contains an invalid wgpu adapter index."
+            }
+            DeviceError::MemMapHeader => "The file does not start with a valid memory map header.",
+            DeviceError::MemMapDatatype => {
+                "The datatype in the memory map header does not match the requested datatype."
+            }
+            DeviceError::MemMapFlush => {
+                "Only a buffer that owns a read-write memory map can be flushed."
+            }
+            DeviceError::NetworkProtocol => "Received an invalid message from the remote device.",
+            DeviceError::UnknownBackend => {
+                "The backend is unknown or not enabled. Expected e.g. 'cpu', 'opencl:1' or 'cuda'."
+            }
+            DeviceError::GraphCapacity => "The capacity of the graph is exhausted.",
+            DeviceError::ArenaExhausted => "The memory of the arena is exhausted.",
+            DeviceError::MissingArenaMemory => {
+                "An Arena device needs a memory region. Use Arena::new instead."
+            }
+            DeviceError::MissingKernel => "The program does not contain a kernel with this name.",
+            DeviceError::AmbiguousKernel => {
+                "The program contains several kernels. Select one by its name."
+            }
+            DeviceError::NoUnifiedMemory => {
+                "The buffer is not accessible from the host, because the OpenCL device does not use unified memory."
+            }
+            DeviceError::MissingSvmDevice => {
+                "An SVM device needs an OpenCL device. Use SVM::fine or SVM::coarse instead."
+            }
+            DeviceError::SvmNotSupported => {
+                "The OpenCL device does not support this kind of shared virtual memory."
+            }
+            DeviceError::NoSharedContext => {
+                "The OpenCL devices do not share a context. Create them with OpenCL::with_shared_context."
+            }
+            DeviceError::InvalidUnifiedEnv => {
+                "Environment variable 'CUSTOS_USE_UNIFIED' must be either true, false or default."
+            }
+            DeviceError::UnifiedMemUnsupported => {
+                "Unified memory was requested, but the OpenCL device does not support it."
+            }
         }
     }
 }
@@ -1,13 +1,34 @@
-use crate::{AddGraph, CacheTrace, Ident, Node, COUNT};
+use alloc::{vec, vec::Vec};
 
+use crate::{get_count, AddGraph, CacheTrace, Ident, Node};
+
+#[cfg(feature = "no-std")]
+use super::NodeArray;
+
+/// Records the operations of a device, which is used to optimize the memory usage of the cache.
+///
+/// Without the standard library, the graph has a fixed capacity of [`GRAPH_CAPACITY`](crate::GRAPH_CAPACITY) nodes.
+/// Nodes added after that are returned as leaves and the graph is marked as `overflowed`, which prevents optimizing it.
 #[derive(Default, Debug)]
 pub struct Graph {
+    #[cfg(not(feature = "no-std"))]
     pub nodes: Vec<Node>,
+    #[cfg(feature = "no-std")]
+    pub nodes: NodeArray,
+    #[cfg(feature = "no-std")]
+    pub overflowed: bool,
 }
 
 impl Graph {
-    pub fn new() -> Self {
-        Self { nodes: Vec::new() }
+    pub const fn new() -> Self {
+        Graph {
+            #[cfg(not(feature = "no-std"))]
+            nodes: Vec::new(),
+            #[cfg(feature = "no-std")]
+            nodes: NodeArray::new(),
+            #[cfg(feature = "no-std")]
+            overflowed: false,
+        }
     }
 
     pub fn add(&mut self, len: usize, add_node: impl AddGraph) -> Node {
@@ -25,16 +46,23 @@ impl Graph {
 
     pub fn add_node(&mut self, len: usize, lhs_idx: isize, rhs_idx: isize) -> Node {
         let idx = self.nodes.len() as isize;
-        let node = COUNT.with(|count| {
-            Node {
-                // subtracting 1, because the count is increased beforehand.
-                ident_idx: count.get() as isize,
-                idx,
-                deps: [lhs_idx, rhs_idx],
-                len,
-            }
-        });
+        let node = Node {
+            // subtracting 1, because the count is increased beforehand.
+            ident_idx: get_count() as isize,
+            idx,
+            deps: [lhs_idx, rhs_idx],
+            len,
+        };
+
+        #[cfg(not(feature = "no-std"))]
         self.nodes.push(node);
+
+        #[cfg(feature = "no-std")]
+        if !self.nodes.push(node) {
+            self.overflowed = true;
+            return self.add_leaf(len);
+        }
+
         node
     }
 
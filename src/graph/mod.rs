@@ -1,5 +1,5 @@
-#[cfg(not(feature = "no-std"))]
 use crate::Ident;
+use alloc::vec::Vec;
 
 use core::cell::RefMut;
 
@@ -7,38 +7,19 @@ use core::cell::RefMut;
 use crate::{CacheReturn, DeviceError};
 
 pub use add_graph::*;
+pub use graph_struct::Graph;
 pub use node::*;
 
-mod add_graph;
-mod node;
+#[cfg(feature = "no-std")]
+pub use node_array::*;
 
-#[cfg(not(feature = "no-std"))]
+mod add_graph;
 mod graph_struct;
-
-#[cfg(not(feature = "no-std"))]
-pub use graph_struct::Graph;
-
-#[cfg(feature = "no-std")]
-pub struct Graph {}
+mod node;
 
 #[cfg(feature = "no-std")]
-impl Graph {
-    #[inline]
-    pub fn add_leaf(&mut self, len: usize) -> Node {
-        Node {
-            idx: -1,
-            ident_idx: -1,
-            deps: [-1, -1],
-            len,
-        }
-    }
-    #[inline]
-    pub fn add_node(&mut self, len: usize, lhs_idx: isize, rhs_idx: isize) -> Node {
-        self.add_leaf(len)
-    }
-}
+mod node_array;
 
-#[cfg(not(feature = "no-std"))]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CacheTrace {
     pub cache_idx: usize,
@@ -55,6 +36,12 @@ pub trait GraphOpt {
     where
         Self: GraphReturn + CacheReturn + crate::RawConv,
     {
+        // nodes, which exceeded the capacity, are missing in the graph. Hence, the traces could be wrong.
+        #[cfg(feature = "no-std")]
+        if self.graph().overflowed {
+            return Err(DeviceError::GraphCapacity.into());
+        }
+
         let mut cache = self.cache();
 
         for trace in self.graph().cache_traces() {
@@ -335,3 +322,25 @@ mod tests {
         //        println!("traces: {traces:?}");
     }
 }
+
+#[cfg(feature = "no-std")]
+#[cfg(test)]
+mod no_std_tests {
+    use crate::{Graph, GRAPH_CAPACITY};
+
+    #[test]
+    fn test_graph_overflow() {
+        let mut graph = Graph::new();
+        let a = graph.add_leaf(10);
+
+        for _ in 0..GRAPH_CAPACITY {
+            assert!(!graph.add_node(10, a.idx, a.idx).is_leaf());
+        }
+        assert!(!graph.overflowed);
+
+        let node = graph.add_node(10, a.idx, a.idx);
+        assert!(node.is_leaf());
+        assert!(graph.overflowed);
+        assert_eq!(graph.nodes.len(), GRAPH_CAPACITY);
+    }
+}
@@ -0,0 +1,84 @@
+use core::ops::Deref;
+
+use super::Node;
+
+/// The maximum amount of nodes a [`Graph`](crate::Graph) can hold without the standard library.
+pub const GRAPH_CAPACITY: usize = 128;
+
+/// A fixed-capacity list of [`Node`]s, which is used by the [`Graph`](crate::Graph) if the `no-std` feature is enabled.
+#[derive(Debug, Clone, Copy)]
+pub struct NodeArray {
+    nodes: [Node; GRAPH_CAPACITY],
+    len: usize,
+}
+
+impl NodeArray {
+    pub const fn new() -> Self {
+        NodeArray {
+            nodes: [Node {
+                ident_idx: -1,
+                idx: -1,
+                deps: [-1, -1],
+                len: 0,
+            }; GRAPH_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Appends a node to the list.
+    /// Returns `false` if the capacity is exhausted and the node was not added.
+    #[inline]
+    pub fn push(&mut self, node: Node) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.nodes[self.len] = node;
+        self.len += 1;
+        true
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == GRAPH_CAPACITY
+    }
+}
+
+impl Default for NodeArray {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for NodeArray {
+    type Target = [Node];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.nodes[..self.len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NodeArray, GRAPH_CAPACITY};
+    use crate::Node;
+
+    #[test]
+    fn test_node_array_capacity() {
+        let mut nodes = NodeArray::new();
+        assert!(nodes.is_empty());
+
+        for idx in 0..GRAPH_CAPACITY {
+            assert!(nodes.push(Node {
+                idx: idx as isize,
+                ..Default::default()
+            }));
+        }
+        assert!(nodes.is_full());
+        assert!(!nodes.push(Node::default()));
+
+        assert_eq!(nodes.len(), GRAPH_CAPACITY);
+        assert_eq!(nodes[GRAPH_CAPACITY - 1].idx, GRAPH_CAPACITY as isize - 1);
+    }
+}
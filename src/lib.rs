@@ -35,6 +35,14 @@
 //! ```
 use core::ffi::c_void;
 
+extern crate alloc;
+use alloc::vec::Vec;
+
+// the unit tests use e.g. println! and vec!
+#[cfg(all(test, feature = "no-std"))]
+#[macro_use]
+extern crate std;
+
 //pub use libs::*;
 pub use buffer::*;
 pub use count::*;
@@ -62,6 +70,27 @@ pub use devices::stack::Stack;
 #[cfg(feature = "network")]
 pub use devices::network::Network;
 
+#[cfg(feature = "mmap")]
+pub use devices::mmap::MemMap;
+
+#[cfg(feature = "trace")]
+pub use devices::trace::Traced;
+
+#[cfg(feature = "sim-gpu")]
+pub use devices::sim_gpu::SimGPU;
+
+#[cfg(feature = "arena")]
+pub use devices::arena::Arena;
+
+#[cfg(not(feature = "no-std"))]
+#[cfg(any(
+    feature = "cpu",
+    feature = "opencl",
+    feature = "cuda",
+    feature = "wgpu"
+))]
+pub use devices::any::{AnyBuffer, AnyDevice};
+
 pub mod devices;
 
 mod buffer;
@@ -76,6 +105,11 @@ mod shape;
 #[cfg(feature = "static-api")]
 pub mod static_api;
 
+// used by the `buf!` macro, which should work in `no_std` crates as well
+#[cfg(feature = "static-api")]
+#[doc(hidden)]
+pub use alloc::vec as __vec;
+
 pub mod number;
 pub use op_traits::*;
 pub use shape::*;
@@ -174,7 +208,6 @@ pub trait Alloc<'a, T, S: Shape = ()>: Device {
 
     /// If the vector `vec` was allocated previously, this function can be used in order to reduce the amount of allocations, which may be faster than using a slice of `vec`.
     #[inline]
-    #[cfg(not(feature = "no-std"))]
     fn alloc_with_vec(&'a self, vec: Vec<T>) -> <Self as Device>::Ptr<T, S>
     where
         T: Clone,
@@ -192,32 +225,28 @@ pub trait Alloc<'a, T, S: Shape = ()>: Device {
     }
 }
 
-#[cfg(not(unified_cl))]
-pub const UNIFIED_CL_MEM: bool = false;
-
-#[cfg(unified_cl)]
-pub const UNIFIED_CL_MEM: bool = true;
-
 #[cfg(feature = "macro")]
 pub use custos_macro::impl_stack;
 
 pub mod prelude {
     pub use crate::{
         cached, number::*, range, shape::*, Alloc, Buffer, CDatatype, CacheBuf, ClearBuf,
-        CopySlice, Device, GraphReturn, MainMemory, Read, ShallowCopy, WithShape, WriteBuf,
+        CopyRect, CopySlice, Device, GraphReturn, MainMemory, Read, Rect, ShallowCopy, WithShape,
+        WriteBuf,
     };
 
     #[cfg(feature = "cpu")]
     pub use crate::{cpu::cpu_cached, CPU};
 
-    #[cfg(not(feature = "no-std"))]
     pub use crate::{cache::CacheReturn, get_count, set_count, Cache};
 
+    #[cfg(not(feature = "no-std"))]
+    pub use crate::DeviceInfo;
+
     #[cfg(feature = "opencl")]
     pub use crate::opencl::{enqueue_kernel, CLBuffer, OpenCL, CL};
 
     #[cfg(feature = "opencl")]
-    #[cfg(unified_cl)]
     #[cfg(not(feature = "realloc"))]
     pub use crate::opencl::{cl_cached, construct_buffer, to_unified};
 
@@ -230,6 +259,27 @@ pub mod prelude {
     #[cfg(feature = "wgpu")]
     pub use crate::wgpu::{launch_shader, WGPU};
 
+    #[cfg(feature = "mmap")]
+    pub use crate::mmap::{MapMode, MemMap};
+
+    #[cfg(feature = "trace")]
+    pub use crate::trace::Traced;
+
+    #[cfg(feature = "sim-gpu")]
+    pub use crate::sim_gpu::SimGPU;
+
+    #[cfg(feature = "arena")]
+    pub use crate::arena::Arena;
+
+    #[cfg(not(feature = "no-std"))]
+    #[cfg(any(
+        feature = "cpu",
+        feature = "opencl",
+        feature = "cuda",
+        feature = "wgpu"
+    ))]
+    pub use crate::{any::AnyPtr, AnyBuffer, AnyDevice};
+
     #[cfg(feature = "cuda")]
     pub use crate::cuda::{launch_kernel1d, CUBuffer, CU, CUDA};
 }
@@ -208,7 +208,7 @@ impl Float for f32 {
 
     #[inline]
     fn abs(&self) -> Self {
-        self * ((*self > 0.) as u32 - (*self < 0.) as u32) as f32
+        libm::fabsf(*self)
     }
 }
 
@@ -256,6 +256,6 @@ impl Float for f64 {
 
     #[inline]
     fn abs(&self) -> Self {
-        self * ((*self > 0.) as u64 - (*self < 0.) as u64) as f64
+        libm::fabs(*self)
     }
 }
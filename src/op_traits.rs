@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::ops::{Bound, Range, RangeBounds};
 
 use crate::{Alloc, Buffer, Device, Shape};
@@ -21,7 +22,10 @@ pub trait ClearBuf<T, D: Device = Self, S: Shape = ()>: Device {
 }
 
 /// Trait for copying a slice of a buffer, to implement the slice() operation.
-pub trait CopySlice<T, D: Device = Self>: Sized + Device {
+///
+/// The source buffer has the shape `S`, the destination buffer the shape `DS`.
+/// Both default to `()`, which matches the previous, shape-less signature of this trait.
+pub trait CopySlice<T, D: Device = Self, S: Shape = (), DS: Shape = S>: Sized + Device {
     /// Copy a slice of the given buffer into a new buffer.
     /// # Example
     ///
@@ -36,11 +40,11 @@ pub trait CopySlice<T, D: Device = Self>: Sized + Device {
     /// ```
     fn copy_slice<'a, R: RangeBounds<usize>>(
         &'a self,
-        buf: &'a Buffer<T, D>,
+        buf: &'a Buffer<T, D, S>,
         range: R,
-    ) -> Buffer<T, Self>
+    ) -> Buffer<T, Self, DS>
     where
-        Self: for<'b> Alloc<'b, T>,
+        Self: for<'b> Alloc<'b, T, DS>,
     {
         let range = bounds_to_range(range, buf.len());
         let mut copied = Buffer::new(self, range.end - range.start);
@@ -63,9 +67,9 @@ pub trait CopySlice<T, D: Device = Self>: Sized + Device {
     /// ```
     fn copy_slice_to<SR: RangeBounds<usize>, DR: RangeBounds<usize>>(
         &self,
-        source: &Buffer<T, D>,
+        source: &Buffer<T, D, S>,
         source_range: SR,
-        dest: &mut Buffer<T, Self>,
+        dest: &mut Buffer<T, Self, DS>,
         dest_range: DR,
     );
 
@@ -90,12 +94,183 @@ pub trait CopySlice<T, D: Device = Self>: Sized + Device {
     ///```
     fn copy_slice_all<I: IntoIterator<Item = (Range<usize>, Range<usize>)>>(
         &self,
-        source: &Buffer<T, D>,
-        dest: &mut Buffer<T, Self>,
+        source: &Buffer<T, D, S>,
+        dest: &mut Buffer<T, Self, DS>,
         ranges: I,
     );
 }
 
+/// Describes a buffer or host slice as a 3D array of slices of rows and the position of a rectangular region within it.
+/// All values are counted in elements.
+///
+/// A pitch of `0` means that the rows or slices are tightly packed, i.e. the pitch is derived from the copied region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    /// The element, row and slice (`[x, y, z]`), at which the region starts.
+    pub origin: [usize; 3],
+    /// The number of elements between the starts of two rows.
+    pub row_pitch: usize,
+    /// The number of elements between the starts of two slices.
+    pub slice_pitch: usize,
+}
+
+impl Rect {
+    #[inline]
+    pub fn new(origin: [usize; 3], row_pitch: usize, slice_pitch: usize) -> Rect {
+        Rect {
+            origin,
+            row_pitch,
+            slice_pitch,
+        }
+    }
+
+    /// A region at the start of tightly packed data.
+    #[inline]
+    pub fn packed() -> Rect {
+        Rect::default()
+    }
+
+    /// A region of a `Dim2<B, A>` matrix (`B` rows, `A` columns), starting at `[row, col]`.
+    #[inline]
+    pub fn dim2<const B: usize, const A: usize>([row, col]: [usize; 2]) -> Rect {
+        Rect::new([col, row, 0], A, B * A)
+    }
+
+    /// A region of a `Dim3<C, B, A>` array (`C` matrices with `B` rows and `A` columns), starting at `[matrix, row, col]`.
+    #[inline]
+    pub fn dim3<const C: usize, const B: usize, const A: usize>(
+        [matrix, row, col]: [usize; 3],
+    ) -> Rect {
+        Rect::new([col, row, matrix], A, B * A)
+    }
+
+    /// Returns the row and slice pitch, after resolving pitches of `0`.
+    #[inline]
+    pub fn pitches(&self, region: [usize; 3]) -> (usize, usize) {
+        let row_pitch = if self.row_pitch == 0 {
+            region[0]
+        } else {
+            self.row_pitch
+        };
+
+        let slice_pitch = if self.slice_pitch == 0 {
+            region[1] * row_pitch
+        } else {
+            self.slice_pitch
+        };
+        (row_pitch, slice_pitch)
+    }
+
+    /// The index of the element at `[x, y, z]` within the region.
+    /// # Panics
+    /// If the index overflows `usize`.
+    #[inline]
+    pub fn index(&self, region: [usize; 3], pos: [usize; 3]) -> usize {
+        self.checked_index(region, pos)
+            .unwrap_or_else(|| panic!("The index of {pos:?} in {self:?} overflows usize."))
+    }
+
+    /// The index of the element at `[x, y, z]` within the region or `None`, if it overflows `usize`.
+    pub fn checked_index(&self, region: [usize; 3], [x, y, z]: [usize; 3]) -> Option<usize> {
+        let (row_pitch, slice_pitch) = self.pitches(region);
+
+        let slice = self.origin[2].checked_add(z)?.checked_mul(slice_pitch)?;
+        let row = self.origin[1].checked_add(y)?.checked_mul(row_pitch)?;
+        slice
+            .checked_add(row)?
+            .checked_add(self.origin[0])?
+            .checked_add(x)
+    }
+
+    /// Panics if the region does not fit into data with length `len`.
+    ///
+    /// Besides the length, every row of the region must end within its row (`origin[0] + region[0] <= row_pitch`)
+    /// and every slice within its slice (`origin[1] + region[1] <= slice_pitch / row_pitch`).
+    /// Otherwise, the region would wrap into the next row or slice.
+    pub fn assert_region(&self, region: [usize; 3], len: usize) {
+        assert!(
+            region.iter().all(|&extent| extent > 0),
+            "invalid region: {region:?}"
+        );
+
+        let (row_pitch, slice_pitch) = self.pitches(region);
+        let fits_row = self.origin[0]
+            .checked_add(region[0])
+            .map_or(false, |end| end <= row_pitch);
+        let fits_slice = self.origin[1]
+            .checked_add(region[1])
+            .map_or(false, |end| end <= slice_pitch / row_pitch);
+
+        assert!(
+            fits_row && fits_slice,
+            "The region {region:?} of {self:?} exceeds a row or slice."
+        );
+
+        let last = self.checked_index(region, [region[0] - 1, region[1] - 1, region[2] - 1]);
+        assert!(
+            last.map_or(false, |last| last < len),
+            "The region {region:?} of {self:?} is out of bounds (len: {len})."
+        );
+    }
+}
+
+/// Trait for copying rectangular (2D or 3D) regions, e.g. a sub-matrix, between buffers and host slices.
+///
+/// The `region` is given as `[elements, rows, slices]`, the position of the region within the data is described by a [`Rect`].
+/// # Example
+#[cfg_attr(feature = "cpu", doc = "```")]
+#[cfg_attr(not(feature = "cpu"), doc = "```ignore")]
+/// use custos::{Buffer, CopyRect, Dim2, Rect, WithShape, CPU};
+///
+/// let device = CPU::new();
+/// let matrix = Buffer::<i32, _, Dim2<3, 4>>::with(&device, [
+///     [1, 2, 3, 4],
+///     [5, 6, 7, 8],
+///     [9, 10, 11, 12],
+/// ]);
+///
+/// let mut sub = Buffer::<i32, _, Dim2<2, 2>>::new(&device, 4);
+/// device.copy_rect(&matrix, Rect::dim2::<3, 4>([1, 2]), &mut sub, Rect::packed(), [2, 2, 1]);
+/// assert_eq!(sub.read(), [7, 8, 11, 12]);
+/// ```
+pub trait CopyRect<T, D: Device = Self, S: Shape = ()>: Device {
+    /// Copies the `region` at `source_rect` of `source` to `dest_rect` of `dest`.
+    /// # Panics
+    /// If the region is out of bounds of one of the buffers.
+    fn copy_rect<DS: Shape>(
+        &self,
+        source: &Buffer<T, D, S>,
+        source_rect: Rect,
+        dest: &mut Buffer<T, Self, DS>,
+        dest_rect: Rect,
+        region: [usize; 3],
+    );
+
+    /// Reads the `region` at `buf_rect` of `buf` into `host_rect` of `host`.
+    /// # Panics
+    /// If the region is out of bounds of the buffer or the host slice.
+    fn read_rect(
+        &self,
+        buf: &Buffer<T, D, S>,
+        buf_rect: Rect,
+        host: &mut [T],
+        host_rect: Rect,
+        region: [usize; 3],
+    );
+
+    /// Writes the `region` at `host_rect` of `host` into `buf_rect` of `buf`.
+    /// # Panics
+    /// If the region is out of bounds of the buffer or the host slice.
+    fn write_rect(
+        &self,
+        buf: &mut Buffer<T, D, S>,
+        buf_rect: Rect,
+        host: &[T],
+        host_rect: Rect,
+        region: [usize; 3],
+    );
+}
+
 /// Trait for reading buffers.
 pub trait Read<T, D: Device = Self, S: Shape = ()>: Device {
     type Read<'a>
@@ -128,7 +303,6 @@ pub trait Read<T, D: Device = Self, S: Shape = ()>: Device {
     /// let read = device.read_to_vec(&a);
     /// assert_eq!(vec![1., 2., 3., 3., 2., 1.,], read);
     /// ```
-    #[cfg(not(feature = "no-std"))]
     fn read_to_vec(&self, buf: &Buffer<T, D, S>) -> Vec<T>
     where
         T: Default + Clone;
@@ -199,6 +373,28 @@ pub trait CacheBuf<'a, T, S: Shape = ()>: Sized + Device {
     fn cached(&'a self, len: usize) -> Buffer<'a, T, Self, S>;
 }
 
+/// Copies the `region` at `source_rect` of `source` to `dest_rect` of `dest` row by row.
+pub(crate) fn copy_rect_slices<T: Copy>(
+    source: &[T],
+    source_rect: Rect,
+    dest: &mut [T],
+    dest_rect: Rect,
+    region: [usize; 3],
+) {
+    source_rect.assert_region(region, source.len());
+    dest_rect.assert_region(region, dest.len());
+
+    for z in 0..region[2] {
+        for y in 0..region[1] {
+            let source_start = source_rect.index(region, [0, y, z]);
+            let dest_start = dest_rect.index(region, [0, y, z]);
+
+            dest[dest_start..dest_start + region[0]]
+                .copy_from_slice(&source[source_start..source_start + region[0]]);
+        }
+    }
+}
+
 /// Convert a possibly-indefinite [`RangeBounds`] into a [`Range`] with a start and stop index.
 #[inline]
 pub(crate) fn bounds_to_range<B: RangeBounds<usize>>(bounds: B, len: usize) -> Range<usize> {
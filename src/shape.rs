@@ -19,7 +19,6 @@ unsafe impl Shape for () {
 // this is used to
 pub trait IsShapeIndep: Device {}
 
-#[cfg(not(feature = "no-std"))]
 impl<D: crate::RawConv> IsShapeIndep for D {}
 
 pub trait IsConstDim: Shape {}
@@ -80,7 +79,6 @@ pub trait ToDim<T, I: Shape, O: Shape>: crate::Device {
     fn to_dim(&self, ptr: Self::Ptr<T, I>) -> Self::Ptr<T, O>;
 }
 
-#[cfg(not(feature = "no-std"))]
 impl<T, D: crate::RawConv, I: Shape, O: Shape> ToDim<T, I, O> for D
 where
     Self::Ptr<T, ()>: crate::PtrType,
@@ -141,7 +139,7 @@ mod tests {
 
     use crate::{Buffer, Device, Dim1, Dim2, Dim3, Shape};
 
-    #[cfg(not(feature = "no-std"))]
+    #[allow(dead_code)]
     fn len_of_shape<T, D: Device, S: Shape>(_: &Buffer<T, D, S>) {
         println!("S::LEN {}", S::LEN);
     }
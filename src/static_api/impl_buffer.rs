@@ -1,7 +1,13 @@
+use alloc::vec::Vec;
+
 use crate::{Alloc, Buffer, GraphReturn, Node};
 
+#[cfg(not(feature = "no-std"))]
 use super::static_cpu;
 
+#[cfg(feature = "no-std")]
+use super::static_api_cpu as static_cpu;
+
 impl<'a, T: Clone> From<&[T]> for Buffer<'a, T> {
     fn from(slice: &[T]) -> Self {
         let device = static_cpu();
@@ -1,7 +1,13 @@
+use alloc::vec::Vec;
+
 use crate::{Alloc, Buffer, GraphReturn};
 
+#[cfg(not(feature = "no-std"))]
 use super::static_cpu;
 
+#[cfg(feature = "no-std")]
+use super::static_api_cpu as static_cpu;
+
 impl<'a, A> FromIterator<A> for Buffer<'a, A>
 where
     A: Clone + Default,
@@ -53,7 +59,8 @@ where
     }
 }
 
-#[cfg(test)]
+// without the standard library, the static API is tested in `static_devices`
+#[cfg(all(test, not(feature = "no-std")))]
 mod tests {
     use crate::Buffer;
 
@@ -4,7 +4,7 @@ macro_rules! buf {
         if $n == 0 {
             panic!("The length of the buffer can't be 0.");
         } else {
-            $crate::Buffer::from(vec![$elem; $n])
+            $crate::Buffer::from($crate::__vec![$elem; $n])
         }
     );
 
@@ -18,7 +18,8 @@ macro_rules! buf {
     )
 }
 
-#[cfg(test)]
+// without the standard library, the static API is tested in `static_devices`
+#[cfg(all(test, not(feature = "no-std")))]
 mod tests {
     #[test]
     fn test_macro_filling() {
@@ -25,3 +25,11 @@ impl StaticGPU for crate::CUDA {
         static_cuda()
     }
 }
+
+#[cfg(feature = "wgpu")]
+impl StaticGPU for crate::WGPU {
+    #[inline]
+    fn as_static() -> &'static Self {
+        static_wgpu()
+    }
+}
@@ -23,16 +23,80 @@ pub fn static_cpu() -> &'static CPU {
     }
 }
 
+/// Without the standard library, there are no thread locals.
+/// Hence, the static CPU is stored in a global, which is initialized exactly once.
 #[cfg(feature = "no-std")]
-pub static GLOBAL_CPU: Option<CPU> = None;
+pub struct GlobalCPU {
+    state: core::sync::atomic::AtomicU8,
+    cpu: core::cell::UnsafeCell<core::mem::MaybeUninit<CPU>>,
+}
 
 #[cfg(feature = "no-std")]
-pub fn static_cpu() -> &'static CPU {
-    if let Some(cpu) = &GLOBAL_CPU {
-        cpu
-    } else {
-        GLOBAL_CPU = Some(CPU::new())
+const UNINIT: u8 = 0;
+#[cfg(feature = "no-std")]
+const INITIALIZING: u8 = 1;
+#[cfg(feature = "no-std")]
+const READY: u8 = 2;
+
+// Safety: the CPU is only written once, while `state` is INITIALIZING. References are handed out after `state` is READY.
+#[cfg(feature = "no-std")]
+unsafe impl Sync for GlobalCPU {}
+
+#[cfg(feature = "no-std")]
+pub static GLOBAL_CPU: GlobalCPU = GlobalCPU {
+    state: core::sync::atomic::AtomicU8::new(UNINIT),
+    cpu: core::cell::UnsafeCell::new(core::mem::MaybeUninit::uninit()),
+};
+
+/// Returns the static CPU, which is created on the first call.
+/// If several threads call this function at the same time, only one of them creates the CPU and the others wait for it.
+///
+/// After the first call, the static API (e.g. `buf!` or `Buffer::from(&[..])`) uses this CPU as well.
+///
+/// # Safety
+/// The CPU is not thread safe, as its cache and graph are stored in `RefCell`s.
+/// Without the standard library, there are no thread locals, hence all threads share the same static CPU.
+/// The caller must ensure that the static CPU and all buffers of the static API are only used by a single thread (e.g. the main loop of an embedded application).
+#[cfg(feature = "no-std")]
+pub unsafe fn static_cpu() -> &'static CPU {
+    use core::sync::atomic::Ordering;
+
+    loop {
+        match GLOBAL_CPU.state.compare_exchange(
+            UNINIT,
+            INITIALIZING,
+            Ordering::Acquire,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {
+                // Safety: only the thread that changed the state to INITIALIZING writes to the CPU.
+                (*GLOBAL_CPU.cpu.get()).write(CPU::new());
+                GLOBAL_CPU.state.store(READY, Ordering::Release);
+                break;
+            }
+            Err(READY) => break,
+            Err(_) => core::hint::spin_loop(),
+        }
     }
+
+    // Safety: the state is READY, hence the CPU is initialized and never written again.
+    (*GLOBAL_CPU.cpu.get()).assume_init_ref()
+}
+
+/// Returns the static CPU for the static API.
+/// # Panics
+/// If the unsafe [`static_cpu`] was not called before, which is the opt-in to the single threaded use of the static CPU.
+#[cfg(feature = "no-std")]
+pub(crate) fn static_api_cpu() -> &'static CPU {
+    use core::sync::atomic::Ordering;
+
+    assert!(
+        GLOBAL_CPU.state.load(Ordering::Acquire) == READY,
+        "Without the standard library, the static API requires a call to the unsafe `static_cpu` beforehand."
+    );
+
+    // Safety: the state is READY. The caller of `static_cpu` ensured that the CPU is only used by a single thread.
+    unsafe { (*GLOBAL_CPU.cpu.get()).assume_init_ref() }
 }
 
 #[cfg(feature = "opencl")]
@@ -49,6 +113,13 @@ thread_local! {
     };
 }
 
+#[cfg(feature = "wgpu")]
+thread_local! {
+    pub static GLOBAL_WGPU: crate::WGPU = {
+        <crate::WGPU as crate::Device>::new().expect("Could not create a static WGPU device.")
+    };
+}
+
 #[cfg(feature = "opencl")]
 #[inline]
 pub fn static_opencl() -> &'static crate::OpenCL {
@@ -73,6 +144,19 @@ pub fn static_cuda() -> &'static crate::CUDA {
     }
 }
 
+/// Returns the static WGPU device, which is configured by the environment variables `CUSTOS_WGPU_BACKEND` and `CUSTOS_WGPU_ADAPTER_IDX`.
+#[cfg(feature = "wgpu")]
+#[inline]
+pub fn static_wgpu() -> &'static crate::WGPU {
+    // Safety: GLOBAL_WGPU should live long enough
+    unsafe {
+        GLOBAL_WGPU
+            .with(|device| device as *const crate::WGPU)
+            .as_ref()
+            .unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(not(feature = "no-std"))]
@@ -106,6 +190,44 @@ mod tests {
         assert_eq!(cached.ptr, out.ptr.ptr as *mut u8);
     }
 
+    #[cfg(feature = "no-std")]
+    #[test]
+    fn test_static_cpu_threads() {
+        use super::static_cpu;
+        use crate::CPU;
+
+        // Safety: the CPU is only compared by address and never used.
+        let cpus = (0..4)
+            .map(|_| std::thread::spawn(|| unsafe { static_cpu() } as *const CPU as usize))
+            .collect::<std::vec::Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<std::vec::Vec<_>>();
+
+        let cpu = unsafe { static_cpu() } as *const CPU as usize;
+        assert!(cpus.iter().all(|other| *other == cpu));
+    }
+
+    #[cfg(feature = "no-std")]
+    #[test]
+    fn test_static_api_no_std() {
+        use super::static_cpu;
+        use crate::{buf, Buffer};
+
+        // Safety: the static API is only used by this test.
+        let cpu = unsafe { static_cpu() };
+
+        let buf = buf![5, 3, 2, 6, 2];
+        assert!(core::ptr::eq(buf.device(), cpu));
+        assert_eq!(buf.as_slice(), &[5, 3, 2, 6, 2]);
+
+        let buf = buf![2.; 10];
+        assert_eq!(buf.as_slice(), &[2.; 10]);
+
+        let buf = (0..5).collect::<Buffer<i32>>();
+        assert_eq!(buf.as_slice(), &[0, 1, 2, 3, 4]);
+    }
+
     #[cfg(feature = "opencl")]
     #[test]
     fn test_to_cl() {
@@ -160,6 +282,18 @@ mod tests {
         assert_eq!(buf.read(), vec![3., 1.4, 1., 2.]);
     }
 
+    #[cfg(feature = "wgpu")]
+    #[test]
+    fn test_to_device_wgpu() {
+        use crate::{buf, WGPU};
+
+        let buf = buf![3f32, 1.4, 1., 2.].to_dev::<WGPU>();
+        assert_eq!(buf.read(), vec![3., 1.4, 1., 2.]);
+
+        let buf = buf.to_cpu();
+        assert_eq!(buf.as_slice(), &[3., 1.4, 1., 2.]);
+    }
+
     #[cfg(feature = "opencl")]
     #[test]
     fn test_to_device_cl() {
@@ -1,6 +1,12 @@
 use crate::{Alloc, Buffer, Device, IsShapeIndep, Read};
 
-use super::{static_cpu, StaticGPU};
+use super::StaticGPU;
+
+#[cfg(not(feature = "no-std"))]
+use super::static_cpu;
+
+#[cfg(feature = "no-std")]
+use super::static_api_cpu as static_cpu;
 
 impl<'a, T: Clone> Buffer<'a, T> {
     /// Moves the buffer [`Buffer`] to a static device.<br>
@@ -62,6 +68,23 @@ impl<'a, T: Clone> Buffer<'a, T> {
         self.to_dev::<crate::OpenCL>()
     }
 
+    /// Converts a [Buffer] to a WGPU device buffer.
+    ///
+    /// Example
+    /// ```
+    /// use custos::prelude::*;
+    ///
+    /// let cpu_buffer = Buffer::from(&[1., 2., 3.]);
+    ///
+    /// let wgpu_buf = cpu_buffer.to_wgpu();
+    /// assert_eq!(wgpu_buf.read(), vec![1., 2., 3.]);
+    /// ```
+    #[cfg(feature = "wgpu")]
+    #[inline]
+    pub fn to_wgpu(self) -> Buffer<'a, T, crate::WGPU> {
+        self.to_dev::<crate::WGPU>()
+    }
+
     /// Converts a [Buffer] to an OpenCL device buffer.
     ///
     /// This method depends on the feature configuration.<br>
@@ -28,3 +28,55 @@ fn test_wgpu_alloc() {
 
     assert_eq!(buf1.read(), &[1., 2., 3., 4., -9.])
 }
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_cpu_aligned_alloc() {
+    let device = CPU::new().with_align(64);
+    assert_eq!(device.align(), 64);
+
+    for len in [1, 3, 17, 100] {
+        let buf = Buffer::<u8>::new(&device, len);
+        assert_eq!(buf.ptr.ptr as usize % 64, 0);
+        assert_eq!(buf.read(), vec![0; len]);
+
+        let buf = Buffer::<f32>::from((&device, vec![1f32; len]));
+        assert_eq!(buf.ptr.ptr as usize % 64, 0);
+        assert_eq!(buf.read(), vec![1.; len]);
+    }
+
+    let buf = Buffer::<i32>::from((&device, [1i32, 2, 3]));
+    assert_eq!(buf.ptr.ptr as usize % 64, 0);
+
+    let cached = Cache::get::<f64, ()>(&device, 10, ());
+    assert_eq!(cached.ptr.ptr as usize % 64, 0);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_cpu_natural_align() {
+    // types with a larger alignment keep their natural alignment
+    let device = CPU::new().with_align(2);
+    let buf = Buffer::<u64>::new(&device, 4);
+    assert_eq!(buf.ptr.ptr as usize % core::mem::align_of::<u64>(), 0);
+    assert_eq!(buf.ptr.align, core::mem::align_of::<u64>());
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+#[should_panic]
+fn test_cpu_align_not_pow_of_two() {
+    let _ = CPU::new().with_align(24);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_cpu_uninit_buf() {
+    let device = CPU::new().with_align(32);
+
+    let mut buf = unsafe { device.uninit_buf::<f32>(8) };
+    assert_eq!(buf.ptr.ptr as usize % 32, 0);
+
+    buf.copy_from_slice(&[1., 2., 3., 4., 5., 6., 7., 8.]);
+    assert_eq!(buf.read(), [1., 2., 3., 4., 5., 6., 7., 8.]);
+}
@@ -0,0 +1,177 @@
+#![cfg(not(feature = "no-std"))]
+#![cfg(any(
+    feature = "cpu",
+    feature = "opencl",
+    feature = "cuda",
+    feature = "wgpu"
+))]
+
+use custos::{
+    any_dispatch, AnyDevice, Buffer, ClearBuf, CloneBuf, CopySlice, Device, DeviceError, ErrorKind,
+    WriteBuf,
+};
+
+// only `f32` is supported by all backends, including WGSL shaders
+pub trait Scale: Device {
+    fn scale(&self, buf: &mut Buffer<f32, Self>, factor: f32);
+}
+
+#[cfg(feature = "cpu")]
+impl Scale for custos::CPU {
+    fn scale(&self, buf: &mut Buffer<f32, Self>, factor: f32) {
+        for value in buf.iter_mut() {
+            *value *= factor;
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl Scale for custos::OpenCL {
+    fn scale(&self, buf: &mut Buffer<f32, Self>, factor: f32) {
+        let src = "__kernel void scale(__global float* buf, float factor) {
+            size_t id = get_global_id(0);
+            buf[id] *= factor;
+        }";
+        custos::opencl::enqueue_kernel(self, src, [buf.len(), 0, 0], None, &[buf, &factor])
+            .unwrap();
+    }
+}
+
+#[cfg(feature = "cuda")]
+impl Scale for custos::CUDA {
+    fn scale(&self, buf: &mut Buffer<f32, Self>, factor: f32) {
+        let src = r#"extern "C" __global__ void scale(float* buf, float factor, int numElements) {
+            int idx = blockDim.x * blockIdx.x + threadIdx.x;
+            if (idx < numElements) {
+                buf[idx] *= factor;
+            }
+        }"#;
+        custos::cuda::launch_kernel1d(buf.len(), self, src, "scale", &[buf, &factor, &buf.len()])
+            .unwrap();
+    }
+}
+
+#[cfg(feature = "wgpu")]
+impl Scale for custos::WGPU {
+    fn scale(&self, buf: &mut Buffer<f32, Self>, factor: f32) {
+        // WGSL has no scalar kernel arguments, hence the factor is part of the source
+        let src = format!(
+            "@group(0)
+            @binding(0)
+            var<storage, read_write> buf: array<f32>;
+
+            @compute
+            @workgroup_size(1)
+            fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+                buf[global_id.x] *= {factor:?};
+            }}"
+        );
+        custos::wgpu::launch_shader(self, &src, [buf.len() as u32, 1, 1], &[buf]);
+    }
+}
+
+impl Scale for AnyDevice {
+    fn scale(&self, buf: &mut Buffer<f32, Self>, factor: f32) {
+        any_dispatch!(self, device => device.scale(&mut buf.backend_mut(device), factor))
+    }
+}
+
+fn roundtrip(device: &AnyDevice) {
+    let mut buf = Buffer::from((device, [1f32, 2., 3., 4.]));
+    assert_eq!(buf.read(), [1., 2., 3., 4.]);
+
+    device.write(&mut buf, &[5., 6., 7., 8.]);
+    assert_eq!(buf.read(), [5., 6., 7., 8.]);
+
+    let cloned = device.clone_buf(&buf);
+    device.clear(&mut buf);
+    assert_eq!(buf.read(), [0.; 4]);
+    assert_eq!(cloned.read(), [5., 6., 7., 8.]);
+
+    device.copy_slice_to(&cloned, 1..3, &mut buf, 2..);
+    assert_eq!(buf.read(), [0., 0., 6., 7.]);
+
+    let mut other = Buffer::<f32, _>::new(device, 4);
+    device.write_buf(&mut other, &cloned);
+    assert_eq!(other.read(), [5., 6., 7., 8.]);
+
+    device.scale(&mut other, 2.);
+    assert_eq!(other.read(), [10., 12., 14., 16.]);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_any_cpu() -> custos::Result<()> {
+    let device: AnyDevice = "cpu".parse()?;
+    assert_eq!(device.backend_name(), "cpu");
+    roundtrip(&device);
+
+    let device = AnyDevice::from(custos::CPU::new());
+    roundtrip(&device);
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_any_opencl() -> custos::Result<()> {
+    let device: AnyDevice = "opencl:0".parse()?;
+    assert_eq!(device.backend_name(), "opencl");
+    roundtrip(&device);
+    Ok(())
+}
+
+#[cfg(feature = "wgpu")]
+#[test]
+fn test_any_wgpu() -> custos::Result<()> {
+    // a device is dropped before the next one is created, as GL adapters of several wgpu instances share one EGL display
+    {
+        let device: AnyDevice = "wgpu".parse()?;
+        assert_eq!(device.backend_name(), "wgpu");
+        roundtrip(&device);
+    }
+    {
+        let device: AnyDevice = "wgpu:0".parse()?;
+        roundtrip(&device);
+    }
+
+    let device = AnyDevice::from(custos::WGPU::new(wgpu::Backends::all())?);
+    roundtrip(&device);
+    Ok(())
+}
+
+#[cfg(feature = "cuda")]
+#[test]
+fn test_any_cuda() -> custos::Result<()> {
+    let device: AnyDevice = "cuda".parse()?;
+    roundtrip(&device);
+    Ok(())
+}
+
+#[test]
+fn test_any_new() -> custos::Result<()> {
+    let device = <AnyDevice as Device>::new()?;
+    roundtrip(&device);
+    Ok(())
+}
+
+#[test]
+fn test_any_unknown_backend() {
+    for backend in ["vulkan", "cpu:1", ""] {
+        let err = backend.parse::<AnyDevice>().err().unwrap();
+        assert_eq!(err.kind(), Some(&DeviceError::UnknownBackend));
+    }
+    assert!("opencl:x".parse::<AnyDevice>().is_err());
+}
+
+#[cfg(all(feature = "cpu", feature = "opencl"))]
+#[test]
+#[should_panic]
+fn test_any_mismatched_backend() {
+    use custos::Read;
+
+    let cpu: AnyDevice = "cpu".parse().unwrap();
+    let cl: AnyDevice = "opencl".parse().unwrap();
+
+    let buf = Buffer::from((&cpu, [1, 2, 3]));
+    cl.read(&buf);
+}
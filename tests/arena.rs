@@ -0,0 +1,69 @@
+use custos::{Arena, Buffer, ClearBuf, Device, DeviceError, ErrorKind, Read, WriteBuf};
+
+#[test]
+fn test_arena_ops() {
+    let mut memory = [0u8; 128];
+    let device = Arena::new(&mut memory);
+
+    let mut buf = Buffer::<i32, _>::new(&device, 4);
+    assert_eq!(device.read(&buf), [0; 4]);
+
+    device.write(&mut buf, &[1, 2, 3, 4]);
+    assert_eq!(buf.read(), [1, 2, 3, 4]);
+
+    let mut other = Buffer::new(&device, 4);
+    WriteBuf::<_, Arena, _>::write_buf(&device, &mut other, &buf);
+    assert_eq!(other.read_to_vec(), vec![1, 2, 3, 4]);
+
+    device.clear(&mut buf);
+    assert_eq!(buf.as_slice(), &[0; 4]);
+}
+
+#[test]
+fn test_arena_device_new() {
+    let err = <Arena as Device>::new().unwrap_err();
+    assert_eq!(err.kind(), Some(&DeviceError::MissingArenaMemory));
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_arena_with_cpu_buffers() {
+    use custos::CPU;
+
+    let mut memory = [0u8; 64];
+    let device = Arena::new(&mut memory);
+
+    let cpu = CPU::new();
+    let cpu_buf = Buffer::from((&cpu, [1f32, 2., 3.]));
+
+    let mut buf = device.try_buf::<f32>(3).unwrap();
+    device.write(&mut buf, &cpu_buf);
+    assert_eq!(device.read(&buf), [1., 2., 3.]);
+
+    // Read, WriteBuf and ClearBuf of the arena work with every MainMemory device
+    let mut cpu_buf = cpu_buf;
+    device.clear(&mut cpu_buf);
+    assert_eq!(device.read(&cpu_buf), [0.; 3]);
+}
+
+#[test]
+fn test_arena_loop() {
+    let mut memory = [0u8; 256];
+    let device = Arena::new(&mut memory);
+
+    let weights = Buffer::from((&device, [2f32; 8]));
+    let used = device.used();
+
+    for i in 0..1000 {
+        let out = device.scope(|scratch| {
+            let mut out = scratch.try_buf::<f32>(8)?;
+            for (out, weight) in out.iter_mut().zip(weights.iter()) {
+                *out = weight * i as f32;
+            }
+            custos::Result::Ok(out.iter().sum::<f32>())
+        });
+        assert_eq!(out.unwrap(), 16. * i as f32);
+    }
+
+    assert_eq!(device.used(), used);
+}
@@ -1,8 +1,5 @@
 use custos::{prelude::*, CommonPtrs, Error};
 
-#[cfg(unified_cl)]
-use custos::MainMemory;
-
 #[cfg(not(feature = "no-std"))]
 #[cfg(not(feature = "realloc"))]
 use custos::{get_count, set_count};
@@ -242,21 +239,28 @@ fn test_debug_print_buf() -> custos::Result<()> {
     Ok(())
 }
 
-#[cfg(unified_cl)]
+#[cfg(feature = "opencl")]
 fn slice_add<T, D: MainMemory>(_lhs: &Buffer<T, D>) {}
 
-#[cfg(unified_cl)]
+#[cfg(feature = "opencl")]
 #[test]
-fn test_slice() {
+fn test_slice() -> custos::Result<()> {
     let device = CPU::new();
 
     let buf = Buffer::from((&device, [1, 2, 3, 4, 5, 6]));
     println!("buf: {:?}", buf.as_slice());
+    slice_add::<i32, _>(&buf);
 
-    let device = custos::OpenCL::new(0).unwrap();
+    // `OpenCL` does not implement `MainMemory`, because unified memory is decided at runtime (see CHANGELOG.md)
+    let device = custos::OpenCL::new(0)?;
     let buf = Buffer::from((&device, [1, 2, 3, 4, 5, 6]));
 
-    slice_add::<i32, _>(&buf);
+    if device.unified_mem() {
+        assert_eq!(buf.try_as_slice()?, &[1, 2, 3, 4, 5, 6]);
+    } else {
+        assert!(buf.try_as_slice().is_err());
+    }
+    Ok(())
 }
 
 #[cfg(feature = "cpu")]
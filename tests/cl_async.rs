@@ -0,0 +1,123 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+use custos::{
+    opencl::{cl_devices, enqueue_kernel_after},
+    Buffer, OpenCL,
+};
+
+// Uses the first device of any type, e.g. a CPU device of POCL.
+fn device() -> custos::Result<OpenCL> {
+    let entry = cl_devices()?.into_iter().next().expect("no OpenCL device");
+    OpenCL::from_cl_device(entry.device)
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// The thread sleeps until a pending event wakes it. Hence, this hangs if an event does not wake its task.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        std::thread::park();
+    }
+}
+
+#[test]
+fn test_read_write_async() -> custos::Result<()> {
+    let device = device()?;
+    let mut buf = Buffer::<i32, _>::new(&device, 1000);
+
+    let input = (0..1000).collect::<Vec<i32>>();
+    let mut output = vec![0; 1000];
+
+    device.async_scope(|scope| {
+        let write = scope.write_async(&mut buf, &input)?;
+
+        // host work, while the data is transferred
+        let sum = input.iter().sum::<i32>();
+        assert_eq!(sum, 499500);
+
+        write.wait()?;
+        let read = scope.read_async(&buf, &mut output)?;
+
+        while !read.is_complete()? {}
+        custos::Result::Ok(())
+    })?;
+
+    assert_eq!(output, input);
+    Ok(())
+}
+
+#[test]
+fn test_await_events() -> custos::Result<()> {
+    let device = device()?;
+    let mut buf = Buffer::<f32, _>::new(&device, 4);
+
+    let mut output = [0.; 4];
+
+    device.async_scope(|scope| {
+        block_on(async {
+            scope.write_async(&mut buf, &[1., 2., 3., 4.])?.await?;
+            scope.read_async(&buf, &mut output)?.await
+        })
+    })?;
+
+    assert_eq!(output, [1., 2., 3., 4.]);
+    Ok(())
+}
+
+#[test]
+fn test_copy_async() -> custos::Result<()> {
+    let device = device()?;
+
+    let source = Buffer::from((&device, [1, 2, 3, 4, 5]));
+    let mut dest = Buffer::<i32, _>::new(&device, 5);
+
+    // Safety: the copy is waited for, before the buffers are accessed again
+    unsafe { device.copy_async(&source, 1..4, &mut dest, 2..5) }?.wait()?;
+    assert_eq!(dest.read(), vec![0, 0, 2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn test_kernel_after_events() -> custos::Result<()> {
+    let device = device()?;
+
+    let src = "
+        __kernel void add(__global const int* lhs, __global int* out) {
+            size_t id = get_global_id(0);
+            out[id] += lhs[id];
+        }
+    ";
+
+    let lhs = Buffer::from((&device, [1, 2, 3, 4]));
+    let mut out = Buffer::<i32, _>::new(&device, 4);
+
+    device.async_scope(|scope| {
+        let write = scope.write_async(&mut out, &[10, 20, 30, 40])?;
+        // Safety: the kernel is waited for, before the buffers are accessed again
+        let kernel = unsafe {
+            enqueue_kernel_after(&device, src, [4, 0, 0], None, &[&lhs, &out], &[&write])
+        }?;
+        kernel.wait()
+    })?;
+
+    assert_eq!(out.read(), vec![11, 22, 33, 44]);
+    Ok(())
+}
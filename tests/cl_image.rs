@@ -0,0 +1,97 @@
+use custos::{
+    opencl::{enqueue_kernel, CLImage, ChannelOrder},
+    Buffer, Dim2, Dim3, OpenCL, WithShape,
+};
+
+#[test]
+fn test_image_write_read() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+
+    let mut image = CLImage::<i32>::new_2d(&device, 3, 2, ChannelOrder::R)?;
+    assert_eq!(image.len(), 6);
+
+    image.write(&[1, 2, 3, 4, 5, 6])?;
+    assert_eq!(image.read()?, vec![1, 2, 3, 4, 5, 6]);
+    Ok(())
+}
+
+#[test]
+fn test_image_rgba() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+
+    let data = (0..16).map(|x| x as u8).collect::<Vec<_>>();
+    let image = CLImage::with_2d(&device, 2, 2, ChannelOrder::RGBA, &data)?;
+    assert_eq!(image.len(), 16);
+    assert_eq!(image.read()?, data);
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+fn test_image_write_wrong_len() {
+    let device = OpenCL::new(0).unwrap();
+    let mut image = CLImage::<f32>::new_2d(&device, 2, 2, ChannelOrder::RG).unwrap();
+    image.write(&[1., 2., 3., 4.]).unwrap();
+}
+
+#[test]
+fn test_image_dim2_roundtrip() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+
+    let buf = Buffer::with(&device, [[1f32, 2., 3., 4.], [5., 6., 7., 8.]]);
+
+    // two pixels with two channels per row
+    let image = CLImage::from_dim2(&buf, ChannelOrder::RG)?;
+    assert_eq!([image.width, image.height, image.depth], [2, 2, 1]);
+    assert_eq!(image.read()?, vec![1., 2., 3., 4., 5., 6., 7., 8.]);
+
+    let out: Buffer<f32, OpenCL, Dim2<2, 4>> = image.to_dim2()?;
+    assert_eq!(
+        out.to_dims::<()>().read_to_vec(),
+        [1., 2., 3., 4., 5., 6., 7., 8.]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_image_dim3_roundtrip() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+
+    let buf = Buffer::from((&device, [1i32, 2, 3, 4, 5, 6, 7, 8])).to_dims::<Dim3<2, 2, 2>>();
+
+    let image = CLImage::from_dim3(&buf, ChannelOrder::R)?;
+    assert_eq!([image.width, image.height, image.depth], [2, 2, 2]);
+
+    let out: Buffer<i32, OpenCL, Dim3<2, 2, 2>> = image.to_dim3()?;
+    assert_eq!(out.to_dims::<()>().read_to_vec(), [1, 2, 3, 4, 5, 6, 7, 8]);
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+fn test_image_to_dim2_wrong_shape() {
+    let device = OpenCL::new(0).unwrap();
+    let image = CLImage::<f32>::new_2d(&device, 2, 3, ChannelOrder::R).unwrap();
+    let _ = image.to_dim2::<2, 3>();
+}
+
+#[test]
+fn test_image_kernel_arg() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+
+    let input = CLImage::with_2d(&device, 2, 1, ChannelOrder::R, &[1i32, 2])?;
+    let out = Buffer::<i32, _>::new(&device, 2);
+
+    let src = "
+        __constant sampler_t sampler = CLK_NORMALIZED_COORDS_FALSE | CLK_ADDRESS_CLAMP | CLK_FILTER_NEAREST;
+
+        __kernel void add_num(__read_only image2d_t input, __global int* out, const int num) {
+            int x = get_global_id(0);
+            out[x] = read_imagei(input, sampler, (int2)(x, 0)).x + num;
+        }
+    ";
+    enqueue_kernel(&device, src, [2, 0, 0], None, &[&input, &out, &3])?;
+
+    assert_eq!(out.read_to_vec(), [4, 5]);
+    Ok(())
+}
@@ -98,3 +98,213 @@ fn test_kernel_launch_2() -> custos::Result<()> {
     assert_eq!(out.read(), vec![-1, -1, -1, -1, -1, -1]);
     Ok(())
 }
+
+#[test]
+fn test_kernel_build_error() -> custos::Result<()> {
+    use custos::{opencl::CLBuildError, ErrorKind};
+
+    let device = OpenCL::new(0)?;
+
+    let src = "
+        __kernel void broken(__global float* out) {
+            out[get_global_id(0)] = undefined_value;
+        }
+    ";
+
+    let out = Buffer::<f32, _>::new(&device, 4);
+    let err = enqueue_kernel(&device, src, [4, 0, 0], None, &[&out]).unwrap_err();
+
+    let build_err = err.kind::<CLBuildError>().unwrap();
+    assert_eq!(build_err.options, "-cl-std=CL1.2 -cl-kernel-arg-info");
+    assert_eq!(build_err.src, src);
+    assert!(build_err.log.contains("undefined_value"));
+    assert!(build_err
+        .numbered_src()
+        .contains("3 |             out[get_global_id(0)] = undefined_value;"));
+    Ok(())
+}
+
+#[test]
+fn test_kernel_build_options() -> custos::Result<()> {
+    use custos::opencl::{enqueue_kernel_with_options, BuildOptions};
+
+    let device = OpenCL::new(0)?;
+
+    let src = "
+        __kernel void fill(__global int* out) {
+            out[get_global_id(0)] = VALUE;
+        }
+    ";
+
+    let out = Buffer::<i32, _>::new(&device, 4);
+
+    for value in [3, 7] {
+        let options = BuildOptions::new().define("VALUE", value);
+        enqueue_kernel_with_options(&device, src, &options, [4, 0, 0], None, &[&out])?;
+        assert_eq!(out.read(), vec![value; 4]);
+    }
+
+    // VALUE is not defined
+    assert!(enqueue_kernel(&device, src, [4, 0, 0], None, &[&out]).is_err());
+    assert_eq!(device.kernel_cache.borrow().programs.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_named_kernels() -> custos::Result<()> {
+    use custos::{
+        opencl::{enqueue_named_kernel, BuildOptions},
+        DeviceError, ErrorKind,
+    };
+
+    let device = OpenCL::new(0)?;
+
+    let src = "
+        __kernel void add(__global const int* lhs, __global const int* rhs, __global int* out) {
+            size_t id = get_global_id(0);
+            out[id] = lhs[id] + rhs[id];
+        }
+
+        __kernel void mul(__global const int* lhs, __global const int* rhs, __global int* out) {
+            size_t id = get_global_id(0);
+            out[id] = lhs[id] * rhs[id];
+        }
+    ";
+
+    let lhs = Buffer::from((&device, [1, 2, 3]));
+    let rhs = Buffer::from((&device, [4, 5, 6]));
+    let out = Buffer::<i32, _>::new(&device, 3);
+
+    let options = BuildOptions::default();
+    let gws = [3, 0, 0];
+
+    enqueue_named_kernel(
+        &device,
+        src,
+        "mul",
+        &options,
+        gws,
+        None,
+        &[&lhs, &rhs, &out],
+    )?;
+    assert_eq!(out.read(), vec![4, 10, 18]);
+
+    enqueue_named_kernel(
+        &device,
+        src,
+        "add",
+        &options,
+        gws,
+        None,
+        &[&lhs, &rhs, &out],
+    )?;
+    assert_eq!(out.read(), vec![5, 7, 9]);
+
+    assert_eq!(device.kernel_cache.borrow().programs.len(), 1);
+
+    let err = enqueue_named_kernel(&device, src, "sub", &options, gws, None, &[]).unwrap_err();
+    assert_eq!(err.kind(), Some(&DeviceError::MissingKernel));
+
+    let err = enqueue_kernel(&device, src, gws, None, &[&lhs, &rhs, &out]).unwrap_err();
+    assert_eq!(err.kind(), Some(&DeviceError::AmbiguousKernel));
+    Ok(())
+}
+
+#[test]
+fn test_binary_cache() -> custos::Result<()> {
+    use custos::opencl::BinaryCache;
+
+    let dir = std::env::temp_dir().join(format!("custos-cl-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let src = "
+        __kernel void square(__global int* x) {
+            size_t id = get_global_id(0);
+            x[id] = x[id] * x[id];
+        }
+    ";
+
+    let run = || -> custos::Result<Vec<i32>> {
+        let device = OpenCL::new(0)?;
+        device.set_binary_cache(Some(BinaryCache::new(&dir)));
+
+        let buf = Buffer::from((&device, [1, 2, 3]));
+        enqueue_kernel(&device, src, [3, 0, 0], None, &[&buf])?;
+        Ok(buf.read_to_vec())
+    };
+
+    // compiled from source
+    assert_eq!(run()?, vec![1, 4, 9]);
+
+    let files = std::fs::read_dir(&dir)?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(files.len(), 1);
+
+    // loaded from the binary
+    assert_eq!(run()?, vec![1, 4, 9]);
+
+    // an invalid binary is replaced
+    std::fs::write(files[0].path(), b"invalid")?;
+    assert_eq!(run()?, vec![1, 4, 9]);
+    assert_ne!(std::fs::read(files[0].path())?, b"invalid");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_kernel_arg_validation() -> custos::Result<()> {
+    use custos::{
+        opencl::{AddressSpace, KernelArgError},
+        ErrorKind,
+    };
+
+    let device = OpenCL::new(0)?;
+    let buf = Buffer::<f32, _>::new(&device, 4);
+
+    let src = "
+        __kernel void fill(__global float* out, const float value) {
+            out[get_global_id(0)] = value;
+        }
+    ";
+
+    let err = enqueue_kernel(&device, src, [4, 0, 0], None, &[&buf]).unwrap_err();
+    assert_eq!(
+        err.kind::<KernelArgError>(),
+        Some(&KernelArgError::Count {
+            kernel: "fill".into(),
+            expected: 2,
+            provided: 1
+        })
+    );
+
+    let kernel = device
+        .kernel_cache
+        .borrow_mut()
+        .kernel_cache(&device, src)?;
+    let signature = &kernel.signature;
+    assert_eq!(signature.num_args, 2);
+
+    // the argument info may not be provided by every OpenCL implementation
+    if let Some(params) = &signature.params {
+        assert_eq!(params[0].address_space, AddressSpace::Global);
+        assert_eq!(params[1].scalar_size(), Some(4));
+
+        // a number instead of a buffer
+        let err = enqueue_kernel(&device, src, [4, 0, 0], None, &[&1i32, &2f32]).unwrap_err();
+        assert!(matches!(
+            err.kind::<KernelArgError>(),
+            Some(KernelArgError::Mismatch { idx: 0, .. })
+        ));
+
+        // a double instead of a float
+        let err = enqueue_kernel(&device, src, [4, 0, 0], None, &[&buf, &2f64]).unwrap_err();
+        assert!(matches!(
+            err.kind::<KernelArgError>(),
+            Some(KernelArgError::Mismatch { idx: 1, .. })
+        ));
+    }
+
+    enqueue_kernel(&device, src, [4, 0, 0], None, &[&buf, &2f32])?;
+    assert_eq!(buf.read(), vec![2.; 4]);
+    Ok(())
+}
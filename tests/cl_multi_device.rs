@@ -0,0 +1,194 @@
+use custos::{
+    opencl::{cl_devices, enqueue_kernel, enqueue_kernel_split, SplitArg},
+    Buffer, DeviceError, ErrorKind, OpenCL, Read,
+};
+use min_cl::api::OCLErrorKind;
+
+const ADD_ONE: &str = "
+    __kernel void add_one(__global int* x) {
+        x[get_global_id(0)] += 1;
+    }
+";
+
+/// Two devices in one context. If there is only one device, it is used twice.
+fn shared_devices() -> custos::Result<Vec<OpenCL>> {
+    let entries = cl_devices()?;
+    let first = &entries[0];
+    let second = entries[1..]
+        .iter()
+        .find(|entry| entry.platform_idx == first.platform_idx)
+        .unwrap_or(first);
+
+    OpenCL::with_shared_context(&[first.device, second.device])
+}
+
+/// Two different devices of the same platform in one context, if there are any.
+fn distinct_devices() -> custos::Result<Option<Vec<OpenCL>>> {
+    let entries = cl_devices()?;
+
+    for (idx, first) in entries.iter().enumerate() {
+        let second = entries[idx + 1..].iter().find(|entry| {
+            entry.platform_idx == first.platform_idx && entry.device.0 != first.device.0
+        });
+
+        if let Some(second) = second {
+            return OpenCL::with_shared_context(&[first.device, second.device]).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+#[test]
+fn test_shared_context_buffers() -> custos::Result<()> {
+    let devices = shared_devices()?;
+    assert_eq!(devices.len(), 2);
+    assert!(devices[0].shares_context(&devices[1]));
+
+    let buf = Buffer::from((&devices[0], [1, 2, 3]));
+
+    enqueue_kernel(&devices[1], ADD_ONE, [3, 0, 0], None, &[&buf])?;
+    enqueue_kernel(&devices[0], ADD_ONE, [3, 0, 0], None, &[&buf])?;
+
+    assert_eq!(buf.read(), vec![3, 4, 5]);
+    Ok(())
+}
+
+#[test]
+fn test_shared_context_outlives_devices() -> custos::Result<()> {
+    let mut devices = shared_devices()?;
+    let second = devices.pop().unwrap();
+    drop(devices);
+
+    // the context is released by the last device only
+    let buf = Buffer::from((&second, [1, 2, 3]));
+    assert_eq!(buf.read(), vec![1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn test_copy_peer() -> custos::Result<()> {
+    let devices = shared_devices()?;
+
+    let source = Buffer::from((&devices[0], [1, 2, 3, 4]));
+    let mut dest = Buffer::<i32, _>::new(&devices[1], 4);
+
+    devices[1].copy_peer(&source, &mut dest)?;
+    assert_eq!(dest.read(), vec![1, 2, 3, 4]);
+
+    let cloned = devices[1].clone_from_peer(&source)?;
+    assert_eq!(cloned.read(), vec![1, 2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn test_copy_peer_separate_contexts() -> custos::Result<()> {
+    let first = OpenCL::new(0)?;
+    let second = OpenCL::new(0)?;
+    assert!(!first.shares_context(&second));
+
+    // transferred through the host
+    let source = Buffer::from((&first, [1, 2, 3]));
+    let cloned = second.clone_from_peer(&source)?;
+    assert_eq!(cloned.read(), vec![1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn test_enqueue_kernel_split() -> custos::Result<()> {
+    let devices = shared_devices()?;
+
+    let len = 1001;
+    let x = Buffer::<i32, _>::from((&devices[0], (0..len as i32).collect::<Vec<_>>()));
+    let out = Buffer::<i32, _>::new(&devices[0], len);
+
+    let src = "
+        __kernel void add(__global const int* x, __global int* out, int value) {
+            size_t id = get_global_id(0);
+            out[id] = x[id] + value;
+        }
+    ";
+    enqueue_kernel_split(
+        &devices,
+        src,
+        [len, 0, 0],
+        None,
+        &[
+            SplitArg::split(&x),
+            SplitArg::split(&out),
+            SplitArg::shared(&2),
+        ],
+    )?;
+
+    assert_eq!(out.read(), (2..len as i32 + 2).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn test_enqueue_kernel_split_distinct_devices() -> custos::Result<()> {
+    let Some(devices) = distinct_devices()? else {
+        println!("skipping: no platform with two devices");
+        return Ok(());
+    };
+
+    let len = 4096;
+    let buf = Buffer::<i32, _>::from((&devices[0], vec![0; len]));
+
+    // the parts of every launch write to disjoint sub-buffers from two devices at the same time
+    for _ in 0..10 {
+        enqueue_kernel_split(
+            &devices,
+            ADD_ONE,
+            [len, 0, 0],
+            None,
+            &[SplitArg::split(&buf)],
+        )?;
+    }
+
+    assert_eq!(buf.read(), vec![10; len]);
+    assert_eq!(devices[1].read_to_vec(&buf), vec![10; len]);
+    Ok(())
+}
+
+#[test]
+fn test_enqueue_kernel_split_local_size() -> custos::Result<()> {
+    let devices = shared_devices()?;
+    let buf = Buffer::<i32, _>::from((&devices[0], vec![0; 256]));
+
+    enqueue_kernel_split(
+        &devices,
+        ADD_ONE,
+        [256, 0, 0],
+        Some([32, 1, 1]),
+        &[SplitArg::split(&buf)],
+    )?;
+    assert_eq!(buf.read(), vec![1; 256]);
+    Ok(())
+}
+
+#[test]
+fn test_enqueue_kernel_split_separate_contexts() -> custos::Result<()> {
+    let devices = [OpenCL::new(0)?, OpenCL::new(0)?];
+    let buf = Buffer::<i32, _>::from((&devices[0], vec![0; 4]));
+
+    let err = enqueue_kernel_split(&devices, ADD_ONE, [4, 0, 0], None, &[SplitArg::split(&buf)])
+        .unwrap_err();
+    assert_eq!(
+        err.kind::<DeviceError>(),
+        Some(&DeviceError::NoSharedContext)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_enqueue_kernel_split_short_buffer() -> custos::Result<()> {
+    let devices = shared_devices()?;
+    let buf = Buffer::<i32, _>::from((&devices[0], vec![0; 4]));
+
+    let err = enqueue_kernel_split(&devices, ADD_ONE, [8, 0, 0], None, &[SplitArg::split(&buf)])
+        .unwrap_err();
+    assert_eq!(
+        err.kind::<OCLErrorKind>(),
+        Some(&OCLErrorKind::InvalidBufferSize)
+    );
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use custos::{
+    opencl::{cl_devices, enqueue_kernel, CommandKind},
+    Buffer, CopySlice, OpenCL, WriteBuf,
+};
+
+// Uses the first device of any type, e.g. a CPU device of POCL.
+fn device() -> custos::Result<OpenCL> {
+    let entry = cl_devices()?.into_iter().next().expect("no OpenCL device");
+    OpenCL::from_cl_device(entry.device)
+}
+
+const SRC: &str = "
+    __kernel void scale(__global float* x, const float factor) {
+        size_t id = get_global_id(0);
+        x[id] *= factor;
+    }
+";
+
+#[test]
+fn test_profile_commands() -> custos::Result<()> {
+    let device = device()?;
+    assert!(!device.profiling());
+
+    device.enable_profiling()?;
+    assert!(device.profiling());
+
+    let mut buf = Buffer::<f32, _>::new(&device, 256);
+    device.write(&mut buf, &[1.; 256]);
+
+    for _ in 0..3 {
+        enqueue_kernel(&device, SRC, [256, 0, 0], None, &[&buf, &2f32])?;
+    }
+
+    let mut copy = Buffer::<f32, _>::new(&device, 256);
+    device.copy_slice_to(&buf, .., &mut copy, ..);
+    assert_eq!(copy.read_to_vec(), vec![8.; 256]);
+
+    let report = device.profile_report()?;
+    let kinds = report
+        .records
+        .iter()
+        .map(|record| record.kind)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        kinds,
+        [
+            CommandKind::Write,
+            CommandKind::Kernel,
+            CommandKind::Kernel,
+            CommandKind::Kernel,
+            CommandKind::Copy,
+            CommandKind::Read
+        ]
+    );
+    assert_eq!(report.records[1].name, "scale");
+
+    for record in &report.records {
+        assert!(record.queued <= record.submit);
+        assert!(record.submit <= record.start);
+        assert!(record.start <= record.end);
+    }
+
+    let summary = report.summary();
+    let scale = summary
+        .iter()
+        .find(|summary| summary.name == "scale")
+        .unwrap();
+    assert_eq!(scale.count, 3);
+
+    assert!(report.to_chrome_trace().contains("\"name\":\"scale\""));
+
+    // the records are cleared
+    assert!(device.profile_report()?.records.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_profile_disabled() -> custos::Result<()> {
+    let device = device()?;
+
+    let buf = Buffer::<f32, _>::from((&device, [1., 2.]));
+    enqueue_kernel(&device, SRC, [2, 0, 0], None, &[&buf, &3f32])?;
+    assert_eq!(buf.read(), vec![3., 6.]);
+
+    assert!(device.profile_report()?.records.is_empty());
+    Ok(())
+}
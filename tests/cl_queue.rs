@@ -0,0 +1,126 @@
+use custos::{
+    opencl::{enqueue_kernel, enqueue_kernel_on, CommandKind},
+    Buffer, OpenCL,
+};
+
+const ADD_ONE: &str = "
+    __kernel void add_one(__global int* x) {
+        x[get_global_id(0)] += 1;
+    }
+";
+
+#[test]
+fn test_queue_transfers() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    let queue = device.create_queue(false)?;
+    assert!(!queue.is_out_of_order());
+
+    let mut buf = Buffer::<i32, _>::new(&device, 4);
+    queue.write(&mut buf, &[1, 2, 3, 4], &[])?;
+
+    // the buffer is usable with the device queue as well
+    assert_eq!(buf.read(), vec![1, 2, 3, 4]);
+    assert_eq!(queue.read(&buf, &[])?, vec![1, 2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn test_queue_cross_queue_dependency() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    let first = device.create_queue(false)?;
+    let second = device.create_queue(false)?;
+
+    let buf = Buffer::from((&device, [1, 2, 3]));
+    let mut out = Buffer::<i32, _>::new(&device, 3);
+
+    // Safety: the blocking read waits for the copy, which waits for the kernel
+    let copy = unsafe {
+        let add = enqueue_kernel_on(&first, ADD_ONE, [3, 0, 0], None, &[&buf], &[])?;
+        second.copy(&buf, .., &mut out, .., &[&add])?
+    };
+
+    assert_eq!(first.read(&out, &[&copy])?, vec![2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn test_queue_depends_on_device_queue() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    let queue = device.create_queue(true)?;
+
+    let buf = Buffer::from((&device, [1, 2, 3]));
+    let mut out = Buffer::<i32, _>::new(&device, 3);
+
+    // Safety: `out` is only accessed after the kernel, which waits for the copy, has finished
+    unsafe {
+        let copy = device.copy_async(&buf, .., &mut out, ..)?;
+        enqueue_kernel_on(&queue, ADD_ONE, [3, 0, 0], None, &[&out], &[&copy])?.wait()?;
+    }
+
+    assert_eq!(out.read(), vec![2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn test_out_of_order_queue_barrier() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    let queue = device.create_queue(true)?;
+    assert!(queue.is_out_of_order());
+
+    let buf = Buffer::from((&device, [0; 16]));
+
+    for _ in 0..4 {
+        // Safety: `buf` is only read after the marker, which waits for all kernels of the queue
+        unsafe { enqueue_kernel_on(&queue, ADD_ONE, [16, 0, 0], None, &[&buf], &[]) }?;
+        // without the barrier, the kernels could run concurrently
+        queue.barrier(&[])?;
+    }
+
+    queue.marker(&[])?.wait()?;
+    assert_eq!(buf.read(), vec![4; 16]);
+    Ok(())
+}
+
+#[test]
+fn test_queue_marker_joins_queues() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    let first = device.create_queue(true)?;
+    let second = device.create_queue(true)?;
+
+    let lhs = Buffer::from((&device, [1, 2]));
+    let rhs = Buffer::from((&device, [3, 4]));
+
+    // Safety: the buffers are only read after the marker, which waits for both kernels
+    let a = unsafe { enqueue_kernel_on(&first, ADD_ONE, [2, 0, 0], None, &[&lhs], &[]) }?;
+    let b = unsafe { enqueue_kernel_on(&second, ADD_ONE, [2, 0, 0], None, &[&rhs], &[]) }?;
+
+    first.marker(&[&a, &b])?.wait()?;
+
+    assert_eq!(lhs.read(), vec![2, 3]);
+    assert_eq!(rhs.read(), vec![4, 5]);
+    Ok(())
+}
+
+#[test]
+fn test_queue_profiling() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    device.enable_profiling()?;
+
+    let queue = device.create_queue(true)?;
+    let buf = Buffer::from((&device, [1, 2, 3]));
+
+    // both launches write to `buf`, hence the second one must not start before the first one is finished
+    // Safety: the kernel is waited for, before `buf` is used again
+    unsafe { enqueue_kernel_on(&queue, ADD_ONE, [3, 0, 0], None, &[&buf], &[]) }?.wait()?;
+    enqueue_kernel(&device, ADD_ONE, [3, 0, 0], None, &[&buf])?;
+
+    let report = device.profile_report()?;
+    assert_eq!(report.records.len(), 2);
+    assert!(report
+        .records
+        .iter()
+        .all(|record| record.kind == CommandKind::Kernel && record.name == "add_one"));
+
+    assert_eq!(buf.read_to_vec(), [3, 4, 5]);
+    Ok(())
+}
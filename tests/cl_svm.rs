@@ -0,0 +1,98 @@
+use custos::{
+    opencl::{enqueue_kernel, CoarseGrained, FineGrained, SVM},
+    Buffer, Device, DeviceError, ErrorKind, OpenCL, WriteBuf,
+};
+
+const ADD_ONE: &str = "
+    __kernel void add_one(__global int* x) {
+        x[get_global_id(0)] += 1;
+    }
+";
+
+#[test]
+fn test_svm_fine_grained() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    if !SVM::<FineGrained>::is_supported(&device) {
+        return Ok(());
+    }
+    let svm = SVM::fine(&device)?;
+
+    let mut buf = Buffer::from((&svm, [1, 2, 3, 4]));
+    buf[1] = 10;
+
+    enqueue_kernel(&device, ADD_ONE, [4, 0, 0], None, &[&buf])?;
+
+    // the host accesses the memory directly
+    assert_eq!(buf.as_slice(), [2, 11, 4, 5]);
+    assert_eq!(buf.read(), [2, 11, 4, 5]);
+    Ok(())
+}
+
+#[test]
+fn test_svm_coarse_grained_map() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    if !SVM::<CoarseGrained>::is_supported(&device) {
+        return Ok(());
+    }
+    let svm = SVM::coarse(&device)?;
+
+    let mut buf = Buffer::<i32, _>::new(&svm, 4);
+    assert_eq!(*buf.map()?, [0; 4]);
+
+    buf.map_mut()?.copy_from_slice(&[1, 2, 3, 4]);
+    enqueue_kernel(&device, ADD_ONE, [4, 0, 0], None, &[&buf])?;
+
+    assert_eq!(*buf.map()?, [2, 3, 4, 5]);
+    assert_eq!(buf.read(), vec![2, 3, 4, 5]);
+    Ok(())
+}
+
+#[test]
+fn test_svm_write() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    if !SVM::<CoarseGrained>::is_supported(&device) {
+        return Ok(());
+    }
+    let svm = SVM::coarse(&device)?;
+
+    let mut buf = Buffer::<f32, _>::new(&svm, 3);
+    svm.write(&mut buf, &[1., 2., 3.]);
+
+    let mut copy = Buffer::<f32, _>::new(&svm, 3);
+    svm.write_buf(&mut copy, &buf);
+    assert_eq!(copy.read(), vec![1., 2., 3.]);
+    Ok(())
+}
+
+#[test]
+fn test_svm_mixed_with_cl_buffers() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    if !SVM::<CoarseGrained>::is_supported(&device) {
+        return Ok(());
+    }
+    let svm = SVM::coarse(&device)?;
+
+    let lhs = Buffer::from((&svm, [1, 2, 3]));
+    let rhs = Buffer::from((&device, [4, 5, 6]));
+    let out = Buffer::<i32, _>::new(&device, 3);
+
+    let src = "
+        __kernel void add(__global const int* lhs, __global const int* rhs, __global int* out) {
+            size_t id = get_global_id(0);
+            out[id] = lhs[id] + rhs[id];
+        }
+    ";
+    enqueue_kernel(&device, src, [3, 0, 0], None, &[&lhs, &rhs, &out])?;
+
+    assert_eq!(out.read(), vec![5, 7, 9]);
+    Ok(())
+}
+
+#[test]
+fn test_svm_device_new() {
+    let err = SVM::<FineGrained>::new().unwrap_err();
+    assert_eq!(
+        err.kind::<DeviceError>(),
+        Some(&DeviceError::MissingSvmDevice)
+    );
+}
@@ -0,0 +1,75 @@
+use custos::{
+    opencl::{enqueue_kernel1d, enqueue_kernel_tuned, Autotuner, WorkGroupInfo},
+    Buffer, OpenCL,
+};
+
+const SCALE: &str = "
+    __kernel void scale(__global const float* x, __global float* out, const int len) {
+        size_t id = get_global_id(0);
+        if (id >= len) {
+            return;
+        }
+        out[id] = x[id] * 2;
+    }
+";
+
+#[test]
+fn test_work_group_info() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    let kernel = device
+        .kernel_cache
+        .borrow_mut()
+        .kernel_cache(&device, SCALE)?;
+
+    let info = WorkGroupInfo::new(&device, &kernel)?;
+    assert!(info.max_size > 0);
+    assert!(info.preferred_multiple > 0);
+
+    let (gws, lws) = info.launch_config_1d(1000);
+    assert!(gws[0] >= 1000);
+    assert_eq!(gws[0] % lws[0], 0);
+    assert!(lws[0] <= info.max_size);
+    Ok(())
+}
+
+#[test]
+fn test_enqueue_kernel1d_padded() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+
+    // a prime length, which is never a multiple of the work-group size
+    let len = 1009;
+    let x = Buffer::<f32, _>::from((&device, vec![1.; len]));
+    let out = Buffer::<f32, _>::new(&device, len);
+
+    enqueue_kernel1d(&device, SCALE, len, &[&x, &out, &(len as i32)])?;
+    assert_eq!(out.read(), vec![2.; len]);
+    Ok(())
+}
+
+#[test]
+fn test_enqueue_kernel_tuned_persists() -> custos::Result<()> {
+    let file = std::env::temp_dir().join(format!("custos-tune-{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&file);
+
+    let device = OpenCL::new(0)?;
+    device.set_autotuner(Autotuner::with_file(&file));
+
+    let x = Buffer::<f32, _>::from((&device, vec![1.; 512]));
+    let out = Buffer::<f32, _>::new(&device, 512);
+
+    enqueue_kernel_tuned(&device, SCALE, [512, 0, 0], &[&x, &out, &512])?;
+    assert_eq!(out.read(), vec![2.; 512]);
+    assert_eq!(device.autotuner.borrow().len(), 1);
+
+    // another problem size is tuned separately
+    enqueue_kernel_tuned(&device, SCALE, [256, 0, 0], &[&x, &out, &256])?;
+    assert_eq!(device.autotuner.borrow().len(), 2);
+
+    // the results are loaded by a new autotuner
+    let loaded = Autotuner::with_file(&file);
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.file(), Some(file.as_path()));
+
+    std::fs::remove_file(&file).unwrap();
+    Ok(())
+}
@@ -38,3 +38,13 @@ fn test_clear_cuda() -> Result<(), custos::Error> {
     assert_eq!(buf.read(), vec![0.; 6]);
     Ok(())
 }
+
+#[cfg(feature = "stack")]
+#[test]
+fn test_clear_stack() {
+    use custos::{ClearBuf, Dim1, Stack};
+
+    let mut buf = Buffer::<_, Stack, Dim1<6>>::from((&Stack, [1., 2., 3., 4., 5., 6.]));
+    Stack.clear(&mut buf);
+    assert_eq!(buf.read(), [0.; 6]);
+}
@@ -0,0 +1,167 @@
+use custos::prelude::*;
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_copy_rect_cpu() {
+    let device = CPU::new();
+    let source =
+        Buffer::<i32, _, Dim2<3, 4>>::with(&device, [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]]);
+
+    let mut dest = Buffer::<i32, _, Dim2<3, 3>>::new(&device, 9);
+    device.copy_rect(
+        &source,
+        Rect::dim2::<3, 4>([0, 1]),
+        &mut dest,
+        Rect::dim2::<3, 3>([1, 1]),
+        [2, 2, 1],
+    );
+
+    assert_eq!(dest.read(), [0, 0, 0, 0, 2, 3, 0, 6, 7]);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_read_write_rect_cpu() {
+    let device = CPU::new();
+    let mut buf = Buffer::<i32, CPU>::new(&device, 8);
+
+    // two 2x2 slices into a 2x2x2 array
+    device.write_rect(
+        &mut buf,
+        Rect::packed(),
+        &[1, 2, 0, 3, 4, 0, 5, 6, 0, 7, 8, 0],
+        Rect::new([0, 0, 0], 3, 6),
+        [2, 2, 2],
+    );
+    assert_eq!(buf.read(), [1, 2, 3, 4, 5, 6, 7, 8]);
+
+    // the second column of every slice
+    let mut host = [0; 4];
+    device.read_rect(
+        &buf,
+        Rect::new([1, 0, 0], 2, 4),
+        &mut host,
+        Rect::packed(),
+        [1, 2, 2],
+    );
+    assert_eq!(host, [2, 4, 6, 8]);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_sub_matrix_cpu() {
+    let device = CPU::new();
+    let mut matrix =
+        Buffer::<i32, _, Dim2<3, 4>>::with(&device, [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]]);
+
+    let sub = matrix.sub_matrix::<2, 3>([1, 1]);
+    assert_eq!(sub.read(), [6, 7, 8, 10, 11, 12]);
+
+    let ones = Buffer::<i32, _, Dim2<1, 2>>::with(&device, [[-1, -1]]);
+    matrix.set_sub_matrix([0, 2], &ones);
+    assert_eq!(matrix.read(), [1, 2, -1, -1, 5, 6, 7, 8, 9, 10, 11, 12]);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_sub_block_cpu() {
+    let device = CPU::new();
+    let mut array =
+        Buffer::from((&device, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])).to_dims::<Dim3<2, 2, 3>>();
+
+    let block = array.sub_block::<2, 2, 1>([0, 0, 2]);
+    assert_eq!(block.read(), [3, 6, 9, 12]);
+
+    let zeros = Buffer::<i32, _, Dim3<1, 2, 2>>::new(&device, 4);
+    array.set_sub_block([1, 0, 1], &zeros);
+    assert_eq!(array.read(), [1, 2, 3, 4, 5, 6, 7, 0, 0, 10, 0, 0]);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+#[should_panic]
+fn test_copy_rect_out_of_bounds_cpu() {
+    let device = CPU::new();
+    let matrix = Buffer::<i32, _, Dim2<2, 2>>::with(&device, [[1, 2], [3, 4]]);
+    matrix.sub_matrix::<2, 2>([1, 0]);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+#[should_panic]
+fn test_copy_rect_column_out_of_bounds_cpu() {
+    let device = CPU::new();
+    let matrix =
+        Buffer::<i32, _, Dim2<3, 4>>::with(&device, [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]]);
+
+    // the columns 3 and 4 of the first row would wrap into the second row
+    matrix.sub_matrix::<1, 2>([0, 3]);
+}
+
+#[cfg(feature = "cpu")]
+#[test]
+#[should_panic]
+fn test_copy_rect_slice_out_of_bounds_cpu() {
+    let device = CPU::new();
+    let array =
+        Buffer::from((&device, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])).to_dims::<Dim3<2, 2, 3>>();
+
+    // the rows 2 and 3 of the first matrix would wrap into the second matrix
+    array.sub_block::<1, 2, 3>([0, 1, 0]);
+}
+
+#[test]
+fn test_rect_checked_index() {
+    let rect = Rect::new([0, 0, usize::MAX], 4, 8);
+    assert_eq!(rect.checked_index([1, 1, 1], [0, 0, 0]), None);
+    assert_eq!(
+        Rect::dim2::<3, 4>([1, 2]).checked_index([2, 2, 1], [1, 1, 0]),
+        Some(11)
+    );
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_sub_matrix_cl() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    let mut matrix =
+        Buffer::<i32, _, Dim2<3, 4>>::with(&device, [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]]);
+
+    let sub = matrix.sub_matrix::<2, 3>([1, 1]);
+    assert_eq!(sub.to_dims::<()>().read_to_vec(), [6, 7, 8, 10, 11, 12]);
+
+    let ones = Buffer::<i32, _, Dim2<1, 2>>::with(&device, [[-1, -1]]);
+    matrix.set_sub_matrix([0, 2], &ones);
+    assert_eq!(
+        matrix.to_dims::<()>().read_to_vec(),
+        [1, 2, -1, -1, 5, 6, 7, 8, 9, 10, 11, 12]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_read_write_rect_cl() -> custos::Result<()> {
+    let device = OpenCL::new(0)?;
+    let mut buf = Buffer::<i32, OpenCL>::new(&device, 8);
+
+    device.write_rect(
+        &mut buf,
+        Rect::packed(),
+        &[1, 2, 0, 3, 4, 0, 5, 6, 0, 7, 8, 0],
+        Rect::new([0, 0, 0], 3, 6),
+        [2, 2, 2],
+    );
+    assert_eq!(buf.read_to_vec(), [1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let mut host = [0; 4];
+    device.read_rect(
+        &buf,
+        Rect::new([1, 0, 0], 2, 4),
+        &mut host,
+        Rect::packed(),
+        [1, 2, 2],
+    );
+    assert_eq!(host, [2, 4, 6, 8]);
+    Ok(())
+}
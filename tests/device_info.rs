@@ -0,0 +1,71 @@
+#![cfg(not(feature = "no-std"))]
+
+use custos::{available_devices, Backend, DeviceInfo};
+
+#[cfg(feature = "cpu")]
+#[test]
+fn test_cpu_info() -> custos::Result<()> {
+    use custos::CPU;
+
+    let device = CPU::new();
+    let info = device.info()?;
+
+    assert_eq!(info.backend, Backend::CPU);
+    assert!(!info.name.is_empty());
+    assert!(info.unified_mem);
+    assert!(info.f64_support);
+    Ok(())
+}
+
+#[cfg(feature = "stack")]
+#[test]
+fn test_stack_info() -> custos::Result<()> {
+    use custos::Stack;
+
+    let info = Stack.info()?;
+    assert_eq!(info.backend, Backend::Stack);
+    assert_eq!(info.global_mem_size, None);
+    Ok(())
+}
+
+#[test]
+fn test_available_devices() {
+    let devices = available_devices();
+
+    #[cfg(feature = "cpu")]
+    assert_eq!(devices[0].backend, Backend::CPU);
+
+    for props in devices {
+        assert!(!props.name.is_empty());
+    }
+}
+
+#[cfg(feature = "opencl")]
+#[test]
+fn test_cl_devices() -> custos::Result<()> {
+    use custos::{opencl::cl_devices, Buffer, OpenCL};
+
+    for entry in cl_devices()? {
+        assert_eq!(entry.props.backend, Backend::OpenCL);
+
+        let device = OpenCL::from_cl_device(entry.device)?;
+        assert_eq!(device.info()?, entry.props);
+
+        let buf = Buffer::from((&device, [1, 2, 3]));
+        assert_eq!(buf.read(), vec![1, 2, 3]);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "wgpu")]
+#[test]
+fn test_wgpu_adapters() -> custos::Result<()> {
+    use custos::{wgpu::wgpu_adapters, WGPU};
+
+    if let Some(entry) = wgpu_adapters(wgpu::Backends::all()).into_iter().next() {
+        let props = entry.props.clone();
+        let device = WGPU::from_adapter(entry.adapter)?;
+        assert_eq!(device.info()?, props);
+    }
+    Ok(())
+}
@@ -41,7 +41,6 @@ fn test_questionmark() -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
     Ok(())
 }
 
-#[cfg(not(feature = "no-std"))]
 #[test]
 fn test_print_error() {
     let err = Error::from(DeviceError::ConstructError);
@@ -55,6 +54,14 @@ fn test_print_error() {
     );
 }
 
+#[test]
+fn test_error_kind() {
+    use custos::ErrorKind;
+
+    let err = Error::from(DeviceError::GraphCapacity);
+    assert_eq!(err.kind(), Some(&DeviceError::GraphCapacity));
+}
+
 #[cfg(not(feature = "no-std"))]
 #[test]
 fn test_std_err() {
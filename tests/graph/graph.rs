@@ -101,3 +101,37 @@ fn test_graph_cu() -> custos::Result<()> {
     }
     Ok(())
 }
+
+#[cfg(feature = "wgpu")]
+#[test]
+fn test_graph_wgpu() -> custos::Result<()> {
+    use custos::WGPU;
+
+    let device = WGPU::new(wgpu::Backends::all())?;
+
+    // idx: 0
+    let a = Buffer::from((&device, [1, 2, 3, 4, 5, 6]));
+    // idx: 1
+    let b = Buffer::from((&device, [2, 3, 1, 4, 0, 5]));
+
+    for ep in range(1) {
+        // idx: 2, deps: [0, 1]
+        let c = a.add(&b);
+        assert_eq!(vec![3, 5, 4, 8, 5, 11], c.read());
+
+        // idx: 3, deps: [2, 2]
+        let d = c.relu();
+
+        assert_eq!(vec![3, 5, 4, 8, 5, 11], d.read());
+
+        // idx: 4, deps: [3, 1]
+        let e = d.add(&b);
+
+        if ep == 1 {
+            assert_eq!(c.ptr.ptr, d.ptr.ptr);
+            assert_eq!(c.ptr.ptr, e.ptr.ptr);
+        }
+        device.optimize()?;
+    }
+    Ok(())
+}
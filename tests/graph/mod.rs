@@ -7,12 +7,15 @@ use custos::{opencl::enqueue_kernel, OpenCL};
 mod graph;
 
 #[cfg(not(feature = "realloc"))]
-#[cfg(unified_cl)]
+#[cfg(feature = "opencl")]
 mod to_unified;
 
 #[cfg(feature = "cuda")]
 use custos::{cuda::launch_kernel1d, CUDA};
 
+#[cfg(feature = "wgpu")]
+use custos::{wgpu::launch_shader, WGPU};
+
 pub trait AddBuf<T, D: Device>: Device {
     fn add(&self, lhs: &Buffer<T, D>, rhs: &Buffer<T, D>) -> Buffer<T, Self>;
     fn relu(&self, lhs: &Buffer<T, D>) -> Buffer<T, Self>;
@@ -119,6 +122,82 @@ impl<T: CDatatype> AddBuf<T, CUDA> for CUDA {
     }
 }
 
+/// The scalar types, which can be used in WGSL shaders, and their names in WGSL.
+#[cfg(feature = "wgpu")]
+pub trait WGSLType: CDatatype {
+    const NAME: &'static str;
+}
+
+#[cfg(feature = "wgpu")]
+impl WGSLType for f32 {
+    const NAME: &'static str = "f32";
+}
+
+#[cfg(feature = "wgpu")]
+impl WGSLType for i32 {
+    const NAME: &'static str = "i32";
+}
+
+#[cfg(feature = "wgpu")]
+impl WGSLType for u32 {
+    const NAME: &'static str = "u32";
+}
+
+#[cfg(feature = "wgpu")]
+impl<T: WGSLType> AddBuf<T, WGPU> for WGPU {
+    fn add(&self, lhs: &Buffer<T, WGPU>, rhs: &Buffer<T, WGPU>) -> Buffer<T, WGPU> {
+        let src = format!(
+            "@group(0)
+            @binding(0)
+            var<storage, read_write> lhs: array<{datatype}>;
+
+            @group(0)
+            @binding(1)
+            var<storage, read_write> rhs: array<{datatype}>;
+
+            @group(0)
+            @binding(2)
+            var<storage, read_write> out: array<{datatype}>;
+
+            @compute
+            @workgroup_size(1)
+            fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+                out[global_id.x] = lhs[global_id.x] + rhs[global_id.x];
+            }}
+            ",
+            datatype = T::NAME
+        );
+
+        let out = Cache::get::<T, ()>(self, lhs.len(), (lhs.node.idx, rhs.node.idx));
+        launch_shader(self, &src, [lhs.len() as u32, 1, 1], &[lhs, rhs, &out]);
+        out
+    }
+
+    fn relu(&self, lhs: &Buffer<T, WGPU>) -> Buffer<T, WGPU> {
+        let src = format!(
+            "@group(0)
+            @binding(0)
+            var<storage, read_write> lhs: array<{datatype}>;
+
+            @group(0)
+            @binding(1)
+            var<storage, read_write> out: array<{datatype}>;
+
+            @compute
+            @workgroup_size(1)
+            fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+                out[global_id.x] = max(lhs[global_id.x], {datatype}(0));
+            }}
+            ",
+            datatype = T::NAME
+        );
+
+        let out = Cache::get::<T, ()>(self, lhs.len(), lhs.node.idx);
+        launch_shader(self, &src, [lhs.len() as u32, 1, 1], &[lhs, &out]);
+        out
+    }
+}
+
 pub trait AddOp<'a, T, D: Device> {
     fn add(&self, rhs: &Buffer<'a, T, D>) -> Buffer<'a, T, D>;
     fn relu(&self) -> Buffer<'a, T, D>;
@@ -2,9 +2,17 @@ use custos::{cache::CacheReturn, opencl::construct_buffer, Buffer, Ident, OpenCL
 
 use super::{AddBuf, AddOp};
 
+/// OpenCL buffers can't be used by CPU operations directly, because `OpenCL` does not implement `MainMemory` (see CHANGELOG.md).
+fn host_buf<'a>(device: &'a CPU, buf: &Buffer<i32, OpenCL>) -> Buffer<'a, i32> {
+    Buffer::from((device, buf.read_to_vec()))
+}
+
 #[test]
 fn test_access_cached_after_unified_construct_buf() -> custos::Result<()> {
     let cl_dev = OpenCL::new(0)?;
+    if !cl_dev.unified_mem() {
+        return Ok(());
+    }
 
     let a = Buffer::from((&cl_dev, [1, 2, 3, 4, 5]));
     let b = Buffer::from((&cl_dev, [1, 2, 3, 4, 5]));
@@ -13,7 +21,7 @@ fn test_access_cached_after_unified_construct_buf() -> custos::Result<()> {
     let c = a.relu();
 
     let device = CPU::new();
-    let no_drop = device.add(&c, &b);
+    let no_drop = device.add(&host_buf(&device, &c), &host_buf(&device, &b));
 
     let cl_cpu_buf = unsafe { construct_buffer(&cl_dev, no_drop, (&c, &b)) }?;
 
@@ -35,9 +43,59 @@ fn test_access_cached_after_unified_construct_buf() -> custos::Result<()> {
     Ok(())
 }
 
+/// Same as above, but the CPU operation reads the unified memory of the OpenCL buffers without copying.
+#[test]
+fn test_access_cached_after_unified_construct_buf_zero_copy() -> custos::Result<()> {
+    let cl_dev = OpenCL::new(0)?;
+    if !cl_dev.unified_mem() {
+        return Ok(());
+    }
+
+    let a = Buffer::from((&cl_dev, [1, 2, 3, 4, 5]));
+    let b = Buffer::from((&cl_dev, [1, 2, 3, 4, 5]));
+
+    // some operation to generate an OpenCL graph
+    let c = a.relu();
+
+    let device = CPU::new();
+
+    let (c_host, b_host) = (c.try_as_slice()?, b.try_as_slice()?);
+
+    // the host pointers of unified buffers stay valid while `c` and `b` are alive
+    let (c_host, b_host) = unsafe {
+        (
+            Buffer::from_raw_host_device(&device, c_host.as_ptr() as *mut i32, c_host.len()),
+            Buffer::from_raw_host_device(&device, b_host.as_ptr() as *mut i32, b_host.len()),
+        )
+    };
+    let no_drop = device.add(&c_host, &b_host);
+    assert_eq!(no_drop.as_slice(), &[2, 4, 6, 8, 10]);
+
+    let cl_cpu_buf = unsafe { construct_buffer(&cl_dev, no_drop, (&c, &b)) }?;
+
+    let cached_cl_cpu_buf = cl_dev
+        .cache()
+        .nodes
+        .get(&Ident {
+            idx: cl_cpu_buf.node.ident_idx as usize,
+            len: cl_cpu_buf.len(),
+        })
+        .unwrap()
+        .clone();
+
+    assert_eq!(cl_cpu_buf.ptrs().0 as *mut u8, cached_cl_cpu_buf.host_ptr);
+    assert_eq!(cl_cpu_buf.ptrs().1, cached_cl_cpu_buf.ptr);
+    assert_eq!(cl_cpu_buf.try_as_slice()?, &[2, 4, 6, 8, 10]);
+
+    Ok(())
+}
+
 #[test]
 fn test_multiple_construct_buffer() -> custos::Result<()> {
     let cl_dev = OpenCL::new(0)?;
+    if !cl_dev.unified_mem() {
+        return Ok(());
+    }
 
     let device = CPU::new();
 
@@ -45,7 +103,7 @@ fn test_multiple_construct_buffer() -> custos::Result<()> {
     let b = Buffer::from((&cl_dev, [1, 2, 3, 4, 5]));
     let c = a.relu();
 
-    let no_drop = device.add(&c, &b);
+    let no_drop = device.add(&host_buf(&device, &c), &host_buf(&device, &b));
     let _cl_cpu_buf = unsafe { construct_buffer(&cl_dev, no_drop, (&c, &b)) }?;
 
     Ok(())
@@ -0,0 +1,138 @@
+use custos::{mmap::MapMode, Buffer, ClearBuf, CopySlice, DeviceError, MemMap, WriteBuf, CPU};
+
+fn tmp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("custos-{}-{name}", std::process::id()))
+}
+
+#[test]
+fn test_mmap_alloc() {
+    let device = MemMap::new(std::env::temp_dir());
+
+    let buf = Buffer::<i32, _>::new(&device, 10);
+    assert_eq!(buf.read(), [0; 10]);
+
+    let path = buf.ptr.mapping.as_ref().unwrap().path().to_path_buf();
+    assert!(path.exists());
+
+    drop(buf);
+    // files of allocated buffers are only scratch memory
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_mmap_write_read() {
+    let device = MemMap::new(std::env::temp_dir());
+
+    let mut buf = Buffer::<f64, _>::new(&device, 5);
+    device.write(&mut buf, &[1., 2., 3., 4., 5.]);
+    assert_eq!(buf.read(), [1., 2., 3., 4., 5.]);
+    assert_eq!(buf.read_to_vec(), vec![1., 2., 3., 4., 5.]);
+
+    let mut other = Buffer::<f64, _>::new(&device, 5);
+    device.write_buf(&mut other, &buf);
+    assert_eq!(other.as_slice(), &[1., 2., 3., 4., 5.]);
+}
+
+#[test]
+fn test_mmap_cpu_ops() {
+    let device = MemMap::new(std::env::temp_dir());
+    let cpu = CPU::new();
+
+    let buf = Buffer::from((&device, [1, 2, 3, 4, 5]));
+    let slice = cpu.copy_slice(&buf, 1..4);
+    assert_eq!(slice.as_slice(), &[2, 3, 4]);
+
+    let mut buf = buf;
+    cpu.clear(&mut buf);
+    assert_eq!(buf.read(), [0; 5]);
+}
+
+#[test]
+fn test_mmap_create_flush_open() -> custos::Result<()> {
+    let path = tmp_path("create_flush_open.mmap");
+    let device = MemMap::new(std::env::temp_dir());
+
+    let mut buf = device.create::<f64>(&path, 4)?;
+    buf.write(&[1.5, 2.5, -3., 4.]);
+    device.flush(&buf)?;
+    drop(buf);
+
+    let mut buf = device.open::<f64>(&path, MapMode::ReadWrite)?;
+    assert_eq!(buf.read(), [1.5, 2.5, -3., 4.]);
+    buf[0] = 10.;
+    device.flush(&buf)?;
+    drop(buf);
+
+    let mut buf = device.open::<f64>(&path, MapMode::ReadOnly)?;
+    assert_eq!(buf.read(), [10., 2.5, -3., 4.]);
+
+    // writes to a read-only map are private
+    buf[1] = 0.;
+    assert_eq!(
+        device.flush(&buf).unwrap_err().downcast_ref::<DeviceError>(),
+        Some(&DeviceError::MemMapFlush)
+    );
+    drop(buf);
+
+    let buf = device.open::<f64>(&path, MapMode::ReadOnly)?;
+    assert_eq!(buf.read(), [10., 2.5, -3., 4.]);
+    drop(buf);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn test_mmap_open_wrong_dtype() -> custos::Result<()> {
+    let path = tmp_path("wrong_dtype.mmap");
+    let device = MemMap::new(std::env::temp_dir());
+
+    drop(device.create::<f32>(&path, 3)?);
+
+    let err = device.open::<i32>(&path, MapMode::ReadOnly).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeviceError>(),
+        Some(&DeviceError::MemMapDatatype)
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn test_mmap_open_invalid_header() -> custos::Result<()> {
+    let path = tmp_path("invalid_header.mmap");
+    std::fs::write(&path, [0u8; 128])?;
+
+    let device = MemMap::new(std::env::temp_dir());
+    let err = device.open::<f32>(&path, MapMode::ReadOnly).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeviceError>(),
+        Some(&DeviceError::MemMapHeader)
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn test_mmap_open_overflowing_len() -> custos::Result<()> {
+    let path = tmp_path("overflowing_len.mmap");
+    let device = MemMap::new(std::env::temp_dir());
+
+    drop(device.create::<f32>(&path, 3)?);
+
+    // the header claims more elements than fit into the address space
+    let mut bytes = std::fs::read(&path)?;
+    bytes[16..24].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+    std::fs::write(&path, bytes)?;
+
+    let err = device.open::<f32>(&path, MapMode::ReadOnly).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeviceError>(),
+        Some(&DeviceError::MemMapHeader)
+    );
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
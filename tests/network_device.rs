@@ -1,15 +1,139 @@
-use custos::prelude::*;
-use cuwanto_server as cuw;
+use std::net::{SocketAddr, TcpListener};
+
+use custos::{
+    network::{protocol::Request, serve, Host},
+    Buffer, ClearBuf, CopySlice, Device, DeviceError, Network, WriteBuf, CPU,
+};
+
+fn start_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || serve::<CPU>(listener));
+    addr
+}
 
 #[test]
 fn test_network_device() -> custos::Result<()> {
-    let device = Network::new("127.0.0.1:11001", cuw::DeviceType::CPU)?;
-    //let device = CPU::new();
-    //let device = Stack::new().unwrap();
-    //let buf = device.cuw_client.borrow_mut().alloc_buf::<f64>(4)?;
+    let device = Network::new(start_server())?;
+
     let buf = Buffer::<f64, _>::from((&device, &[1., 2., 3., 4.]));
-    // ....
-    println!("buf: {:?}", buf.read());
-    //buf.clear();
+    assert_eq!(buf.read(), vec![1., 2., 3., 4.]);
+    Ok(())
+}
+
+#[test]
+fn test_network_alloc_write_clear() -> custos::Result<()> {
+    let device = Network::new(start_server())?;
+
+    let mut buf = Buffer::<i32, _>::new(&device, 5);
+    assert_eq!(buf.read(), vec![0; 5]);
+
+    device.write(&mut buf, &[1, -2, 3, -4, 5]);
+    assert_eq!(buf.read_to_vec(), vec![1, -2, 3, -4, 5]);
+
+    let mut other = Buffer::new(&device, 5);
+    device.write_buf(&mut other, &buf);
+
+    device.clear(&mut buf);
+    assert_eq!(buf.read(), vec![0; 5]);
+    assert_eq!(other.read(), vec![1, -2, 3, -4, 5]);
+    Ok(())
+}
+
+#[test]
+fn test_network_copy_slice() -> custos::Result<()> {
+    let device = Network::new(start_server())?;
+
+    let buf = Buffer::from((&device, [1u16, 2, 3, 4, 5, 6]));
+    let slice = device.copy_slice(&buf, 2..5);
+    assert_eq!(slice.read(), vec![3, 4, 5]);
+
+    let mut dest = Buffer::new(&device, 4);
+    device.copy_slice_all(&buf, &mut dest, [(0..2, 2..4), (4..6, 0..2)]);
+    assert_eq!(dest.read(), vec![5, 6, 1, 2]);
+
+    // source and destination are the same buffer on the server
+    let mut buf = buf;
+    let shallow = unsafe { buf.shallow() };
+    device.copy_slice_to(&shallow, 0..3, &mut buf, 3..6);
+    assert_eq!(buf.read(), vec![1, 2, 3, 1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn test_network_frees_buffers() -> custos::Result<()> {
+    let device = Network::new(start_server())?;
+
+    let id = {
+        let buf = Buffer::<u8, _>::new(&device, 10);
+        buf.ptr.id
+    };
+
+    let err = device.client.borrow_mut().read(id).unwrap_err();
+    assert_eq!(err.to_string(), format!("Unknown buffer id: {id}"));
+    Ok(())
+}
+
+#[test]
+fn test_network_server_errors() -> custos::Result<()> {
+    let device = Network::new(start_server())?;
+
+    let id = device.client.borrow_mut().alloc(8)?;
+    let mut client = device.client.borrow_mut();
+
+    assert!(client.write(id, &[1, 2, 3]).is_err());
+    assert!(client.copy_slice(id, 4, id, 0, 8).is_err());
+    assert!(client.alloc(0).is_err());
+    assert!(client.alloc(usize::MAX).is_err());
+
+    // the connection is still usable after an error
+    client.write(id, &[1; 8])?;
+    assert_eq!(client.read(id)?, [1; 8]);
+    Ok(())
+}
+
+#[test]
+fn test_network_host_limit() -> custos::Result<()> {
+    let device = CPU::new();
+    let mut host = Host::with_limit(&device, 16);
+
+    let mut payload = Vec::new();
+    host.execute(Request::Alloc { bytes: 10 }, &mut payload)?;
+    let id = u64::from_le_bytes(payload[..8].try_into().unwrap());
+
+    let err = host
+        .execute(Request::Alloc { bytes: 10 }, &mut Vec::new())
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Cannot allocate 10 bytes, 10 of 16 bytes are already allocated."
+    );
+
+    // freed bytes can be allocated again
+    host.execute(Request::Free { id }, &mut Vec::new())?;
+    host.execute(Request::Alloc { bytes: 16 }, &mut Vec::new())?;
+    Ok(())
+}
+
+#[test]
+fn test_network_multiple_clients() -> custos::Result<()> {
+    let addr = start_server();
+    let first = Network::new(addr)?;
+    let second = Network::new(addr)?;
+
+    let lhs = Buffer::from((&first, [1, 2, 3]));
+    let rhs = Buffer::from((&second, [4, 5, 6]));
+
+    assert_eq!(lhs.read(), vec![1, 2, 3]);
+    assert_eq!(rhs.read(), vec![4, 5, 6]);
     Ok(())
 }
+
+#[test]
+fn test_network_device_new_requires_address() {
+    let err = <Network as Device>::new().unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<DeviceError>(),
+        Some(&DeviceError::MissingAddress)
+    );
+}
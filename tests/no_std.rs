@@ -0,0 +1,41 @@
+use std::{path::Path, process::Command};
+
+/// Builds the crate in `tests/no_std_check`, which uses custos with the features "no-std", "cpu", "static-api", "opt-cache" and "arena", for a no_std target.
+/// The target can be changed with the `CUSTOS_NO_STD_TARGET` environment variable (default: thumbv7em-none-eabihf).
+/// The test is skipped if the standard library of the target is not installed (e.g. `rustup target add thumbv7em-none-eabihf`).
+#[test]
+fn test_no_std_build() {
+    let target = std::env::var("CUSTOS_NO_STD_TARGET")
+        .unwrap_or_else(|_| "thumbv7em-none-eabihf".to_string());
+
+    let sysroot = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .expect("Could not run rustc.");
+    let sysroot = String::from_utf8(sysroot.stdout).unwrap();
+
+    if !Path::new(sysroot.trim())
+        .join("lib/rustlib")
+        .join(&target)
+        .exists()
+    {
+        println!("skipping no_std build: target {target} is not installed");
+        return;
+    }
+
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+
+    let output = Command::new(cargo)
+        .current_dir(manifest_dir.join("tests/no_std_check"))
+        .args(["build", "--target", &target, "--target-dir"])
+        .arg(manifest_dir.join("target/no-std-check"))
+        .output()
+        .expect("Could not run cargo.");
+
+    assert!(
+        output.status.success(),
+        "no_std build failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,63 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use custos::{
+    buf, static_api::static_cpu, Arena, Buffer, CacheReturn, ClearBuf, Device, DeviceError,
+    ErrorKind, GraphOpt, Read, Stack, WriteBuf, CPU,
+};
+
+// The allocations of the CPU use the `#[global_allocator]` of the final binary.
+
+pub fn cpu_ops() -> custos::Result<Vec<f32>> {
+    let device = CPU::new();
+
+    let mut buf = Buffer::from((&device, [1f32, 2., 3., 4.]));
+    device.write(&mut buf, &[4., 3., 2., 1.]);
+    device.clear(&mut buf);
+
+    let cached = device.retrieve::<f32, ()>(4, &buf);
+    device.optimize()?;
+
+    if device.cache().nodes.is_empty() {
+        return Err(DeviceError::GraphOptimization.into());
+    }
+
+    Ok(device.read_to_vec(&cached))
+}
+
+pub fn stack_ops() -> [i32; 3] {
+    let buf = Buffer::<_, Stack, custos::Dim1<3>>::from((&Stack, [1, 2, 3]));
+    Stack.read(&buf)
+}
+
+/// # Safety
+/// The static API must only be used by a single thread.
+pub unsafe fn static_ops() -> Vec<f32> {
+    let cpu = static_cpu();
+
+    let buf = buf![1f32, 2., 3.];
+    let filled = buf![2f32; 10];
+
+    assert!(core::ptr::eq(buf.device(), cpu));
+    filled.read_to_vec()
+}
+
+pub fn arena_ops(memory: &mut [u8]) -> custos::Result<[f32; 3]> {
+    let device = Arena::new(memory);
+
+    let mut buf = device.try_buf::<f32>(3)?;
+    device.write(&mut buf, &[1., 2., 3.]);
+
+    let sum = device.scope(|scratch| -> custos::Result<f32> {
+        let tmp = scratch.try_buf::<f32>(3)?;
+        Ok(tmp.iter().sum())
+    })?;
+
+    Ok([sum, buf[1], buf[2]])
+}
+
+pub fn error_kind(err: &custos::Error) -> Option<&DeviceError> {
+    err.kind::<DeviceError>()
+}
@@ -37,6 +37,36 @@ fn test_unified_mem_bool() -> Result<(), Error> {
     Ok(())
 }
 
+#[cfg(feature = "opencl")]
+#[test]
+fn test_unified_mem_runtime_switch() -> Result<(), Error> {
+    use custos::opencl::CLRead;
+
+    let device = OpenCL::new(0)?;
+
+    device.set_unified_mem(false)?;
+    let explicit = Buffer::from((&device, [1, 2, 3]));
+    assert!(explicit.try_as_slice().is_err());
+    assert!(matches!(explicit.read(), CLRead::Owned(_)));
+    assert_eq!(explicit.read(), vec![1, 2, 3]);
+
+    // the mapped memory of a device with dedicated memory would not show the writes of kernels
+    if !device.device().unified_mem()? {
+        assert!(device.set_unified_mem(true).is_err());
+        assert!(!device.unified_mem());
+        return Ok(());
+    }
+
+    device.set_unified_mem(true)?;
+    let mut mapped = Buffer::from((&device, [1, 2, 3]));
+    mapped.try_as_mut_slice()?[1] = 5;
+    assert!(matches!(mapped.read(), CLRead::Borrowed(&[1, 5, 3])));
+
+    // already allocated buffers keep their kind of memory
+    assert_eq!(explicit.read_to_vec(), vec![1, 2, 3]);
+    Ok(())
+}
+
 #[cfg(feature = "opencl")]
 #[test]
 fn test_unified_mem() -> Result<(), Error> {
@@ -183,7 +213,6 @@ fn test_unified_mem_iterate() -> custos::Result<()> {
     Ok(())
 }*/
 
-#[cfg(unified_cl)]
 #[cfg(not(feature = "realloc"))]
 #[test]
 fn test_cpu_to_unified() -> custos::Result<()> {
@@ -192,21 +221,27 @@ fn test_cpu_to_unified() -> custos::Result<()> {
     buf.copy_from_slice(&[1, 2, 3, 4, 5, 6]);
 
     let cl_dev = OpenCL::new(0)?;
+    if !cl_dev.unified_mem() {
+        return Ok(());
+    }
+
     let cl_cpu_buf = unsafe { custos::opencl::construct_buffer(&cl_dev, buf, ())? };
 
-    assert_eq!(cl_cpu_buf.as_slice(), &[1, 2, 3, 4, 5, 6]);
+    assert_eq!(cl_cpu_buf.try_as_slice()?, &[1, 2, 3, 4, 5, 6]);
     assert_eq!(cl_cpu_buf.read(), &[1, 2, 3, 4, 5, 6]);
 
     Ok(())
 }
 
-#[cfg(unified_cl)]
 #[cfg(not(feature = "realloc"))]
 #[test]
 fn test_cpu_to_unified_leak() -> custos::Result<()> {
     use std::rc::Rc;
 
     let cl_dev = OpenCL::new(0)?;
+    if !cl_dev.unified_mem() {
+        return Ok(());
+    }
 
     set_count(0);
 
@@ -225,19 +260,22 @@ fn test_cpu_to_unified_leak() -> custos::Result<()> {
             }
             cl_cpu_buf
         };
-        assert_eq!(cl_cpu_buf.as_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(cl_cpu_buf.try_as_slice()?, &[1, 2, 3, 4, 5, 6]);
         assert_eq!(cl_cpu_buf.read(), &[1, 2, 3, 4, 5, 6]);
     }
     Ok(())
 }
 
-#[cfg(unified_cl)]
 #[cfg(not(feature = "realloc"))]
 #[test]
 fn test_cpu_to_unified_perf() -> custos::Result<()> {
     use std::time::Instant;
 
     let cl_dev = OpenCL::new(0)?;
+    if !cl_dev.unified_mem() {
+        return Ok(());
+    }
+
     let device = CPU::new();
 
     let mut dur = 0.;
@@ -251,7 +289,7 @@ fn test_cpu_to_unified_perf() -> custos::Result<()> {
         let cl_cpu_buf = unsafe { custos::opencl::construct_buffer(&cl_dev, buf, ())? };
         dur += start.elapsed().as_secs_f64();
 
-        assert_eq!(cl_cpu_buf.as_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(cl_cpu_buf.try_as_slice()?, &[1, 2, 3, 4, 5, 6]);
         assert_eq!(cl_cpu_buf.read(), &[1, 2, 3, 4, 5, 6]);
     }
 
@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+use custos::{
+    sim_gpu::SimStats, Alloc, Buffer, ClearBuf, CloneBuf, CopySlice, Device, Read, SimGPU,
+    WriteBuf,
+};
+
+// generic code, which has to transfer the data explicitly
+fn sum<'a, D>(device: &'a D, buf: &Buffer<'a, i32, D>) -> i32
+where
+    D: Read<i32, D> + Alloc<'a, i32>,
+{
+    device.read_to_vec(buf).iter().sum()
+}
+
+#[test]
+fn test_sim_gpu_transfers() {
+    let device = SimGPU::new();
+
+    let mut buf = Buffer::<i32, _>::new(&device, 5);
+    assert_eq!(device.stats(), SimStats::default());
+
+    device.write(&mut buf, &[1, 2, 3, 4, 5]);
+    assert_eq!(buf.read(), vec![1, 2, 3, 4, 5]);
+    assert_eq!(sum(&device, &buf), 15);
+
+    assert_eq!(
+        device.stats(),
+        SimStats {
+            reads: 2,
+            writes: 1,
+            bytes_read: 40,
+            bytes_written: 20,
+            launches: 0
+        }
+    );
+
+    device.reset_stats();
+    assert_eq!(device.stats(), SimStats::default());
+}
+
+#[test]
+fn test_sim_gpu_device_ops_do_not_transfer() {
+    let device = SimGPU::new();
+
+    let buf = Buffer::from((&device, [1f32, 2., 3., 4.]));
+    device.reset_stats();
+
+    let slice = device.copy_slice(&buf, 1..3);
+    let mut cloned = device.clone_buf(&buf);
+
+    let mut other = Buffer::new(&device, 4);
+    device.write_buf(&mut other, &buf);
+    device.clear(&mut cloned);
+
+    assert_eq!(device.stats(), SimStats::default());
+
+    assert_eq!(slice.read(), vec![2., 3.]);
+    assert_eq!(cloned.read(), vec![0.; 4]);
+    assert_eq!(other.read(), vec![1., 2., 3., 4.]);
+    assert_eq!(buf.read(), vec![1., 2., 3., 4.]);
+    assert_eq!(device.stats().reads, 4);
+}
+
+#[test]
+fn test_sim_gpu_copy_slice_all() {
+    let device = SimGPU::new();
+
+    let source = Buffer::from((&device, [1, 2, 3, 4, 5, 6]));
+    let mut dest = Buffer::new(&device, 4);
+
+    device.copy_slice_all(&source, &mut dest, [(0..2, 2..4), (4..6, 0..2)]);
+    assert_eq!(dest.read(), vec![5, 6, 1, 2]);
+}
+
+#[test]
+fn test_sim_gpu_launch() {
+    let device = SimGPU::new();
+
+    let lhs = Buffer::from((&device, [1, 2, 3]));
+    let rhs = Buffer::from((&device, [3, 2, 1]));
+    let bias = Buffer::from((&device, [10, 10, 10, 10]));
+
+    let mut out = Buffer::new(&device, 3);
+    device.launch(&mut out, &[&lhs, &rhs, &bias], |x| x[0] * x[1] + x[2]);
+    assert_eq!(out.read(), vec![13, 14, 13]);
+    assert_eq!(device.stats().launches, 1);
+}
+
+#[test]
+#[should_panic]
+fn test_sim_gpu_launch_short_input() {
+    let device = SimGPU::new();
+
+    let lhs = Buffer::from((&device, [1, 2]));
+    let mut out = Buffer::new(&device, 3);
+    device.launch(&mut out, &[&lhs], |x| x[0]);
+}
+
+#[test]
+fn test_sim_gpu_frees_allocations() {
+    let device = SimGPU::new();
+
+    let buf = Buffer::<u8, _>::new(&device, 10);
+    let shallow = unsafe { buf.shallow() };
+    assert_eq!(device.arena.borrow().allocations(), 1);
+
+    drop(shallow);
+    assert_eq!(device.arena.borrow().allocations(), 1);
+
+    drop(buf);
+    assert_eq!(device.arena.borrow().allocations(), 0);
+}
+
+#[test]
+fn test_sim_gpu_latency() {
+    let device = SimGPU::new().with_latency(Duration::from_millis(5));
+
+    let start = Instant::now();
+    let buf = Buffer::from((&device, [1, 2, 3]));
+    buf.read();
+    assert!(start.elapsed() >= Duration::from_millis(10));
+}
+
+#[test]
+fn test_sim_gpu_device_new() {
+    let device = <SimGPU as Device>::new().unwrap();
+    assert_eq!(device.latency, None);
+}
+
+#[cfg(feature = "static-api")]
+#[test]
+fn test_sim_gpu_to_cpu() {
+    let device = SimGPU::new();
+    let buf = Buffer::from((&device, [1, 2, 3]));
+
+    let cpu_buf = buf.to_cpu();
+    assert_eq!(cpu_buf.as_slice(), &[1, 2, 3]);
+    assert_eq!(device.stats().reads, 1);
+}
@@ -84,3 +84,29 @@ fn test_buf_copy_slice_all_opencl_out_of_bounds() {
 
     device.copy_slice_all(&source, &mut dest, [(2..5, 8..11), (1..3, 3..5)]);
 }
+
+#[cfg(feature = "stack")]
+#[test]
+fn test_buf_slice_stack() {
+    use custos::Stack;
+
+    let source = Buffer::<_, Stack, Dim1<5>>::from((&Stack, [1., 2., 6., 2., 4.]));
+    let actual: Buffer<_, Stack, Dim1<2>> = Stack.copy_slice(&source, 1..3);
+    assert_eq!(actual.read(), [2., 6.]);
+}
+
+#[cfg(feature = "stack")]
+#[test]
+fn test_buf_copy_slice_all_stack() {
+    use custos::Stack;
+
+    let source = Buffer::<_, Stack, Dim1<5>>::from((&Stack, [1., 2., 6., 2., 4.]));
+    let mut dest = Buffer::<_, Stack, Dim1<10>>::new(&Stack, 10);
+
+    Stack.copy_slice_all(&source, &mut dest, [(2..5, 7..10), (1..3, 3..5)]);
+
+    assert_eq!(
+        dest.read(),
+        [0.0, 0.0, 0.0, 2.0, 6.0, 0.0, 0.0, 6.0, 2.0, 4.0]
+    );
+}
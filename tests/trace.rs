@@ -0,0 +1,161 @@
+use custos::{
+    trace::TraceOp, Buffer, CacheBuf, ClearBuf, CloneBuf, CopySlice, Traced, WriteBuf, CPU,
+};
+
+fn ops(device: &Traced<CPU>) -> Vec<TraceOp> {
+    device.events().iter().map(|event| event.op).collect()
+}
+
+#[test]
+fn test_trace_forwards_ops() {
+    let device = Traced::new(CPU::new());
+
+    let mut buf = Buffer::<f32, _>::new(&device, 4);
+    device.write(&mut buf, &[1., 2., 3., 4.]);
+    assert_eq!(buf.read(), [1., 2., 3., 4.]);
+
+    let slice = device.copy_slice(&buf, 1..3);
+    assert_eq!(slice.read(), [2., 3.]);
+
+    let cloned = device.clone_buf(&buf);
+    device.clear(&mut buf);
+    assert_eq!(buf.read(), [0.; 4]);
+    assert_eq!(cloned.read(), [1., 2., 3., 4.]);
+
+    let cached: Buffer<f32, _> = device.cached(8);
+    assert_eq!(cached.len(), 8);
+
+    assert_eq!(
+        ops(&device),
+        [
+            TraceOp::Alloc,
+            TraceOp::Write,
+            TraceOp::Read,
+            TraceOp::Alloc,
+            TraceOp::CopySlice,
+            TraceOp::Read,
+            TraceOp::CloneBuf,
+            TraceOp::Clear,
+            TraceOp::Read,
+            TraceOp::Read,
+            TraceOp::CacheBuf,
+        ]
+    );
+
+    let events = device.events();
+    assert_eq!(events[0].bytes, 16);
+    assert_eq!(events[4].bytes, 8);
+    assert_eq!(events[10].bytes, 32);
+    assert!(events.windows(2).all(|w| w[0].start <= w[1].start));
+}
+
+#[test]
+fn test_trace_cpu_op_on_traced_buf() {
+    let device = Traced::new(CPU::new());
+    let buf = Buffer::from((&device, [1, 2, 3]));
+
+    // the inner device works on main memory buffers of the traced device, these calls are not recorded
+    let cpu = CPU::new();
+    let mut out = Buffer::new(&cpu, 3);
+    cpu.copy_slice_to(&buf, .., &mut out, ..);
+    assert_eq!(out.as_slice(), &[1, 2, 3]);
+
+    assert_eq!(ops(&device), [TraceOp::Alloc]);
+}
+
+#[test]
+fn test_trace_labels() {
+    let device = Traced::new(CPU::new());
+
+    let mut buf = Buffer::<i32, _>::new(&device, 10);
+    {
+        let _outer = device.label("forward");
+        device.clear(&mut buf);
+        {
+            let _inner = device.label("relu");
+            device.write(&mut buf, &[1; 10]);
+        }
+    }
+
+    let events = device.events();
+    let labels = events
+        .iter()
+        .map(|event| (event.op, event.label.as_deref()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        labels,
+        [
+            (TraceOp::Alloc, None),
+            (TraceOp::Clear, Some("forward")),
+            (TraceOp::Write, Some("relu")),
+            (TraceOp::Label, Some("relu")),
+            (TraceOp::Label, Some("forward")),
+        ]
+    );
+
+    // the label spans the labeled calls
+    let forward = &events[4];
+    assert!(forward.start <= events[1].start);
+    assert!(forward.duration >= events[2].duration);
+}
+
+#[test]
+fn test_trace_chrome_export() {
+    let device = Traced::new(CPU::new());
+
+    let mut buf = Buffer::<u8, _>::new(&device, 3);
+    {
+        let _label = device.label("say \"hi\"");
+        device.write(&mut buf, &[1, 2, 3]);
+    }
+
+    let json = device.to_chrome_trace();
+    assert!(
+        json.starts_with("{\"traceEvents\":[{\"name\":\"alloc\",\"cat\":\"alloc\",\"ph\":\"X\"")
+    );
+    assert!(json.contains("\"args\":{\"bytes\":3,\"label\":\"say \\\"hi\\\"\"}"));
+    assert!(json.contains("{\"name\":\"say \\\"hi\\\"\",\"cat\":\"label\""));
+    assert!(json.ends_with("],\"displayTimeUnit\":\"ns\"}"));
+    assert_eq!(json.matches("\"ph\":\"X\"").count(), 3);
+
+    let path = std::env::temp_dir().join(format!("custos-trace-{}.json", std::process::id()));
+    device.write_chrome_trace(&path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), json);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_trace_summary() {
+    let device = Traced::new(CPU::new());
+
+    let mut buf = Buffer::<f64, _>::new(&device, 4);
+    for _ in 0..3 {
+        let _label = device.label("step");
+        device.clear(&mut buf);
+    }
+
+    let summary = device.summary();
+    let rows = summary
+        .iter()
+        .map(|row| (row.op, row.label.as_deref(), row.calls, row.bytes))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        rows,
+        [
+            (TraceOp::Alloc, None, 1, 32),
+            (TraceOp::Clear, Some("step"), 3, 96),
+            (TraceOp::Label, Some("step"), 3, 0),
+        ]
+    );
+
+    let table = device.summary_table();
+    assert_eq!(table.lines().count(), 5);
+    assert!(table
+        .lines()
+        .any(|line| line.starts_with("clear") && line.contains("step")));
+
+    device.clear_events();
+    assert!(device.events().is_empty());
+}
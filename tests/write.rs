@@ -31,3 +31,13 @@ fn test_write_cuda() -> custos::Result<()> {
     assert_eq!(device.read(&buf), vec![1., 2., 3., 4., 5.]);
     Ok(())
 }
+
+#[cfg(feature = "stack")]
+#[test]
+fn test_write_stack() {
+    use custos::{Dim1, Stack};
+
+    let mut buf = Buffer::<_, Stack, Dim1<5>>::new(&Stack, 5);
+    Stack.write(&mut buf, &[1., 2., 3., 4., 5.]);
+    assert_eq!(buf.read(), [1., 2., 3., 4., 5.])
+}